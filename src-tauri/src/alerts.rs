@@ -0,0 +1,136 @@
+use std::thread;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::conditions::Gate;
+use crate::window::TargetWindow;
+use crate::{diagnostics, win_input, AutoInputSettings, CONDITION_POLL};
+
+// ---------------------------------------------------------------------------
+// Alerts
+//
+// `trigger-fired` tells the UI each time a start or stop condition or a
+// process watch fires, with the warning sound when `trigger_sound` is set,
+// so the user can tell detection works while looking elsewhere.
+//
+// `action_type: "monitor"` sends no input at all. The run watches its start
+// condition (a color, image or text on screen) and/or `target_window`
+// opening, and raises `monitor-alert`, with a sound if asked, each time
+// they come true. It re-arms once they stop holding, so a queue popping
+// twice alerts twice. Schedules, process watches and the other triggers
+// start it like any other run.
+// ---------------------------------------------------------------------------
+
+/// Payload of `trigger-fired`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TriggerFired {
+    /// "color", "image" or "text" for a start condition, "color-change" or
+    /// "stop-text" for a stop condition, "process" for a process launch
+    trigger: &'static str,
+    label: Option<String>,
+}
+
+pub fn trigger_fired(
+    app: &AppHandle,
+    settings: &AutoInputSettings,
+    trigger: &'static str,
+    label: Option<String>,
+) {
+    diagnostics::log(&format!("trigger fired: {trigger}"));
+    if settings.trigger_sound {
+        win_input::alert_sound();
+    }
+    let _ = app.emit("trigger-fired", TriggerFired { trigger, label });
+}
+
+/// Payload of `monitor-alert`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MonitorAlert {
+    /// Unix time in milliseconds
+    at: i64,
+    /// Alerts so far this run, this one included
+    count: u64,
+    label: Option<String>,
+}
+
+pub struct AlertWatch {
+    window: Option<TargetWindow>,
+    sound: bool,
+}
+
+impl AlertWatch {
+    /// The watch for `action_type: "monitor"`; None for other action types.
+    /// `has_condition` is whether a start condition is set.
+    pub fn from_settings(
+        settings: &AutoInputSettings,
+        has_condition: bool,
+        window: Option<&TargetWindow>,
+    ) -> Result<Option<Self>, String> {
+        if settings.action_type != "monitor" {
+            return Ok(None);
+        }
+        if !has_condition && window.is_none() {
+            return Err("Monitor-only runs need a start condition or a target window".into());
+        }
+        Ok(Some(Self {
+            window: window.cloned(),
+            sound: settings.monitor_sound,
+        }))
+    }
+
+    /// An immediate gate doesn't count, so a window-only watch waits on the
+    /// window alone.
+    fn met(&self, gate: &mut Gate) -> bool {
+        let condition = matches!(gate, Gate::Immediately) || gate.met();
+        condition && self.window.as_ref().is_none_or(|w| w.find().is_some())
+    }
+
+    /// Alerts every time the triggers come true until `should_stop`, or
+    /// until `max_alerts` when that's nonzero.
+    pub fn run(
+        &self,
+        app: &AppHandle,
+        gate: &mut Gate,
+        label: Option<String>,
+        max_alerts: u64,
+        should_stop: impl Fn() -> bool,
+    ) {
+        let mut count = 0;
+        loop {
+            // Wait for the triggers to hold, then for them to clear again
+            for want in [true, false] {
+                while self.met(gate) != want {
+                    if should_stop() {
+                        return;
+                    }
+                    thread::sleep(CONDITION_POLL);
+                }
+                if want {
+                    count += 1;
+                    self.alert(app, count, label.clone());
+                    if max_alerts > 0 && count >= max_alerts {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    fn alert(&self, app: &AppHandle, count: u64, label: Option<String>) {
+        diagnostics::log(&format!("monitor alert {count}"));
+        if self.sound {
+            win_input::alert_sound();
+        }
+        let _ = app.emit(
+            "monitor-alert",
+            MonitorAlert {
+                at: chrono::Local::now().timestamp_millis(),
+                count,
+                label,
+            },
+        );
+    }
+}
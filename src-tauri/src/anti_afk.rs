@@ -0,0 +1,124 @@
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::win_input::{self, VIRTUAL_KEY};
+use crate::AutoInputSettings;
+
+// ---------------------------------------------------------------------------
+// Anti-AFK
+//
+// Keeps a session from being flagged idle with the smallest input that
+// counts: a few pixels of mouse movement there and back, and/or a tap of
+// one of a handful of keys, every interval (plus jitter) rather than the
+// click loop's steady stream.
+// ---------------------------------------------------------------------------
+
+/// How long the cursor sits off its spot before moving back; some games
+/// only register movement that lasts past a frame
+const WIGGLE_HOLD: Duration = Duration::from_millis(40);
+
+pub struct AntiAfk {
+    wiggle_px: i32,
+    keys: Vec<VIRTUAL_KEY>,
+}
+
+impl AntiAfk {
+    /// The action for `action_type: "anti-afk"`; None for other action types.
+    pub fn from_settings(settings: &AutoInputSettings) -> Result<Option<Self>, String> {
+        if settings.action_type != "anti-afk" {
+            return Ok(None);
+        }
+        let keys: Vec<_> = settings
+            .anti_afk_keys
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(win_input::resolve_vk)
+            .collect();
+        if settings.anti_afk_wiggle_px == 0 && keys.is_empty() {
+            return Err("Anti-AFK needs a wiggle distance or at least one key".into());
+        }
+        Ok(Some(Self {
+            wiggle_px: settings.anti_afk_wiggle_px.min(100) as i32,
+            keys,
+        }))
+    }
+
+    /// One round: a wiggle in a random direction, then a random key.
+    pub fn nudge(&self, no_coalesce: bool) {
+        if self.wiggle_px > 0 {
+            let (dx, dy) = match fastrand::u8(..4) {
+                0 => (self.wiggle_px, 0),
+                1 => (-self.wiggle_px, 0),
+                2 => (0, self.wiggle_px),
+                _ => (0, -self.wiggle_px),
+            };
+            win_input::move_mouse_rel(dx, dy, no_coalesce);
+            thread::sleep(WIGGLE_HOLD);
+            win_input::move_mouse_rel(-dx, -dy, no_coalesce);
+        }
+        if !self.keys.is_empty() {
+            win_input::key_press(self.keys[fastrand::usize(..self.keys.len())]);
+        }
+    }
+}
+
+/// A ready-made configuration the UI can offer as a starting point.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Preset {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub settings: AutoInputSettings,
+}
+
+fn anti_afk_preset(minutes: u64, seconds: u64, jitter_secs: u64) -> AutoInputSettings {
+    AutoInputSettings {
+        action_type: "anti-afk".into(),
+        minutes,
+        seconds,
+        milliseconds: 0,
+        interval_jitter_ms: jitter_secs * 1000,
+        // Anti-AFK is meant to run unattended for hours
+        max_runtime_minutes: 0,
+        ..Default::default()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub fn list_presets() -> Vec<Preset> {
+    vec![
+        Preset {
+            id: "anti-afk-wiggle",
+            name: "Anti-AFK: mouse wiggle",
+            description: "Nudges the mouse a few pixels and back about once a minute",
+            settings: anti_afk_preset(1, 0, 15),
+        },
+        Preset {
+            id: "anti-afk-keys",
+            name: "Anti-AFK: movement keys",
+            description: "Taps W, A, S or D every four minutes or so, without touching the mouse",
+            settings: AutoInputSettings {
+                anti_afk_wiggle_px: 0,
+                anti_afk_keys: "w,a,s,d".into(),
+                ..anti_afk_preset(4, 0, 30)
+            },
+        },
+        Preset {
+            id: "anti-afk-both",
+            name: "Anti-AFK: wiggle and jump",
+            description: "Wiggles the mouse and presses Space every two minutes or so",
+            settings: AutoInputSettings {
+                anti_afk_keys: "space".into(),
+                ..anti_afk_preset(2, 0, 20)
+            },
+        },
+    ]
+}
@@ -0,0 +1,393 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::win_input;
+
+// ---------------------------------------------------------------------------
+// Screen capture
+//
+// Small screen regions are copied out of the composited desktop with GDI,
+// which sees every window (games included, unless they run exclusive
+// fullscreen) at the cost of a few hundred microseconds per grab. A `Grabber`
+// keeps its device contexts around for repeated grabs of the same size.
+// ---------------------------------------------------------------------------
+
+const SAMPLE_SIZE: i32 = 64;
+const DEFAULT_SAMPLE_DURATION: Duration = Duration::from_secs(1);
+const MAX_SAMPLE_DURATION: Duration = Duration::from_secs(5);
+/// Below this the content is treated as static and the refresh rate is used
+const MIN_CONTENT_FPS: f64 = 5.0;
+
+/// Repeatedly copies a fixed-size screen rectangle.
+pub struct Grabber {
+    inner: imp::Grabber,
+    width: i32,
+    height: i32,
+}
+
+impl Grabber {
+    pub fn new(width: i32, height: i32) -> Option<Self> {
+        Some(Self {
+            inner: imp::Grabber::new(width, height)?,
+            width,
+            height,
+        })
+    }
+
+    /// BGRA pixels, top row first, of the rectangle whose top-left is
+    /// `(x, y)` in screen pixels.
+    pub fn grab(&mut self, x: i32, y: i32) -> Option<&[u8]> {
+        self.inner.grab(x, y, self.width, self.height)
+    }
+}
+
+/// Largest side `capture_region` accepts
+const MAX_CAPTURE_SIDE: i32 = 8192;
+
+/// Encodes a top-down BGRA buffer as an opaque RGB PNG.
+pub fn encode_png(width: i32, height: i32, bgra: &[u8]) -> Result<Vec<u8>, String> {
+    let rgb: Vec<u8> = bgra
+        .chunks_exact(4)
+        .flat_map(|px| [px[2], px[1], px[0]])
+        .collect();
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer.write_image_data(&rgb).map_err(|e| e.to_string())?;
+    writer.finish().map_err(|e| e.to_string())?;
+    Ok(out)
+}
+
+/// `#RRGGBB` for a BGRA pixel.
+fn hex_color(pixel: &[u8]) -> String {
+    format!("#{:02X}{:02X}{:02X}", pixel[2], pixel[1], pixel[0])
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PixelColor {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+/// Color of one screen pixel, or None off-screen.
+pub fn pixel_color(x: i32, y: i32) -> Option<PixelColor> {
+    imp::pixel(x, y)
+}
+
+// ---------------------------------------------------------------------------
+// Frame rate sampling
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameRateSample {
+    /// Desktop composition rate, i.e. the monitor refresh rate
+    pub refresh_hz: Option<f64>,
+    /// How often the pixels around the sampled point changed
+    pub content_fps: f64,
+    /// Shortest interval that gives every input a frame of its own
+    pub suggested_interval_ms: u64,
+}
+
+fn sample(x: i32, y: i32, duration: Duration) -> Result<FrameRateSample, String> {
+    let mut grabber =
+        Grabber::new(SAMPLE_SIZE, SAMPLE_SIZE).ok_or("Couldn't capture the screen")?;
+    let (left, top) = (x - SAMPLE_SIZE / 2, y - SAMPLE_SIZE / 2);
+
+    let mut last = None;
+    let mut changes = 0u32;
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        let pixels = grabber
+            .grab(left, top)
+            .ok_or("Couldn't capture the screen")?;
+        let mut hasher = DefaultHasher::new();
+        pixels.hash(&mut hasher);
+        let hash = hasher.finish();
+        if last.is_some_and(|h| h != hash) {
+            changes += 1;
+        }
+        last = Some(hash);
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    let content_fps = changes as f64 / start.elapsed().as_secs_f64();
+    let refresh_hz = imp::refresh_rate();
+    let fps = if content_fps >= MIN_CONTENT_FPS {
+        content_fps
+    } else {
+        refresh_hz.ok_or("The content isn't changing and the refresh rate is unknown")?
+    };
+    Ok(FrameRateSample {
+        refresh_hz,
+        content_fps,
+        suggested_interval_ms: (1000.0 / fps).ceil() as u64,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Cursor tracking
+//
+// Feeds the UI's live coordinate readout. The cursor is polled at ~30Hz and a
+// `cursor-position` event goes out whenever it moves or the pixel under it
+// changes.
+// ---------------------------------------------------------------------------
+
+const TRACK_INTERVAL: Duration = Duration::from_millis(33);
+
+static TRACKING: Mutex<Option<Arc<AtomicBool>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CursorSample {
+    pub x: i32,
+    pub y: i32,
+    /// `#RRGGBB` under the cursor, if the screen could be read
+    pub color: Option<String>,
+}
+
+fn track_cursor(app: AppHandle, stop: Arc<AtomicBool>) {
+    let mut grabber = Grabber::new(1, 1);
+    let mut last = None;
+    while !stop.load(Ordering::Acquire) {
+        let (x, y) = win_input::cursor_pos();
+        let color = grabber.as_mut().and_then(|g| g.grab(x, y)).map(hex_color);
+        let sample = CursorSample { x, y, color };
+        if last.as_ref() != Some(&sample) {
+            let _ = app.emit("cursor-position", &sample);
+            last = Some(sample);
+        }
+        thread::sleep(TRACK_INTERVAL);
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::Graphics::Dwm::{DwmGetCompositionTimingInfo, DWM_TIMING_INFO};
+    use windows::Win32::Graphics::Gdi::{
+        BitBlt, CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject, GetDC,
+        GetDIBits, GetPixel, ReleaseDC, SelectObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB,
+        CAPTUREBLT, CLR_INVALID, DIB_RGB_COLORS, HBITMAP, HDC, HGDIOBJ, SRCCOPY,
+    };
+
+    use super::PixelColor;
+
+    pub struct Grabber {
+        screen: HDC,
+        memory: HDC,
+        bitmap: HBITMAP,
+        previous: HGDIOBJ,
+        pixels: Vec<u8>,
+    }
+
+    impl Grabber {
+        pub fn new(width: i32, height: i32) -> Option<Self> {
+            if width <= 0 || height <= 0 {
+                return None;
+            }
+            unsafe {
+                let screen = GetDC(None);
+                if screen.is_invalid() {
+                    return None;
+                }
+                let memory = CreateCompatibleDC(Some(screen));
+                let bitmap = CreateCompatibleBitmap(screen, width, height);
+                let previous = SelectObject(memory, bitmap.into());
+                Some(Self {
+                    screen,
+                    memory,
+                    bitmap,
+                    previous,
+                    pixels: vec![0; (width * height * 4) as usize],
+                })
+            }
+        }
+
+        pub fn grab(&mut self, x: i32, y: i32, width: i32, height: i32) -> Option<&[u8]> {
+            unsafe {
+                BitBlt(
+                    self.memory,
+                    0,
+                    0,
+                    width,
+                    height,
+                    Some(self.screen),
+                    x,
+                    y,
+                    SRCCOPY | CAPTUREBLT,
+                )
+                .ok()?;
+                let mut info = BITMAPINFO {
+                    bmiHeader: BITMAPINFOHEADER {
+                        biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                        biWidth: width,
+                        // Negative height asks for top-down rows
+                        biHeight: -height,
+                        biPlanes: 1,
+                        biBitCount: 32,
+                        biCompression: BI_RGB.0,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                };
+                let lines = GetDIBits(
+                    self.memory,
+                    self.bitmap,
+                    0,
+                    height as u32,
+                    Some(self.pixels.as_mut_ptr().cast()),
+                    &mut info,
+                    DIB_RGB_COLORS,
+                );
+                (lines == height).then_some(self.pixels.as_slice())
+            }
+        }
+    }
+
+    impl Drop for Grabber {
+        fn drop(&mut self) {
+            unsafe {
+                SelectObject(self.memory, self.previous);
+                let _ = DeleteObject(self.bitmap.into());
+                let _ = DeleteDC(self.memory);
+                ReleaseDC(None, self.screen);
+            }
+        }
+    }
+
+    pub fn pixel(x: i32, y: i32) -> Option<PixelColor> {
+        unsafe {
+            let screen = GetDC(None);
+            if screen.is_invalid() {
+                return None;
+            }
+            let color = GetPixel(screen, x, y);
+            ReleaseDC(None, screen);
+            // COLORREF is 0x00BBGGRR
+            (color.0 != CLR_INVALID).then(|| PixelColor {
+                r: color.0 as u8,
+                g: (color.0 >> 8) as u8,
+                b: (color.0 >> 16) as u8,
+            })
+        }
+    }
+
+    pub fn refresh_rate() -> Option<f64> {
+        let mut timing = DWM_TIMING_INFO {
+            cbSize: std::mem::size_of::<DWM_TIMING_INFO>() as u32,
+            ..Default::default()
+        };
+        unsafe { DwmGetCompositionTimingInfo(HWND::default(), &mut timing).ok()? };
+        let rate = timing.rateRefresh;
+        (rate.uiDenominator != 0 && rate.uiNumerator != 0)
+            .then(|| rate.uiNumerator as f64 / rate.uiDenominator as f64)
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod imp {
+    pub struct Grabber;
+
+    impl Grabber {
+        pub fn new(_width: i32, _height: i32) -> Option<Self> {
+            None
+        }
+
+        pub fn grab(&mut self, _x: i32, _y: i32, _width: i32, _height: i32) -> Option<&[u8]> {
+            None
+        }
+    }
+
+    pub fn pixel(_x: i32, _y: i32) -> Option<super::PixelColor> {
+        None
+    }
+
+    pub fn refresh_rate() -> Option<f64> {
+        None
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+/// RGB of the screen pixel at `(x, y)`, in global screen pixels.
+#[tauri::command]
+pub fn get_pixel_color(x: i32, y: i32) -> Result<PixelColor, String> {
+    pixel_color(x, y).ok_or_else(|| format!("Couldn't read the pixel at {x},{y}"))
+}
+
+/// Starts emitting `cursor-position` events. Does nothing if already running.
+#[tauri::command]
+pub fn start_cursor_tracking(app: AppHandle) {
+    let mut tracking = TRACKING.lock().unwrap_or_else(|e| e.into_inner());
+    if tracking.is_some() {
+        return;
+    }
+    let stop = Arc::new(AtomicBool::new(false));
+    *tracking = Some(Arc::clone(&stop));
+    thread::spawn(move || track_cursor(app, stop));
+}
+
+#[tauri::command]
+pub fn stop_cursor_tracking() {
+    if let Some(stop) = TRACKING.lock().unwrap_or_else(|e| e.into_inner()).take() {
+        stop.store(true, Ordering::Release);
+    }
+}
+
+/// PNG of the screen rectangle with top-left `(x, y)`, in global screen
+/// pixels, sent as raw bytes rather than a JSON array.
+#[tauri::command]
+pub async fn capture_region(
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+) -> Result<tauri::ipc::Response, String> {
+    if !(1..=MAX_CAPTURE_SIDE).contains(&width) || !(1..=MAX_CAPTURE_SIDE).contains(&height) {
+        return Err(format!(
+            "Width and height must be between 1 and {MAX_CAPTURE_SIDE}"
+        ));
+    }
+    let png = tauri::async_runtime::spawn_blocking(move || {
+        let mut grabber = Grabber::new(width, height).ok_or("Couldn't capture the screen")?;
+        let pixels = grabber.grab(x, y).ok_or("Couldn't capture the screen")?;
+        encode_png(width, height, pixels)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    Ok(tauri::ipc::Response::new(png))
+}
+
+/// Watches the screen around `(x, y)` (default: the cursor) for `duration_ms`
+/// and suggests a click interval matching how fast the app there renders.
+#[tauri::command]
+pub async fn sample_frame_rate(
+    x: Option<i32>,
+    y: Option<i32>,
+    duration_ms: Option<u64>,
+) -> Result<FrameRateSample, String> {
+    let (x, y) = match x.zip(y) {
+        Some(point) => point,
+        None => win_input::cursor_pos(),
+    };
+    let duration = duration_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_SAMPLE_DURATION)
+        .min(MAX_SAMPLE_DURATION);
+    tauri::async_runtime::spawn_blocking(move || sample(x, y, duration))
+        .await
+        .map_err(|e| e.to_string())?
+}
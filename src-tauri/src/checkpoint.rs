@@ -0,0 +1,157 @@
+use std::fs;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::storage::{self, BackgroundWriter, Storage};
+use crate::AutoInputSettings;
+
+// ---------------------------------------------------------------------------
+// Run checkpoints
+//
+// Count-limited runs with `checkpoint_enabled` save their progress next to
+// the store every few seconds. A run that finishes or is stopped by the user
+// deletes it; one cut short by quitting the app or a crash leaves it behind,
+// so the next launch can offer to pick up where it left off.
+// ---------------------------------------------------------------------------
+
+const CHECKPOINT_FILE: &str = "checkpoint.json";
+const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Checkpoint {
+    settings: AutoInputSettings,
+    completed: u64,
+    total: u64,
+    saved_at: u64,
+    /// The label the run was started with, if any
+    #[serde(default)]
+    label: Option<String>,
+}
+
+/// What `get_resumable_run` reports about an interrupted run.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumableRun {
+    pub completed: u64,
+    pub total: u64,
+    pub saved_at: u64,
+    pub label: Option<String>,
+}
+
+fn read(app: &AppHandle) -> Option<Checkpoint> {
+    let storage = app.state::<Mutex<Storage>>();
+    let storage = storage::lock_storage(&storage);
+    let text = fs::read_to_string(storage.data_path(CHECKPOINT_FILE)).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+fn clear(app: &AppHandle) {
+    let storage = app.state::<Mutex<Storage>>();
+    let storage = storage::lock_storage(&storage);
+    if !storage.is_read_only() {
+        let _ = fs::remove_file(storage.data_path(CHECKPOINT_FILE));
+    }
+}
+
+/// Saves a worker's progress, at most every `SAVE_INTERVAL`, the file writes
+/// happening off the worker thread.
+pub struct Checkpointer {
+    writer: BackgroundWriter,
+    checkpoint: Checkpoint,
+    /// Inputs done before this run, when resuming
+    offset: u64,
+    saved: Instant,
+}
+
+impl Checkpointer {
+    /// `offset` inputs were already done by the run being resumed, if any;
+    /// `remaining` is this run's repeat count.
+    pub fn start(
+        app: AppHandle,
+        settings: &AutoInputSettings,
+        offset: u64,
+        remaining: u64,
+        label: Option<String>,
+    ) -> Self {
+        let checkpoint = Checkpoint {
+            settings: settings.clone(),
+            completed: offset,
+            total: offset + remaining,
+            saved_at: storage::unix_now_ms(),
+            label,
+        };
+        let mut checkpointer = Self {
+            writer: BackgroundWriter::new(app, CHECKPOINT_FILE),
+            checkpoint,
+            offset,
+            saved: Instant::now(),
+        };
+        checkpointer.save(0);
+        checkpointer
+    }
+
+    /// Records that `count` of this run's inputs are done.
+    pub fn progress(&mut self, count: u64) {
+        if self.saved.elapsed() >= SAVE_INTERVAL {
+            self.save(count);
+        }
+    }
+
+    fn save(&mut self, count: u64) {
+        self.checkpoint.completed = self.offset + count;
+        self.checkpoint.saved_at = storage::unix_now_ms();
+        if let Ok(text) = serde_json::to_string(&self.checkpoint) {
+            self.writer.write(text);
+        }
+        self.saved = Instant::now();
+    }
+
+    /// Ends the run, keeping the checkpoint only if it was interrupted.
+    pub fn end(mut self, count: u64, interrupted: bool) {
+        if interrupted {
+            self.save(count);
+        } else {
+            self.writer.remove();
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+/// The run left unfinished by the last session, if any.
+#[tauri::command]
+pub fn get_resumable_run(app: AppHandle) -> Option<ResumableRun> {
+    read(&app).map(|c| ResumableRun {
+        completed: c.completed,
+        total: c.total,
+        saved_at: c.saved_at,
+        label: c.label,
+    })
+}
+
+/// Forgets the unfinished run.
+#[tauri::command]
+pub fn discard_resumable_run(app: AppHandle) {
+    clear(&app);
+}
+
+/// Continues the unfinished run with the inputs it had left, under the
+/// label it had.
+#[tauri::command]
+pub fn resume_run(app: AppHandle) -> Result<(), String> {
+    let checkpoint = read(&app).ok_or("No run to resume")?;
+    let remaining = checkpoint.total.saturating_sub(checkpoint.completed);
+    if remaining == 0 {
+        clear(&app);
+        return Err("That run had already finished".into());
+    }
+    let mut settings = checkpoint.settings;
+    settings.repeat_count = remaining;
+    crate::start_worker_from(&app, settings, checkpoint.completed, checkpoint.label)
+}
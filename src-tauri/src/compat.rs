@@ -0,0 +1,202 @@
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::conditions::{StartCondition, TextProbe};
+use crate::monitors::{self, MonitorInfo, TargetMapping};
+use crate::permissions::{self, Feature};
+use crate::scheduler::ActivityWindow;
+use crate::window::TargetWindow;
+use crate::{vision, win_input, AutoInputSettings};
+
+// ---------------------------------------------------------------------------
+// Profile compatibility
+//
+// A profile made on another machine can name a monitor that isn't here, aim
+// at a point off every local screen, or lean on something this machine
+// hasn't granted. Checking on load lists all of that up front, with what to
+// change, rather than the run failing (or clicking somewhere unexpected)
+// once started.
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Incompatibility {
+    /// Setting the problem is with, as named in the profile
+    pub setting: &'static str,
+    pub problem: String,
+    pub fix: String,
+}
+
+impl Incompatibility {
+    fn new(setting: &'static str, problem: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            setting,
+            problem: problem.into(),
+            fix: fix.into(),
+        }
+    }
+}
+
+fn on_screen(monitors: &[MonitorInfo], x: i32, y: i32) -> bool {
+    monitors
+        .iter()
+        .any(|m| (m.x..m.x + m.width).contains(&x) && (m.y..m.y + m.height).contains(&y))
+}
+
+fn check_target(settings: &AutoInputSettings, found: &mut Vec<Incompatibility>) {
+    let monitors = monitors::list();
+    if !settings.fixed_monitor.is_empty()
+        && !monitors.iter().any(|m| m.id == settings.fixed_monitor)
+    {
+        found.push(Incompatibility::new(
+            "fixedMonitor",
+            format!("Monitor {} isn't connected", settings.fixed_monitor),
+            "Pick one of this machine's monitors, or clear it to use screen coordinates",
+        ));
+        return;
+    }
+
+    if settings.coordinate_mode == "percent" {
+        if let Err(e) = monitors::percent_to_pixels(
+            &settings.fixed_monitor,
+            settings.fixed_percent_x,
+            settings.fixed_percent_y,
+        ) {
+            found.push(Incompatibility::new(
+                "fixedPercentX",
+                e,
+                "Set the target again with the position picker",
+            ));
+        }
+        return;
+    }
+
+    let Ok(mapping) = TargetMapping::resolve(
+        &settings.fixed_monitor,
+        settings.logical_coords,
+        settings.fixed_x,
+        settings.fixed_y,
+    ) else {
+        return;
+    };
+    let (x, y) = mapping.apply(settings.fixed_x, settings.fixed_y);
+    if !monitors.is_empty() && !on_screen(&monitors, x, y) {
+        found.push(Incompatibility::new(
+            "fixedX",
+            format!("The target {x},{y} is off every connected screen"),
+            "Set the target again with the position picker, or switch to percent \
+             coordinates so it follows the resolution",
+        ));
+    }
+}
+
+/// Everything in `settings` this machine can't honor as-is.
+pub fn check(app: &AppHandle, settings: &AutoInputSettings) -> Vec<Incompatibility> {
+    let mut found = Vec::new();
+
+    if settings.location_mode == "fixed" {
+        check_target(settings, &mut found);
+    }
+
+    if settings.block_input {
+        if !permissions::allowed(app, Feature::InputBlocking) {
+            found.push(Incompatibility::new(
+                "blockInput",
+                "Input blocking hasn't been allowed on this machine",
+                "Allow input blocking in permissions, or turn blocking off",
+            ));
+        }
+        if !win_input::is_elevated() {
+            found.push(Incompatibility::new(
+                "blockInput",
+                "Blocking user input needs administrator rights",
+                "Restart AutoInput as administrator, or turn blocking off",
+            ));
+        }
+    }
+
+    if settings.nudge_enabled && !permissions::allowed(app, Feature::LiveTuning) {
+        found.push(Incompatibility::new(
+            "nudgeEnabled",
+            "Live tuning hasn't been allowed on this machine",
+            "Allow live tuning in permissions, or turn it off",
+        ));
+    }
+
+    if settings.action_type == "gamepad-mouse" && win_input::gamepad_right_stick().is_none() {
+        found.push(Incompatibility::new(
+            "actionType",
+            "No controller is connected",
+            "Connect an XInput controller before starting",
+        ));
+    }
+
+    if settings.bpm > 0 && !(1..=4).contains(&settings.subdivision) {
+        found.push(Incompatibility::new(
+            "subdivision",
+            "Subdivision must be between 1 and 4",
+            "Choose quarter, eighth, triplet or sixteenth notes",
+        ));
+    }
+
+    if let Err(e) = ActivityWindow::parse(&settings.active_from, &settings.active_until) {
+        found.push(Incompatibility::new(
+            "activeFrom",
+            e,
+            "Enter both times as HH:MM, or clear both",
+        ));
+    }
+
+    if let Err(e) = vision::Template::from_settings(settings) {
+        found.push(Incompatibility::new(
+            "targetImage",
+            e,
+            "Capture the target image again",
+        ));
+    }
+
+    if let Err(e) = StartCondition::from_settings(settings) {
+        found.push(match settings.start_condition.as_str() {
+            "image" => Incompatibility::new("startImage", e, "Capture the start image again"),
+            "text" => Incompatibility::new(
+                "startText",
+                e,
+                "Fix the pattern, or select the text region again",
+            ),
+            _ => Incompatibility::new(
+                "startColor",
+                e,
+                "Pick the color again with the color picker",
+            ),
+        });
+    }
+
+    if let Err(e) = TargetWindow::from_settings(settings) {
+        found.push(Incompatibility::new(
+            "targetWindow",
+            e,
+            "Pick the window again, or match it by title instead",
+        ));
+    }
+
+    if let Err(e) = TextProbe::stop_from_settings(settings) {
+        found.push(Incompatibility::new(
+            "stopText",
+            e,
+            "Fix the pattern, or select the text region again",
+        ));
+    }
+
+    found
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+/// Checks a loaded profile against this machine. Empty means it should run
+/// as intended.
+#[tauri::command]
+pub fn check_profile_compat(app: AppHandle, settings: AutoInputSettings) -> Vec<Incompatibility> {
+    check(&app, &settings)
+}
@@ -0,0 +1,467 @@
+use std::cell::{Cell, RefCell};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use regex_lite::Regex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::capture::{self, Grabber, PixelColor};
+use crate::monitors::Rect;
+use crate::ocr::{self, Reader};
+use crate::vision::{Finder, Template};
+use crate::{AutoInputSettings, CONDITION_POLL};
+
+// ---------------------------------------------------------------------------
+// Run conditions
+//
+// Screen checks the worker polls to decide when to start and stop
+// injecting. Parsed once per run from the settings, so a bad color is
+// refused at start rather than mid-run.
+// ---------------------------------------------------------------------------
+
+/// Parses `#RRGGBB` (the `#` is optional).
+pub fn parse_color(text: &str) -> Result<PixelColor, String> {
+    let hex = text.trim().trim_start_matches('#');
+    // from_str_radix alone would take a sign, e.g. "+f"
+    let channel = |i: usize| {
+        hex.get(i..i + 2)
+            .filter(|c| c.bytes().all(|b| b.is_ascii_hexdigit()))
+            .and_then(|c| u8::from_str_radix(c, 16).ok())
+    };
+    match (hex.len(), channel(0), channel(2), channel(4)) {
+        (6, Some(r), Some(g), Some(b)) => Ok(PixelColor { r, g, b }),
+        _ => Err(format!("Invalid color: {text} (expected #RRGGBB)")),
+    }
+}
+
+/// One screen pixel compared against a color.
+#[derive(Debug, Clone, Copy)]
+pub struct ColorProbe {
+    pub x: i32,
+    pub y: i32,
+    pub color: PixelColor,
+    /// Largest per-channel difference still counted as the same color
+    pub tolerance: u8,
+}
+
+impl ColorProbe {
+    /// Whether the pixel is currently within tolerance of the color; None if
+    /// the screen can't be read.
+    pub fn matches(&self) -> Option<bool> {
+        self.difference()
+            .map(|(_, difference)| difference <= self.tolerance)
+    }
+
+    /// The pixel's current color and its largest per-channel difference from
+    /// the probe's color; None if the screen can't be read.
+    fn difference(&self) -> Option<(PixelColor, u8)> {
+        let now = capture::pixel_color(self.x, self.y)?;
+        let difference = now
+            .r
+            .abs_diff(self.color.r)
+            .max(now.g.abs_diff(self.color.g))
+            .max(now.b.abs_diff(self.color.b));
+        Some((now, difference))
+    }
+}
+
+/// Text in a screen region compared against a pattern.
+#[derive(Debug, Clone)]
+pub struct TextProbe {
+    pub region: Rect,
+    pub pattern: Regex,
+}
+
+impl TextProbe {
+    /// Probe of the settings' text region; `pattern` is a case-insensitive
+    /// regular expression.
+    fn parse(s: &AutoInputSettings, pattern: &str) -> Result<Self, String> {
+        let sides = 1..=ocr::MAX_TEXT_SIDE;
+        if !sides.contains(&s.text_width) || !sides.contains(&s.text_height) {
+            return Err(format!(
+                "The text region must be between 1 and {} pixels a side",
+                ocr::MAX_TEXT_SIDE
+            ));
+        }
+        let pattern = Regex::new(&format!("(?i){pattern}"))
+            .map_err(|e| format!("Invalid text pattern: {e}"))?;
+        Ok(Self {
+            region: Rect {
+                x: s.text_x,
+                y: s.text_y,
+                width: s.text_width,
+                height: s.text_height,
+            },
+            pattern,
+        })
+    }
+
+    /// The `stop_text` probe, if one is set.
+    pub fn stop_from_settings(s: &AutoInputSettings) -> Result<Option<Self>, String> {
+        if s.stop_text.is_empty() {
+            return Ok(None);
+        }
+        Self::parse(s, &s.stop_text).map(Some)
+    }
+}
+
+/// `start_condition` setting, resolved once per run.
+#[derive(Debug)]
+pub enum StartCondition {
+    Immediately,
+    /// Start once the probe's match state equals `matching`
+    Color {
+        probe: ColorProbe,
+        matching: bool,
+    },
+    /// Start once the image is on screen, and pause whenever it's gone
+    Image(Template),
+    /// Start once the region's text matches
+    Text(TextProbe),
+}
+
+impl StartCondition {
+    pub fn from_settings(s: &AutoInputSettings) -> Result<Self, String> {
+        let matching = match s.start_condition.as_str() {
+            "color-match" => true,
+            "color-mismatch" => false,
+            "image" if s.start_image.is_empty() => {
+                return Err("No start image selected".into());
+            }
+            "image" => return Template::decode(&s.start_image, s.image_tolerance).map(Self::Image),
+            "text" => return TextProbe::parse(s, &s.start_text).map(Self::Text),
+            _ => return Ok(Self::Immediately),
+        };
+        Ok(Self::Color {
+            probe: ColorProbe {
+                x: s.start_color_x,
+                y: s.start_color_y,
+                color: parse_color(&s.start_color)?,
+                tolerance: s.start_color_tolerance,
+            },
+            matching,
+        })
+    }
+
+    pub fn is_immediate(&self) -> bool {
+        matches!(self, Self::Immediately)
+    }
+
+    /// Readies the condition to be checked on the worker thread.
+    pub fn into_gate(self) -> Gate {
+        match self {
+            Self::Immediately => Gate::Immediately,
+            Self::Color { probe, matching } => Gate::Color { probe, matching },
+            Self::Image(template) => Gate::Image(Finder::new(template)),
+            Self::Text(probe) => Gate::Text {
+                reader: Reader::new(probe.region),
+                pattern: probe.pattern,
+            },
+        }
+    }
+}
+
+/// A `StartCondition` being checked. Kept apart from it because the image
+/// search's capture surfaces can't move between threads.
+pub enum Gate {
+    Immediately,
+    Color { probe: ColorProbe, matching: bool },
+    Image(Finder),
+    Text { reader: Reader, pattern: Regex },
+}
+
+impl Gate {
+    /// An unreadable screen counts as not met, so nothing fires blind.
+    pub fn met(&mut self) -> bool {
+        match self {
+            Self::Immediately => true,
+            Self::Color { probe, matching } => probe.matches() == Some(*matching),
+            Self::Image(finder) => finder.locate().is_some(),
+            Self::Text { reader, pattern } => reader.read().is_some_and(|t| pattern.is_match(&t)),
+        }
+    }
+
+    /// The `trigger` of `trigger-fired` when this is met.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Immediately => "immediately",
+            Self::Color { .. } => "color",
+            Self::Image(_) => "image",
+            Self::Text { .. } => "text",
+        }
+    }
+
+    /// One look at the screen, with what was seen, for `test_trigger`.
+    fn reading(&mut self) -> TriggerReading {
+        let mut reading = TriggerReading {
+            trigger: self.name(),
+            met: false,
+            color: None,
+            difference: None,
+            position: None,
+            text: None,
+        };
+        match self {
+            Self::Immediately => reading.met = true,
+            Self::Color { probe, matching } => {
+                if let Some((color, difference)) = probe.difference() {
+                    reading.met = (difference <= probe.tolerance) == *matching;
+                    reading.color = Some(color);
+                    reading.difference = Some(difference as f64);
+                }
+            }
+            Self::Image(finder) => {
+                if let Some((at, difference)) = finder.closest() {
+                    reading.met = finder.accepts(difference);
+                    reading.position = Some(at);
+                    reading.difference = Some(difference);
+                }
+            }
+            Self::Text { reader, pattern } => {
+                if let Some(text) = reader.read() {
+                    reading.met = pattern.is_match(&text);
+                    reading.text = Some(text);
+                }
+            }
+        }
+        reading
+    }
+
+    /// Whether the condition also has to keep holding once the run is
+    /// going, rather than only gating its start.
+    pub fn holds_run(&self) -> bool {
+        matches!(self, Self::Image(_))
+    }
+}
+
+/// How often `ColorWatch` actually looks, however often it's asked
+const WATCH_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Average color of a BGRA buffer.
+fn average(pixels: &[u8]) -> PixelColor {
+    let (mut r, mut g, mut b, mut n) = (0u64, 0u64, 0u64, 0u64);
+    for px in pixels.chunks_exact(4) {
+        b += px[0] as u64;
+        g += px[1] as u64;
+        r += px[2] as u64;
+        n += 1;
+    }
+    let n = n.max(1);
+    PixelColor {
+        r: (r / n) as u8,
+        g: (g / n) as u8,
+        b: (b / n) as u8,
+    }
+}
+
+struct WatchState {
+    grabber: Option<Grabber>,
+    baseline: Option<PixelColor>,
+    checked_at: Option<Instant>,
+}
+
+/// `stop_on_color_change`: notices a screen square drifting away from the
+/// color it had when injecting began, e.g. a progress bar filling up or a
+/// button disappearing.
+pub struct ColorWatch {
+    left: i32,
+    top: i32,
+    tolerance: u8,
+    // Checked from the worker's `should_stop`, which only has `&self`
+    state: RefCell<WatchState>,
+}
+
+impl ColorWatch {
+    pub fn from_settings(s: &AutoInputSettings) -> Option<Self> {
+        if !s.stop_on_color_change {
+            return None;
+        }
+        let size = s.stop_color_size.clamp(1, 64) as i32;
+        Some(Self {
+            left: s.stop_color_x - size / 2,
+            top: s.stop_color_y - size / 2,
+            tolerance: s.stop_color_tolerance,
+            state: RefCell::new(WatchState {
+                grabber: Grabber::new(size, size),
+                baseline: None,
+                checked_at: None,
+            }),
+        })
+    }
+
+    fn sample(&self, state: &mut WatchState) -> Option<PixelColor> {
+        state
+            .grabber
+            .as_mut()?
+            .grab(self.left, self.top)
+            .map(average)
+    }
+
+    /// Takes the color to compare against from now on.
+    pub fn begin(&self) {
+        let mut state = self.state.borrow_mut();
+        state.baseline = self.sample(&mut state);
+    }
+
+    /// Whether the color has moved past the tolerance since `begin`.
+    pub fn changed(&self) -> bool {
+        let mut state = self.state.borrow_mut();
+        let Some(baseline) = state.baseline else {
+            return false;
+        };
+        if state
+            .checked_at
+            .is_some_and(|t| t.elapsed() < WATCH_INTERVAL)
+        {
+            return false;
+        }
+        state.checked_at = Some(Instant::now());
+        let Some(now) = self.sample(&mut state) else {
+            return false;
+        };
+        let far = |a: u8, b: u8| a.abs_diff(b) > self.tolerance;
+        far(now.r, baseline.r) || far(now.g, baseline.g) || far(now.b, baseline.b)
+    }
+}
+
+/// How often `TextWatch` reads, recognition being comparatively slow
+const TEXT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// `stop_text`: notices the text region coming to match, e.g. "Done" or
+/// "Out of stock" showing up.
+pub struct TextWatch {
+    pattern: Regex,
+    armed: Cell<bool>,
+    checked_at: Cell<Option<Instant>>,
+    // Checked from the worker's `should_stop`, which only has `&self`
+    reader: RefCell<Reader>,
+}
+
+impl TextWatch {
+    pub fn new(probe: TextProbe) -> Self {
+        Self {
+            pattern: probe.pattern,
+            armed: Cell::new(false),
+            checked_at: Cell::new(None),
+            reader: RefCell::new(Reader::new(probe.region)),
+        }
+    }
+
+    /// Starts watching; text showing while the run waits to start doesn't
+    /// count.
+    pub fn begin(&self) {
+        self.armed.set(true);
+    }
+
+    /// Whether the text currently matches.
+    pub fn matched(&self) -> bool {
+        if !self.armed.get()
+            || self
+                .checked_at
+                .get()
+                .is_some_and(|t| t.elapsed() < TEXT_INTERVAL)
+        {
+            return false;
+        }
+        self.checked_at.set(Some(Instant::now()));
+        self.reader
+            .borrow_mut()
+            .read()
+            .is_some_and(|t| self.pattern.is_match(&t))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Trigger testing
+// ---------------------------------------------------------------------------
+
+/// Longest `test_trigger` will stream readings for
+const MAX_TEST_STREAM: Duration = Duration::from_secs(10);
+
+/// What `test_trigger` saw, sent as its result and as `trigger-reading`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerReading {
+    /// "color", "image" or "text", as in `trigger-fired`
+    trigger: &'static str,
+    /// Whether the start condition holds, i.e. a run would start now
+    met: bool,
+    /// Color: the pixel's current color
+    color: Option<PixelColor>,
+    /// Color: largest per-channel difference (0-255) from the set color.
+    /// Image: mean per-pixel difference (0-1) of the closest placement,
+    /// compared against `image_tolerance`
+    difference: Option<f64>,
+    /// Image: center of the closest placement, matching or not
+    position: Option<(i32, i32)>,
+    /// Text: what was read in the region
+    text: Option<String>,
+}
+
+/// Checks the settings' start condition against the screen without starting
+/// a run or sending any input, so tolerances and thresholds can be tuned by
+/// looking rather than by live clicking. With `stream_ms`, keeps checking
+/// for that long (at most 10s), emitting `trigger-reading` each time, and
+/// returns the last reading.
+#[tauri::command]
+pub async fn test_trigger(
+    app: AppHandle,
+    settings: AutoInputSettings,
+    stream_ms: Option<u64>,
+) -> Result<TriggerReading, String> {
+    let condition = StartCondition::from_settings(&settings)?;
+    if condition.is_immediate() {
+        return Err("No start condition is set".into());
+    }
+    let stream = stream_ms
+        .map(Duration::from_millis)
+        .unwrap_or_default()
+        .min(MAX_TEST_STREAM);
+    tauri::async_runtime::spawn_blocking(move || {
+        // Made here, as the image search can't move between threads
+        let mut gate = condition.into_gate();
+        let started = Instant::now();
+        let mut reading = gate.reading();
+        while started.elapsed() < stream {
+            let _ = app.emit("trigger-reading", &reading);
+            thread::sleep(CONDITION_POLL);
+            reading = gate.reading();
+        }
+        reading
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_color_accepts_hex_with_or_without_hash() {
+        let color = PixelColor {
+            r: 0x12,
+            g: 0xAB,
+            b: 0xef,
+        };
+        assert_eq!(parse_color("#12ABef"), Ok(color));
+        assert_eq!(parse_color(" 12abef "), Ok(color));
+    }
+
+    #[test]
+    fn parse_color_rejects_other_forms() {
+        for text in [
+            "",
+            "#",
+            "#fff",
+            "#12345",
+            "#1234567",
+            "#12ab0g",
+            "rgb(1,2,3)",
+            "#+1+2+3",
+        ] {
+            assert!(parse_color(text).is_err(), "{text}");
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::permissions::FeaturePermissions;
+use crate::storage::{self, Storage};
+use crate::{
+    engine, hooks, injection_signature, monitors, safe_mode, scheduler, win_input,
+    DEFAULT_INJECTION_SIGNATURE,
+};
+
+// ---------------------------------------------------------------------------
+// Diagnostics
+//
+// A short activity log and the last crash report live in the app log
+// directory, and `export_diagnostics` bundles them with what this machine
+// can do and a settings snapshot into one file a user can attach to a bug
+// report. Settings go through an allow-list: anything free-form (config
+// names, typed text, window titles, text expansions) is left out.
+// ---------------------------------------------------------------------------
+
+const LOG_FILE: &str = "autoinput.log";
+const CRASH_FILE: &str = "last-crash.txt";
+/// The log moves to `autoinput.log.1` past this, so at most two are kept
+const LOG_MAX_BYTES: u64 = 256 * 1024;
+/// Lines of log that go into a bundle
+const BUNDLE_LOG_LINES: usize = 500;
+
+/// Top-level store keys safe to share. Text expansions, the injection
+/// signature and per-process limits (process names) stay out.
+const STORE_KEYS: &[&str] = &[
+    "backupRetention",
+    "dailyLimitMinutes",
+    "featurePermissions",
+    "toggleKeys",
+    "wheelKeys",
+];
+
+/// String fields of a config that only ever hold one of a few fixed values.
+/// Numbers and booleans are kept as well; every other string is dropped.
+const CONFIG_MODE_FIELDS: &[&str] = &[
+    "actionType",
+    "autoFocus",
+    "clickType",
+    "coordinateMode",
+    "interferenceMode",
+    "keyMode",
+    "locationMode",
+    "modifierHandling",
+    "mouseButton",
+    "mouseMode",
+    "outsideHoursMode",
+    "remoteSessionMode",
+    "repeatMode",
+    "startCondition",
+];
+
+static LOG_DIR: OnceLock<PathBuf> = OnceLock::new();
+/// Serializes appends and rotation
+static LOG_LOCK: Mutex<()> = Mutex::new(());
+
+/// Points the log and crash report at the app log directory. Nothing is
+/// written before this.
+pub fn init(app: &AppHandle) {
+    if let Ok(dir) = app.path().app_log_dir() {
+        if fs::create_dir_all(&dir).is_ok() {
+            let _ = LOG_DIR.set(dir);
+        }
+    }
+}
+
+/// Appends a timestamped line to the activity log. Best effort: a log that
+/// can't be written is not worth failing anything over.
+pub fn log(line: &str) {
+    let Some(dir) = LOG_DIR.get() else {
+        return;
+    };
+    let _guard = LOG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = dir.join(LOG_FILE);
+    if fs::metadata(&path).is_ok_and(|m| m.len() > LOG_MAX_BYTES) {
+        let _ = fs::rename(&path, dir.join(format!("{LOG_FILE}.1")));
+    }
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&path) {
+        let _ = writeln!(file, "{} {line}", storage::unix_now_ms());
+    }
+}
+
+/// Records a panic for the next bundle. Called from the panic hook, so it
+/// takes no locks beyond the file system's.
+pub fn record_crash(info: &std::panic::PanicHookInfo<'_>) {
+    let Some(dir) = LOG_DIR.get() else {
+        return;
+    };
+    let thread = std::thread::current();
+    let report = format!(
+        "at: {}\nversion: {}\nthread: {}\n{info}\n",
+        storage::unix_now_ms(),
+        env!("CARGO_PKG_VERSION"),
+        thread.name().unwrap_or("unnamed"),
+    );
+    let _ = fs::write(dir.join(CRASH_FILE), report);
+}
+
+/// The last `max` lines of the log, oldest first, reaching into the rotated
+/// file if the current one is short.
+fn recent_log(dir: &Path, max: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for name in [LOG_FILE.to_string(), format!("{LOG_FILE}.1")] {
+        let Ok(text) = fs::read_to_string(dir.join(name)) else {
+            continue;
+        };
+        let older: Vec<_> = text.lines().rev().take(max - lines.len()).collect();
+        lines.extend(older.into_iter().map(str::to_string));
+        if lines.len() >= max {
+            break;
+        }
+    }
+    lines.reverse();
+    lines
+}
+
+/// What this machine and install can do, as far as the app can tell.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Capabilities {
+    elevated: bool,
+    remote_session: bool,
+    safe_mode: bool,
+    keyboard_hook: bool,
+    mouse_hook: bool,
+    custom_injection_signature: bool,
+    permissions: FeaturePermissions,
+    virtual_screen: monitors::Rect,
+    monitors: Vec<monitors::MonitorInfo>,
+}
+
+fn capabilities(app: &AppHandle) -> Capabilities {
+    let (keyboard_hook, mouse_hook) = hooks::installed();
+    Capabilities {
+        elevated: win_input::is_elevated(),
+        remote_session: win_input::is_remote_session(),
+        safe_mode: safe_mode(),
+        keyboard_hook,
+        mouse_hook,
+        custom_injection_signature: injection_signature() != DEFAULT_INJECTION_SIGNATURE,
+        permissions: *app
+            .state::<Mutex<FeaturePermissions>>()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()),
+        virtual_screen: monitors::virtual_screen(),
+        monitors: monitors::list(),
+    }
+}
+
+/// A config with only its numbers, flags, mode strings and hotkeys.
+fn redact_config(config: &Value) -> Value {
+    let Some(fields) = config.as_object() else {
+        return Value::Null;
+    };
+    let kept: Map<_, _> = fields
+        .iter()
+        .filter(|(key, value)| match value {
+            Value::Number(_) | Value::Bool(_) => true,
+            Value::String(_) => CONFIG_MODE_FIELDS.contains(&key.as_str()),
+            Value::Object(_) => key.as_str() == "hotkeys",
+            _ => false,
+        })
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    Value::Object(kept)
+}
+
+/// The allow-listed parts of the store, plus each config through
+/// `redact_config`.
+fn redact_store(data: &Value) -> Value {
+    let mut kept = Map::new();
+    for &key in STORE_KEYS {
+        if let Some(value) = data.get(key) {
+            kept.insert(key.into(), value.clone());
+        }
+    }
+    if let Some(configs) = data.pointer("/appState/configs").and_then(Value::as_array) {
+        let configs = configs.iter().map(redact_config).collect();
+        kept.insert("configs".into(), Value::Array(configs));
+    }
+    Value::Object(kept)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiagnosticsBundle {
+    generated_at: u64,
+    app_version: String,
+    os: &'static str,
+    arch: &'static str,
+    running: bool,
+    schedule: Option<scheduler::ScheduleInfo>,
+    data_dir: storage::DataDirInfo,
+    backups: usize,
+    capabilities: Capabilities,
+    settings: Value,
+    log: Vec<String>,
+    last_crash: Option<String>,
+}
+
+/// Replaces the user's home directory in every string with `~` so paths
+/// don't leak account names into bug reports.
+fn redact_home(value: &mut Value, home: &str) {
+    match value {
+        Value::String(s) if !home.is_empty() => *s = s.replace(home, "~"),
+        Value::Array(items) => items.iter_mut().for_each(|v| redact_home(v, home)),
+        Value::Object(map) => map.values_mut().for_each(|v| redact_home(v, home)),
+        _ => {}
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+/// Writes a diagnostics bundle to the app log directory and returns its path.
+#[tauri::command]
+pub fn export_diagnostics(app: AppHandle) -> Result<String, String> {
+    let running = engine::current(&app).is_running();
+    let schedule = scheduler::get_schedule(app.state());
+    let (data_dir, backups, settings) = {
+        let state = app.state::<Mutex<Storage>>();
+        let storage = storage::lock_storage(&state);
+        (
+            storage.info(),
+            storage.list_backups().len(),
+            redact_store(&storage.read_store()),
+        )
+    };
+    let dir = app.path().app_log_dir().map_err(|e| e.to_string())?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let bundle = DiagnosticsBundle {
+        generated_at: storage::unix_now_ms(),
+        app_version: app.package_info().version.to_string(),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        running,
+        schedule,
+        data_dir,
+        backups,
+        capabilities: capabilities(&app),
+        settings,
+        log: recent_log(&dir, BUNDLE_LOG_LINES),
+        last_crash: fs::read_to_string(dir.join(CRASH_FILE)).ok(),
+    };
+    let mut json = serde_json::to_value(&bundle).map_err(|e| e.to_string())?;
+    if let Ok(home) = app.path().home_dir() {
+        redact_home(&mut json, &home.to_string_lossy());
+    }
+
+    let path = dir.join(format!("diagnostics-{}.json", bundle.generated_at));
+    let text = serde_json::to_string_pretty(&json).map_err(|e| e.to_string())?;
+    storage::write_atomic(&path, &text)?;
+    Ok(path.to_string_lossy().into_owned())
+}
@@ -0,0 +1,149 @@
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{hot_corner, idle_trigger, process_watch, scheduler};
+
+// ---------------------------------------------------------------------------
+// Engine state
+//
+// What the engine is doing, as one explicit value rather than something
+// pieced together from the run handle and the worker's `done` flag. Every
+// change goes through `transition`, which refuses moves the engine can't make
+// (e.g. a pause landing after a stop was requested) and emits `engine-state`.
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EngineState {
+    #[default]
+    Idle,
+    /// Nothing running, but a schedule or trigger will start a run
+    Armed,
+    /// A run is in its start delay
+    CountingDown,
+    /// A run is holding off until its start condition is met
+    Waiting,
+    Running,
+    /// A run is waiting out typing, interference, a rest period and the like
+    Paused,
+    /// A stop was requested and the worker is letting go of its inputs
+    Stopping,
+    /// The last run ended abnormally; cleared by the next start
+    Error,
+}
+
+impl EngineState {
+    /// A worker owns the run, whether or not it's injecting right now.
+    pub fn is_active(self) -> bool {
+        matches!(
+            self,
+            Self::CountingDown | Self::Waiting | Self::Running | Self::Paused | Self::Stopping
+        )
+    }
+
+    /// Active and not on its way out.
+    pub fn is_running(self) -> bool {
+        matches!(
+            self,
+            Self::CountingDown | Self::Waiting | Self::Running | Self::Paused
+        )
+    }
+
+    fn can_become(self, next: Self) -> bool {
+        use EngineState::*;
+        match (self, next) {
+            (Idle | Error, Armed) | (Armed, Idle) => true,
+            // Started directly or by the schedule firing
+            (Idle | Armed | Error, CountingDown | Waiting | Running) => true,
+            (CountingDown, Waiting | Running) | (Waiting, Running) => true,
+            (Running, Paused) | (Paused, Running) => true,
+            (CountingDown | Waiting | Running | Paused, Stopping) => true,
+            // Runs end on their own (count reached, timeout) or after a stop
+            (CountingDown | Waiting | Running | Paused | Stopping, Idle | Armed) => true,
+            (Error, Idle) => true,
+            (from, Error) => from != Error,
+            _ => false,
+        }
+    }
+}
+
+/// Payload of `engine-state`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EngineTransition {
+    from: EngineState,
+    to: EngineState,
+}
+
+fn lock_engine(state: &Mutex<EngineState>) -> std::sync::MutexGuard<'_, EngineState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn current(app: &AppHandle) -> EngineState {
+    *lock_engine(&app.state::<Mutex<EngineState>>())
+}
+
+/// Picks the next state from the current one under the lock, so nothing can
+/// change in between, and applies it if it's a valid step.
+fn update(app: &AppHandle, next: impl FnOnce(EngineState) -> Option<EngineState>) -> bool {
+    let (from, to) = {
+        let state = app.state::<Mutex<EngineState>>();
+        let mut current = lock_engine(&state);
+        let from = *current;
+        let Some(to) = next(from).filter(|&to| from.can_become(to)) else {
+            return false;
+        };
+        *current = to;
+        (from, to)
+    };
+    let _ = app.emit("engine-state", EngineTransition { from, to });
+    true
+}
+
+/// Moves to `to` if that's a valid step from the current state. Returns
+/// whether it did.
+pub fn transition(app: &AppHandle, to: EngineState) -> bool {
+    update(app, |_| Some(to))
+}
+
+/// Where the engine rests when no run is going: armed if a schedule or
+/// trigger is pending, otherwise idle.
+fn resting(app: &AppHandle) -> EngineState {
+    if scheduler::get_schedule(app.state()).is_some()
+        || process_watch::is_watching(app)
+        || idle_trigger::is_armed(app)
+        || hot_corner::is_armed(app)
+    {
+        EngineState::Armed
+    } else {
+        EngineState::Idle
+    }
+}
+
+/// Ends a run.
+pub fn settle(app: &AppHandle) {
+    let to = resting(app);
+    update(app, |from| from.is_active().then_some(to));
+}
+
+/// Follows a schedule or trigger being armed, cancelled or used up while
+/// nothing runs.
+pub fn schedule_changed(app: &AppHandle) {
+    let to = resting(app);
+    update(app, |from| {
+        matches!(from, EngineState::Idle | EngineState::Armed).then_some(to)
+    });
+}
+
+/// Puts the engine into `Error` if the worker unwinds from a panic.
+pub struct PanicWatch(pub AppHandle);
+
+impl Drop for PanicWatch {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            transition(&self.0, EngineState::Error);
+        }
+    }
+}
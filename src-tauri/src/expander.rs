@@ -0,0 +1,190 @@
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::hooks::{self, ListenerGuard, VK_BACK, VK_CAPITAL, VK_CONTROL, VK_MENU, VK_SHIFT};
+use crate::permissions::{self, Feature};
+use crate::storage::{self, Storage};
+use crate::{diagnostics, win_input};
+
+// ---------------------------------------------------------------------------
+// Text expansion
+//
+// Watches physical typing through the keyboard hook and, when the last keys
+// typed spell a configured abbreviation, erases it and types the expansion.
+// Key-to-character mapping assumes a US layout; the expansion itself is typed
+// as Unicode and works under any layout.
+// ---------------------------------------------------------------------------
+
+const STORE_KEY: &str = "textExpansions";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Expansion {
+    pub abbreviation: String,
+    pub text: String,
+}
+
+#[derive(Default)]
+pub struct ExpanderState {
+    expansions: Vec<Expansion>,
+    listener: Option<ListenerGuard>,
+}
+
+fn lock_expander(state: &Mutex<ExpanderState>) -> std::sync::MutexGuard<'_, ExpanderState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn key_char(vk: u16, shift: bool) -> Option<char> {
+    let (plain, shifted) = match vk {
+        0x41..=0x5A => {
+            let c = vk as u8 as char;
+            (c.to_ascii_lowercase(), c)
+        }
+        0x30..=0x39 => {
+            let i = (vk - 0x30) as usize;
+            ((b'0' + i as u8) as char, b")!@#$%^&*("[i] as char)
+        }
+        0x20 => (' ', ' '),
+        0xBA => (';', ':'),
+        0xBB => ('=', '+'),
+        0xBC => (',', '<'),
+        0xBD => ('-', '_'),
+        0xBE => ('.', '>'),
+        0xBF => ('/', '?'),
+        0xC0 => ('`', '~'),
+        0xDB => ('[', '{'),
+        0xDC => ('\\', '|'),
+        0xDD => (']', '}'),
+        0xDE => ('\'', '"'),
+        _ => return None,
+    };
+    Some(if shift { shifted } else { plain })
+}
+
+fn is_modifier(vk: u16) -> bool {
+    matches!(
+        vk,
+        VK_SHIFT | VK_CONTROL | VK_MENU | VK_CAPITAL | 0xA0..=0xA5
+    )
+}
+
+fn listen(app: &AppHandle, expansions: Vec<Expansion>) -> Result<ListenerGuard, String> {
+    let longest = expansions
+        .iter()
+        .map(|e| e.abbreviation.chars().count())
+        .max()
+        .unwrap_or(0);
+    let typed = Mutex::new(String::new());
+    // One expansion at a time, so two quick triggers can't interleave
+    let expand = hooks::spawn_injector(move |(erase, text): (usize, String)| {
+        let backspace = win_input::resolve_vk("backspace");
+        for _ in 0..erase {
+            win_input::key_press(backspace);
+        }
+        win_input::type_text(&text);
+    });
+
+    permissions::add_key_listener(app, Feature::TextExpansion, move |event| {
+        // Our own replacement typing comes back through the hook as injected
+        if event.injected || !event.down || is_modifier(event.vk) {
+            return false;
+        }
+        let mut typed = typed.lock().unwrap_or_else(|e| e.into_inner());
+        if event.vk == VK_BACK {
+            typed.pop();
+            return false;
+        }
+
+        let mods = hooks::modifiers();
+        let c = (!mods.ctrl && !mods.alt)
+            .then(|| key_char(event.vk, mods.shift))
+            .flatten();
+        // Navigation, Enter, shortcuts etc. break the word being typed
+        let Some(c) = c else {
+            typed.clear();
+            return false;
+        };
+        typed.push(c);
+        if typed.chars().count() > longest {
+            typed.remove(0);
+        }
+
+        let Some(expansion) = expansions
+            .iter()
+            .find(|e| typed.ends_with(e.abbreviation.as_str()))
+        else {
+            return false;
+        };
+        // The final key is swallowed, so only the earlier ones need erasing
+        let erase = expansion.abbreviation.chars().count() - 1;
+        let _ = expand.send((erase, expansion.text.clone()));
+        typed.clear();
+        true
+    })
+}
+
+/// Stores the list, and arms it only if the feature is permitted and this
+/// isn't safe mode; an error means it isn't armed.
+fn apply(
+    app: &AppHandle,
+    state: &mut ExpanderState,
+    expansions: Vec<Expansion>,
+) -> Result<(), String> {
+    // Drop the old listener first so two never run at once
+    state.listener = None;
+    let armed = if expansions.is_empty() {
+        Ok(())
+    } else {
+        listen(app, expansions.clone()).map(|l| state.listener = Some(l))
+    };
+    state.expansions = expansions;
+    armed
+}
+
+/// Arms the expansions saved in the store, if any and if permitted.
+pub fn load(app: &AppHandle) {
+    let saved = {
+        let storage = app.state::<Mutex<Storage>>();
+        let data = storage::lock_storage(&storage).read_store();
+        data.get(STORE_KEY)
+            .cloned()
+            .and_then(|v| serde_json::from_value::<Vec<Expansion>>(v).ok())
+            .unwrap_or_default()
+    };
+    let state = app.state::<Mutex<ExpanderState>>();
+    let armed = apply(app, &mut lock_expander(&state), saved);
+    if let Err(e) = armed {
+        diagnostics::log(&format!("text expansions not armed: {e}"));
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub fn get_text_expansions(state: tauri::State<'_, Mutex<ExpanderState>>) -> Vec<Expansion> {
+    lock_expander(&state).expansions.clone()
+}
+
+/// Replaces and persists the expansion list; an empty list turns expansion off.
+/// Without the permission, or in safe mode, the list is saved but the error
+/// says it isn't armed.
+#[tauri::command]
+pub fn set_text_expansions(
+    app: AppHandle,
+    state: tauri::State<'_, Mutex<ExpanderState>>,
+    storage: tauri::State<'_, Mutex<Storage>>,
+    expansions: Vec<Expansion>,
+) -> Result<(), String> {
+    if expansions.iter().any(|e| e.abbreviation.is_empty()) {
+        return Err("Abbreviation cannot be empty".into());
+    }
+
+    let list = serde_json::to_value(&expansions).map_err(|e| e.to_string())?;
+    storage::lock_storage(&storage).write_key(STORE_KEY, list)?;
+
+    apply(&app, &mut lock_expander(&state), expansions)
+}
@@ -0,0 +1,94 @@
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::OnceLock;
+
+use crate::window::{self, WindowInfo};
+
+// ---------------------------------------------------------------------------
+// Foreground window changes
+//
+// Windows reports focus moving between top-level windows through an
+// out-of-context WinEvent hook, which only delivers to a thread pumping
+// messages, so the hook gets a dedicated thread. Cheaper and more prompt than
+// polling GetForegroundWindow.
+// ---------------------------------------------------------------------------
+
+type Handler = Box<dyn Fn(WindowInfo) + Send + Sync>;
+
+static HANDLER: OnceLock<Handler> = OnceLock::new();
+
+/// Last window reported, so focus bouncing within a window isn't repeated
+static LAST: AtomicIsize = AtomicIsize::new(0);
+
+/// Starts delivering the new foreground window to `handler` whenever it
+/// changes. Only the first call takes effect.
+pub fn watch(handler: impl Fn(WindowInfo) + Send + Sync + 'static) {
+    if HANDLER.set(Box::new(handler)).is_ok() {
+        imp::spawn_hook();
+    }
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn dispatch(hwnd: isize) {
+    if hwnd == 0 || LAST.swap(hwnd, Ordering::Relaxed) == hwnd {
+        return;
+    }
+    if let (Some(handler), Some(info)) = (HANDLER.get(), window::info(hwnd)) {
+        handler(info);
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use std::thread;
+
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::Accessibility::{SetWinEventHook, HWINEVENTHOOK};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        DispatchMessageW, GetForegroundWindow, GetMessageW, TranslateMessage,
+        EVENT_SYSTEM_FOREGROUND, MSG, WINEVENT_OUTOFCONTEXT,
+    };
+
+    use super::dispatch;
+
+    unsafe extern "system" fn on_event(
+        _hook: HWINEVENTHOOK,
+        _event: u32,
+        hwnd: HWND,
+        _id_object: i32,
+        _id_child: i32,
+        _thread: u32,
+        _time: u32,
+    ) {
+        dispatch(hwnd.0 as isize);
+    }
+
+    pub fn spawn_hook() {
+        thread::spawn(|| unsafe {
+            let hook = SetWinEventHook(
+                EVENT_SYSTEM_FOREGROUND,
+                EVENT_SYSTEM_FOREGROUND,
+                None,
+                Some(on_event),
+                0,
+                0,
+                WINEVENT_OUTOFCONTEXT,
+            );
+            if hook.is_invalid() {
+                return;
+            }
+            // Whatever already has focus counts as the first change
+            dispatch(GetForegroundWindow().0 as isize);
+
+            let mut msg = MSG::default();
+            while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        });
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod imp {
+    pub fn spawn_hook() {}
+}
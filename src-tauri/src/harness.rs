@@ -0,0 +1,344 @@
+use std::cell::RefCell;
+
+use crate::win_input::Sent;
+
+// ---------------------------------------------------------------------------
+// Determinism harness
+//
+// With `dev-harness` on, the stub input backend logs every input it's asked
+// to send instead of dropping it. The tests below drive the engine's building
+// blocks, and the worker's tick loop on a fake clock, against that log with a
+// seeded RNG and assert the exact sequence and timing, so timing refactors
+// can't quietly change what reaches the target app.
+//
+//     cargo test --features dev-harness
+// ---------------------------------------------------------------------------
+
+thread_local! {
+    // Per thread, so tests running in parallel keep separate logs
+    static LOG: RefCell<Vec<Sent>> = const { RefCell::new(Vec::new()) };
+}
+
+pub fn record(event: Sent) {
+    LOG.with(|log| log.borrow_mut().push(event));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::win_input::{self, VIRTUAL_KEY};
+    use crate::{
+        calc_interval_ms, inject_without_modifiers, jittered_interval, jittered_point, move_to,
+        release_held_inputs, verify_released, AutoInputSettings, ClickButton, Clock, HeldInput,
+        ModifierHandling, TickHost, TickLoop,
+    };
+
+    const KEY_E: u16 = 0x45;
+
+    // Held inputs live in process-wide lists, so tests touching them take turns
+    static HELD_INPUTS: Mutex<()> = Mutex::new(());
+
+    fn serial() -> std::sync::MutexGuard<'static, ()> {
+        HELD_INPUTS.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Everything sent on this thread since the last call.
+    fn take() -> Vec<Sent> {
+        LOG.with(|log| std::mem::take(&mut *log.borrow_mut()))
+    }
+
+    /// How the tick loop spent its time, in whole milliseconds.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Slept {
+        Pause(u64),
+        Sleep(u64),
+    }
+
+    /// Only moves when slept on, and keeps a log of every sleep.
+    struct FakeClock {
+        now: Cell<Instant>,
+        slept: RefCell<Vec<Slept>>,
+    }
+
+    impl FakeClock {
+        fn new() -> Self {
+            Self {
+                now: Cell::new(Instant::now()),
+                slept: RefCell::new(Vec::new()),
+            }
+        }
+
+        fn advance(&self, dur: Duration, slept: Slept) {
+            self.now.set(self.now.get() + dur);
+            self.slept.borrow_mut().push(slept);
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> Instant {
+            self.now.get()
+        }
+
+        fn pause(&self, pause: Duration, _base_ms: u64, _stop: &AtomicBool) -> bool {
+            self.advance(pause, Slept::Pause(pause.as_millis() as u64));
+            true
+        }
+
+        fn sleep(&self, dur: Duration, _stop: &AtomicBool) -> bool {
+            self.advance(dur, Slept::Sleep(dur.as_millis() as u64));
+            true
+        }
+    }
+
+    #[derive(Default)]
+    struct Host {
+        interval_ms: u64,
+        ticks: Vec<(u64, u64)>,
+        idled: usize,
+        rests: Vec<Option<Duration>>,
+    }
+
+    impl TickHost for Host {
+        fn interval_ms(&self) -> u64 {
+            self.interval_ms
+        }
+
+        fn min_tick(&mut self, _inputs: u64) -> Duration {
+            Duration::ZERO
+        }
+
+        fn ticked(&mut self, count: u64, inputs: u64) {
+            self.ticks.push((count, inputs));
+        }
+
+        fn idled(&mut self) {
+            self.idled += 1;
+        }
+
+        fn resting(&mut self, rest: Option<Duration>) {
+            self.rests.push(rest);
+        }
+    }
+
+    /// Runs `settings` the way the worker's tick loop does until it ends on
+    /// its own, with nothing in between ticks.
+    fn run_ticks(settings: &AutoInputSettings, repeat_count: u64) -> (Host, Vec<Slept>) {
+        let clock = FakeClock::new();
+        let mut host = Host {
+            interval_ms: calc_interval_ms(settings),
+            ..Default::default()
+        };
+        let mut ticks = TickLoop::new(settings, repeat_count, clock.now());
+        let stop = AtomicBool::new(false);
+        while ticks.tick(&clock, &mut host, &stop) {}
+        (host, clock.slept.into_inner())
+    }
+
+    fn clicks(interval_ms: u64) -> AutoInputSettings {
+        AutoInputSettings {
+            action_type: "click".into(),
+            milliseconds: interval_ms,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn double_click_sends_two_clicks_per_tick() {
+        take();
+        let stop = AtomicBool::new(false);
+        for mode in [ModifierHandling::Off, ModifierHandling::Wait] {
+            assert!(inject_without_modifiers(mode, &stop, || {
+                for _ in 0..2 {
+                    win_input::mouse_click(ClickButton::Left);
+                }
+            }));
+        }
+        assert_eq!(take(), vec![Sent::Click(ClickButton::Left); 4]);
+    }
+
+    #[test]
+    fn key_repeat_taps_down_then_up() {
+        take();
+        win_input::key_press(VIRTUAL_KEY(KEY_E));
+        win_input::key_press(VIRTUAL_KEY(KEY_E));
+        assert_eq!(
+            take(),
+            vec![
+                Sent::KeyDown(KEY_E),
+                Sent::KeyUp(KEY_E),
+                Sent::KeyDown(KEY_E),
+                Sent::KeyUp(KEY_E),
+            ]
+        );
+    }
+
+    #[test]
+    fn hold_presses_once_and_releases_on_drop() {
+        let _serial = serial();
+        take();
+        let held = HeldInput::key(VIRTUAL_KEY(KEY_E));
+        assert_eq!(take(), vec![Sent::KeyDown(KEY_E)]);
+        drop(held);
+        verify_released();
+        assert_eq!(take(), vec![Sent::KeyUp(KEY_E)]);
+    }
+
+    #[test]
+    fn release_paths_let_go_exactly_once() {
+        let _serial = serial();
+        take();
+        let key = HeldInput::key(VIRTUAL_KEY(KEY_E));
+        let button = HeldInput::button(ClickButton::Right);
+        release_held_inputs();
+        drop(key);
+        drop(button);
+        assert_eq!(
+            take(),
+            vec![
+                Sent::KeyDown(KEY_E),
+                Sent::Down(ClickButton::Right),
+                Sent::KeyUp(KEY_E),
+                Sent::Up(ClickButton::Right),
+            ]
+        );
+    }
+
+    #[test]
+    fn jitter_is_reproducible_for_a_seed() {
+        let run = || {
+            fastrand::seed(7);
+            (0..200)
+                .map(|_| (jittered_interval(100, 20), jittered_point(500, 500, 8)))
+                .collect::<Vec<_>>()
+        };
+        let first = run();
+        assert_eq!(first, run());
+        for (interval, (x, y)) in first {
+            assert!((Duration::from_millis(80)..=Duration::from_millis(120)).contains(&interval));
+            let (dx, dy) = ((x - 500) as f64, (y - 500) as f64);
+            assert!((dx * dx + dy * dy).sqrt() <= 8.5);
+        }
+        assert_eq!(jittered_interval(100, 0), Duration::from_millis(100));
+        assert_eq!(jittered_point(500, 500, 0), (500, 500));
+    }
+
+    #[test]
+    fn travel_without_duration_warps_once() {
+        take();
+        move_to(10, 20, 0, &AtomicBool::new(false));
+        // Stopped before the first step, so nothing moves
+        move_to(30, 40, 50, &AtomicBool::new(true));
+        assert_eq!(take(), vec![Sent::MoveAbs(10, 20)]);
+    }
+
+    #[test]
+    fn ticks_follow_the_interval_and_stop_at_the_count() {
+        take();
+        let (host, slept) = run_ticks(&clicks(100), 5);
+        assert_eq!(take(), vec![Sent::Click(ClickButton::Left); 5]);
+        // Nothing to wait for after the last one
+        assert_eq!(slept, vec![Slept::Pause(100); 4]);
+        assert_eq!(host.ticks, (1..=5).map(|n| (n, 1)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn max_cps_floors_the_pause() {
+        take();
+        let settings = AutoInputSettings {
+            click_type: "double".into(),
+            max_cps: 20,
+            ..clicks(1)
+        };
+        // Two inputs a tick at 20 per second
+        let (host, slept) = run_ticks(&settings, 3);
+        assert_eq!(take(), vec![Sent::Click(ClickButton::Left); 6]);
+        assert_eq!(slept, vec![Slept::Pause(100); 2]);
+        assert_eq!(host.ticks, vec![(1, 2), (2, 2), (3, 2)]);
+
+        let settings = AutoInputSettings {
+            unlimited_cps: true,
+            ..settings
+        };
+        assert_eq!(run_ticks(&settings, 2).1, vec![Slept::Pause(1)]);
+        take();
+    }
+
+    #[test]
+    fn micro_pauses_come_due_on_the_clock() {
+        take();
+        let settings = AutoInputSettings {
+            micro_pause_enabled: true,
+            micro_pause_every_min_secs: 1,
+            micro_pause_every_max_secs: 1,
+            micro_pause_min_ms: 200,
+            micro_pause_max_ms: 200,
+            ..clicks(250)
+        };
+        let (host, slept) = run_ticks(&settings, 6);
+        assert_eq!(take().len(), 6);
+        // Due a second in, and a second after the last one ended
+        assert_eq!(
+            slept,
+            vec![
+                Slept::Pause(250),
+                Slept::Pause(250),
+                Slept::Pause(250),
+                Slept::Pause(250),
+                Slept::Sleep(200),
+                Slept::Pause(250),
+            ]
+        );
+        assert_eq!(host.idled, 1);
+    }
+
+    #[test]
+    fn work_rest_rests_once_the_work_phase_is_up() {
+        take();
+        let settings = AutoInputSettings {
+            action_type: "key-repeat".into(),
+            hold_key: "e".into(),
+            seconds: 20,
+            milliseconds: 0,
+            work_minutes: 1,
+            rest_minutes: 2,
+            ..Default::default()
+        };
+        let (host, slept) = run_ticks(&settings, 5);
+        assert_eq!(
+            take(),
+            vec![[Sent::KeyDown(KEY_E), Sent::KeyUp(KEY_E)]; 5].concat()
+        );
+        assert_eq!(
+            slept,
+            vec![
+                Slept::Pause(20_000),
+                Slept::Pause(20_000),
+                Slept::Pause(20_000),
+                Slept::Sleep(120_000),
+                Slept::Pause(20_000),
+            ]
+        );
+        assert_eq!(host.rests, vec![Some(Duration::from_secs(120)), None]);
+    }
+
+    #[test]
+    fn tempo_overrides_the_interval() {
+        let settings = AutoInputSettings {
+            bpm: 120,
+            subdivision: 2,
+            ..Default::default()
+        };
+        assert_eq!(calc_interval_ms(&settings), 250);
+        let settings = AutoInputSettings {
+            seconds: 1,
+            milliseconds: 5,
+            ..Default::default()
+        };
+        assert_eq!(calc_interval_ms(&settings), 1005);
+    }
+}
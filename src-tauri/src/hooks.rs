@@ -0,0 +1,389 @@
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+
+// ---------------------------------------------------------------------------
+// Low-level input hooks
+//
+// One process-wide keyboard hook and one mouse hook, each installed on first
+// use, fan events out to any number of listeners. Listeners run on the hook
+// thread inside the OS callback, so they must be quick; returning true
+// swallows the event. The mouse hook sees every cursor move, so it's only
+// installed once something actually listens.
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy)]
+pub struct KeyEvent {
+    pub vk: u16,
+    pub down: bool,
+    /// Synthesized by SendInput (ours or another program's)
+    pub injected: bool,
+    /// The sender's dwExtraInfo; ours carry the injection signature
+    pub extra_info: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+    X1,
+    X2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub enum MouseKind {
+    Move,
+    Down(MouseButton),
+    Up(MouseButton),
+    /// Vertical wheel; positive is away from the user, 120 per notch
+    Wheel(i16),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MouseEvent {
+    pub kind: MouseKind,
+    pub injected: bool,
+    /// Cursor position in screen pixels
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Physical modifier state as last seen by the hook.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+}
+
+/// dwExtraInfo of the no-op moves the CPS measurement injects. The mouse
+/// hook lets them pass without telling any listener, so a measurement can't
+/// look like user activity or trip a mouse binding.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub const TEST_SINK_EXTRA_INFO: usize = 0x4155_5453;
+
+pub const VK_BACK: u16 = 0x08;
+pub const VK_SHIFT: u16 = 0x10;
+pub const VK_CONTROL: u16 = 0x11;
+pub const VK_MENU: u16 = 0x12;
+pub const VK_CAPITAL: u16 = 0x14;
+pub const VK_ESCAPE: u16 = 0x1B;
+pub const VK_PRIOR: u16 = 0x21;
+pub const VK_NEXT: u16 = 0x22;
+pub const VK_END: u16 = 0x23;
+pub const VK_LEFT: u16 = 0x25;
+pub const VK_UP: u16 = 0x26;
+pub const VK_RIGHT: u16 = 0x27;
+pub const VK_DOWN: u16 = 0x28;
+
+type KeyListener = Arc<dyn Fn(&KeyEvent) -> bool + Send + Sync>;
+type MouseListener = Arc<dyn Fn(&MouseEvent) -> bool + Send + Sync>;
+/// Listeners by id, rebuilt on every add and remove so an event only clones
+/// the `Arc`; None when there are none
+type Listeners<L> = Option<Arc<[(u64, L)]>>;
+
+fn with_listener<L: Clone>(listeners: &Listeners<L>, id: u64, listener: L) -> Listeners<L> {
+    let current = listeners.as_deref().unwrap_or_default();
+    Some(current.iter().cloned().chain([(id, listener)]).collect())
+}
+
+fn without_listener<L: Clone>(listeners: &Listeners<L>, id: u64) -> Listeners<L> {
+    let kept: Vec<_> = listeners
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .filter(|(i, _)| *i != id)
+        .cloned()
+        .collect();
+    (!kept.is_empty()).then(|| kept.into())
+}
+
+struct Registry {
+    next_id: u64,
+    key_listeners: Listeners<KeyListener>,
+    mouse_listeners: Listeners<MouseListener>,
+    modifiers: Modifiers,
+    /// Keys and buttons physically down, by virtual-key code
+    physical: [bool; 256],
+}
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    next_id: 0,
+    key_listeners: None,
+    mouse_listeners: None,
+    modifiers: Modifiers {
+        ctrl: false,
+        alt: false,
+        shift: false,
+    },
+    physical: [false; 256],
+});
+
+fn registry() -> MutexGuard<'static, Registry> {
+    REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Removes its listener when dropped.
+pub struct ListenerGuard(u64);
+
+impl Drop for ListenerGuard {
+    fn drop(&mut self) {
+        let mut reg = registry();
+        reg.key_listeners = without_listener(&reg.key_listeners, self.0);
+        reg.mouse_listeners = without_listener(&reg.mouse_listeners, self.0);
+    }
+}
+
+pub fn add_key_listener(f: impl Fn(&KeyEvent) -> bool + Send + Sync + 'static) -> ListenerGuard {
+    imp::ensure_keyboard_installed();
+    let mut reg = registry();
+    reg.next_id += 1;
+    let id = reg.next_id;
+    reg.key_listeners = with_listener(&reg.key_listeners, id, Arc::new(f));
+    ListenerGuard(id)
+}
+
+pub fn add_mouse_listener(
+    f: impl Fn(&MouseEvent) -> bool + Send + Sync + 'static,
+) -> ListenerGuard {
+    imp::ensure_mouse_installed();
+    let mut reg = registry();
+    reg.next_id += 1;
+    let id = reg.next_id;
+    reg.mouse_listeners = with_listener(&reg.mouse_listeners, id, Arc::new(f));
+    ListenerGuard(id)
+}
+
+pub fn modifiers() -> Modifiers {
+    registry().modifiers
+}
+
+/// Whether the keyboard and mouse hooks are installed.
+pub fn installed() -> (bool, bool) {
+    (imp::keyboard_installed(), imp::mouse_installed())
+}
+
+/// Runs `inject` for everything sent to the returned sender, one after
+/// another on its own thread, so a listener can queue input instead of
+/// injecting from inside the hook callback, which would stall all input.
+/// Items never interleave. Lives as long as the sender.
+pub fn spawn_injector<T: Send + 'static>(inject: impl Fn(T) + Send + 'static) -> Sender<T> {
+    let (sender, queued) = mpsc::channel();
+    thread::spawn(move || {
+        for item in queued {
+            inject(item);
+        }
+    });
+    sender
+}
+
+/// Starts following the physical mouse buttons, for `physically_down`, for
+/// as long as the guard lives.
+pub fn track_buttons() -> ListenerGuard {
+    add_mouse_listener(|_| false)
+}
+
+/// Whether the user is physically holding a key or mouse button (by
+/// virtual-key code), as opposed to it being down from injected input. None
+/// when the hook that would see it isn't installed.
+pub fn physically_down(vk: u16) -> Option<bool> {
+    let is_button = matches!(vk, 0x01 | 0x02 | 0x04 | 0x05 | 0x06);
+    let hooked = if is_button {
+        imp::mouse_installed()
+    } else {
+        imp::keyboard_installed()
+    };
+    hooked.then(|| registry().physical[vk as usize & 0xFF])
+}
+
+/// Virtual-key code of a mouse button.
+fn button_vk(button: MouseButton) -> u16 {
+    match button {
+        MouseButton::Left => 0x01,
+        MouseButton::Right => 0x02,
+        MouseButton::Middle => 0x04,
+        MouseButton::X1 => 0x05,
+        MouseButton::X2 => 0x06,
+    }
+}
+
+/// Called by the platform hook for every keyboard event; true means swallow.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn dispatch_key(event: &KeyEvent) -> bool {
+    let listeners = {
+        let mut reg = registry();
+        if !event.injected {
+            reg.physical[event.vk as usize & 0xFF] = event.down;
+            let m = &mut reg.modifiers;
+            match event.vk {
+                // Generic and left/right variants
+                VK_SHIFT | 0xA0 | 0xA1 => m.shift = event.down,
+                VK_CONTROL | 0xA2 | 0xA3 => m.ctrl = event.down,
+                VK_MENU | 0xA4 | 0xA5 => m.alt = event.down,
+                _ => {}
+            }
+        }
+        reg.key_listeners.clone()
+    };
+    // Call outside the lock so listeners may add/remove listeners
+    let mut swallow = false;
+    for (_, listener) in listeners.as_deref().unwrap_or_default() {
+        swallow |= listener(event);
+    }
+    swallow
+}
+
+/// Called by the platform hook for every mouse event; true means swallow.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn dispatch_mouse(event: &MouseEvent) -> bool {
+    let listeners = {
+        let mut reg = registry();
+        if !event.injected {
+            match event.kind {
+                MouseKind::Down(button) => reg.physical[button_vk(button) as usize] = true,
+                MouseKind::Up(button) => reg.physical[button_vk(button) as usize] = false,
+                _ => {}
+            }
+        }
+        reg.mouse_listeners.clone()
+    };
+    let mut swallow = false;
+    for (_, listener) in listeners.as_deref().unwrap_or_default() {
+        swallow |= listener(event);
+    }
+    swallow
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Once;
+    use std::thread;
+
+    use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CallNextHookEx, DispatchMessageW, GetMessageW, SetWindowsHookExW, TranslateMessage,
+        HC_ACTION, HOOKPROC, KBDLLHOOKSTRUCT, LLKHF_INJECTED, LLMHF_INJECTED, MSG, MSLLHOOKSTRUCT,
+        WH_KEYBOARD_LL, WH_MOUSE_LL, WINDOWS_HOOK_ID, WM_KEYDOWN, WM_LBUTTONDOWN, WM_LBUTTONUP,
+        WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEMOVE, WM_MOUSEWHEEL, WM_RBUTTONDOWN, WM_RBUTTONUP,
+        WM_SYSKEYDOWN, WM_XBUTTONDOWN, WM_XBUTTONUP,
+    };
+
+    use super::{
+        dispatch_key, dispatch_mouse, KeyEvent, MouseButton, MouseEvent, MouseKind,
+        TEST_SINK_EXTRA_INFO,
+    };
+
+    static KEYBOARD: Once = Once::new();
+    static MOUSE: Once = Once::new();
+    /// Set once the hook is actually in place, which the `Once`s can't tell
+    static KEYBOARD_HOOKED: AtomicBool = AtomicBool::new(false);
+    static MOUSE_HOOKED: AtomicBool = AtomicBool::new(false);
+
+    unsafe extern "system" fn keyboard_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        if code == HC_ACTION as i32 {
+            let kb = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+            let msg = wparam.0 as u32;
+            let event = KeyEvent {
+                vk: kb.vkCode as u16,
+                down: msg == WM_KEYDOWN || msg == WM_SYSKEYDOWN,
+                injected: kb.flags.contains(LLKHF_INJECTED),
+                extra_info: kb.dwExtraInfo,
+            };
+            if dispatch_key(&event) {
+                return LRESULT(1);
+            }
+        }
+        CallNextHookEx(None, code, wparam, lparam)
+    }
+
+    unsafe extern "system" fn mouse_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        // lparam only points at the event for HC_ACTION
+        if code != HC_ACTION as i32 {
+            return CallNextHookEx(None, code, wparam, lparam);
+        }
+        let ms = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+        if ms.dwExtraInfo != TEST_SINK_EXTRA_INFO {
+            // High word of mouseData: wheel delta, or which X button
+            let high = (ms.mouseData >> 16) as u16;
+            let x_button = if high == 1 {
+                MouseButton::X1
+            } else {
+                MouseButton::X2
+            };
+            let kind = match wparam.0 as u32 {
+                WM_MOUSEMOVE => Some(MouseKind::Move),
+                WM_LBUTTONDOWN => Some(MouseKind::Down(MouseButton::Left)),
+                WM_LBUTTONUP => Some(MouseKind::Up(MouseButton::Left)),
+                WM_RBUTTONDOWN => Some(MouseKind::Down(MouseButton::Right)),
+                WM_RBUTTONUP => Some(MouseKind::Up(MouseButton::Right)),
+                WM_MBUTTONDOWN => Some(MouseKind::Down(MouseButton::Middle)),
+                WM_MBUTTONUP => Some(MouseKind::Up(MouseButton::Middle)),
+                WM_XBUTTONDOWN => Some(MouseKind::Down(x_button)),
+                WM_XBUTTONUP => Some(MouseKind::Up(x_button)),
+                WM_MOUSEWHEEL => Some(MouseKind::Wheel(high as i16)),
+                _ => None,
+            };
+            if let Some(kind) = kind {
+                let event = MouseEvent {
+                    kind,
+                    injected: ms.flags & LLMHF_INJECTED != 0,
+                    x: ms.pt.x,
+                    y: ms.pt.y,
+                };
+                if dispatch_mouse(&event) {
+                    return LRESULT(1);
+                }
+            }
+        }
+        CallNextHookEx(None, code, wparam, lparam)
+    }
+
+    // LL hooks are delivered to the installing thread's message loop, so each
+    // hook gets a dedicated thread that lives for the rest of the process
+    fn spawn_hook_thread(id: WINDOWS_HOOK_ID, proc: HOOKPROC, hooked: &'static AtomicBool) {
+        thread::spawn(move || unsafe {
+            if SetWindowsHookExW(id, proc, None, 0).is_err() {
+                return;
+            }
+            hooked.store(true, Ordering::Release);
+            let mut msg = MSG::default();
+            while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        });
+    }
+
+    pub fn ensure_keyboard_installed() {
+        KEYBOARD
+            .call_once(|| spawn_hook_thread(WH_KEYBOARD_LL, Some(keyboard_proc), &KEYBOARD_HOOKED));
+    }
+
+    pub fn ensure_mouse_installed() {
+        MOUSE.call_once(|| spawn_hook_thread(WH_MOUSE_LL, Some(mouse_proc), &MOUSE_HOOKED));
+    }
+
+    pub fn keyboard_installed() -> bool {
+        KEYBOARD_HOOKED.load(Ordering::Acquire)
+    }
+
+    pub fn mouse_installed() -> bool {
+        MOUSE_HOOKED.load(Ordering::Acquire)
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod imp {
+    pub fn ensure_keyboard_installed() {}
+    pub fn ensure_mouse_installed() {}
+    pub fn keyboard_installed() -> bool {
+        false
+    }
+    pub fn mouse_installed() -> bool {
+        false
+    }
+}
@@ -0,0 +1,185 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{
+    engine, release_held_inputs, safe_mode, sleep_unless_stopped, start_worker, stop_worker_for,
+    win_input, AutoInputSettings,
+};
+
+// ---------------------------------------------------------------------------
+// Hot-corner toggle
+//
+// Pushing the cursor into a chosen corner of the primary screen starts the
+// run, and doing it again stops it. Edge-triggered: the cursor has to leave
+// the corner before it counts again, so resting there does nothing more.
+// ---------------------------------------------------------------------------
+
+const CORNER_POLL: Duration = Duration::from_millis(30);
+/// Pixels from each edge that still count as the corner
+const CORNER_MARGIN: i32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Corner {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "top-left" => Ok(Self::TopLeft),
+            "top-right" => Ok(Self::TopRight),
+            "bottom-left" => Ok(Self::BottomLeft),
+            "bottom-right" => Ok(Self::BottomRight),
+            other => Err(format!("Unknown screen corner \"{other}\"")),
+        }
+    }
+
+    fn contains(self, x: i32, y: i32) -> bool {
+        let (w, h) = win_input::screen_size();
+        if w == 0 || h == 0 {
+            return false;
+        }
+        let (left, top) = (x <= CORNER_MARGIN, y <= CORNER_MARGIN);
+        let (right, bottom) = (x >= w - 1 - CORNER_MARGIN, y >= h - 1 - CORNER_MARGIN);
+        match self {
+            Self::TopLeft => left && top,
+            Self::TopRight => right && top,
+            Self::BottomLeft => left && bottom,
+            Self::BottomRight => right && bottom,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct HotCornerState {
+    cancel: Option<Arc<AtomicBool>>,
+    info: Option<HotCornerInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotCornerInfo {
+    pub corner: Corner,
+}
+
+/// Payload of `hot-corner-toggled`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HotCornerToggled {
+    started: bool,
+    /// Why the run couldn't be started
+    error: Option<String>,
+}
+
+fn lock_corner(state: &Mutex<HotCornerState>) -> std::sync::MutexGuard<'_, HotCornerState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Whether the hot corner will start a run.
+pub fn is_armed(app: &AppHandle) -> bool {
+    lock_corner(&app.state::<Mutex<HotCornerState>>())
+        .info
+        .is_some()
+}
+
+fn toggle(app: &AppHandle, settings: &AutoInputSettings) {
+    let toggled = if engine::current(app).is_active() {
+        stop_worker_for(app, "hot-corner");
+        release_held_inputs();
+        HotCornerToggled {
+            started: false,
+            error: None,
+        }
+    } else {
+        HotCornerToggled {
+            started: true,
+            error: start_worker(app, settings.clone()).err(),
+        }
+    };
+    let _ = app.emit("hot-corner-toggled", toggled);
+}
+
+fn spawn_watch(
+    app: AppHandle,
+    settings: AutoInputSettings,
+    corner: Corner,
+    cancel: Arc<AtomicBool>,
+) {
+    thread::spawn(move || {
+        // Already sitting in the corner when armed doesn't count
+        let (x, y) = win_input::cursor_pos();
+        let mut inside = corner.contains(x, y);
+        loop {
+            let (x, y) = win_input::cursor_pos();
+            let now_inside = corner.contains(x, y);
+            if now_inside && !inside {
+                toggle(&app, &settings);
+            }
+            inside = now_inside;
+            if !sleep_unless_stopped(CORNER_POLL, &cancel) {
+                return;
+            }
+        }
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+/// Toggles `settings` whenever the cursor is pushed into `corner`
+/// ("top-left", "top-right", "bottom-left" or "bottom-right"). Replaces any
+/// existing hot corner.
+#[tauri::command]
+pub fn arm_hot_corner(
+    app: AppHandle,
+    state: tauri::State<'_, Mutex<HotCornerState>>,
+    settings: AutoInputSettings,
+    corner: String,
+) -> Result<HotCornerInfo, String> {
+    if safe_mode() {
+        return Err("Hot corners are disabled in safe mode".into());
+    }
+    let info = HotCornerInfo {
+        corner: Corner::parse(&corner)?,
+    };
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let mut hot = lock_corner(&state);
+        if let Some(old) = hot.cancel.replace(Arc::clone(&cancel)) {
+            old.store(true, Ordering::Release);
+        }
+        hot.info = Some(info.clone());
+    }
+    engine::schedule_changed(&app);
+    spawn_watch(app, settings, info.corner, cancel);
+    Ok(info)
+}
+
+/// Stops watching the corner. A run it started keeps going.
+#[tauri::command]
+pub fn disarm_hot_corner(app: AppHandle, state: tauri::State<'_, Mutex<HotCornerState>>) {
+    {
+        let mut hot = lock_corner(&state);
+        if let Some(cancel) = hot.cancel.take() {
+            cancel.store(true, Ordering::Release);
+        }
+        hot.info = None;
+    }
+    engine::schedule_changed(&app);
+}
+
+#[tauri::command]
+pub fn get_hot_corner(state: tauri::State<'_, Mutex<HotCornerState>>) -> Option<HotCornerInfo> {
+    lock_corner(&state).info.clone()
+}
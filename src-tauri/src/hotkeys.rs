@@ -0,0 +1,393 @@
+use std::collections::HashSet;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::hooks::{ListenerGuard, MouseButton, MouseKind};
+use crate::permissions::{self, Feature};
+use crate::{
+    engine, keys, safe_mode, start_action, stop_action, AutoInputSettings, HotkeySettings,
+};
+
+// ---------------------------------------------------------------------------
+// Global hotkeys
+//
+// Registered from here rather than the webview so they keep working while
+// the window is hidden in the tray or its JS is throttled. Each press goes
+// through the same start/stop paths the UI uses, and `hotkey-triggered`
+// tells the UI what happened so it can follow along.
+//
+// Hold-to-activate keys can't go through the plugin, which only reports
+// presses, and neither can mouse buttons, so those come from the low-level
+// hooks instead.
+// ---------------------------------------------------------------------------
+
+/// Presses of one binding closer together than this are one press; some
+/// keyboards bounce
+const PRESS_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// One config's hotkeys and what they run.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyBinding {
+    /// The config's id, echoed back in `hotkey-triggered`
+    pub id: String,
+    pub label: Option<String>,
+    pub settings: AutoInputSettings,
+    pub hotkeys: HotkeySettings,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum HotkeyAction {
+    Start,
+    Stop,
+    Toggle,
+    Hold,
+}
+
+/// Payload of `hotkey-triggered`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HotkeyTriggered {
+    id: String,
+    action: HotkeyAction,
+    /// Whether a run for `id` is going afterwards
+    started: bool,
+    error: Option<String>,
+}
+
+/// A hotkey that couldn't be registered: not a valid shortcut, or already
+/// held by another program.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyConflict {
+    pub hotkey: String,
+    pub error: String,
+}
+
+#[derive(Default)]
+pub struct HotkeyState {
+    registered: Vec<Shortcut>,
+    /// Config the hotkeys last started, so its toggle stops it while another
+    /// config's toggle switches over
+    running: Option<String>,
+    hook_listeners: Vec<ListenerGuard>,
+}
+
+fn lock_hotkeys(state: &Mutex<HotkeyState>) -> std::sync::MutexGuard<'_, HotkeyState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn trigger(app: &AppHandle, binding: &HotkeyBinding, action: HotkeyAction) {
+    let running = lock_hotkeys(&app.state()).running.clone();
+    let active = engine::current(app).is_active();
+    let start = match action {
+        HotkeyAction::Start | HotkeyAction::Hold => true,
+        HotkeyAction::Stop => false,
+        HotkeyAction::Toggle => !(active && running.as_deref() == Some(binding.id.as_str())),
+    };
+    run(app, binding, action, start);
+}
+
+/// A hold key went down or came back up. Letting go leaves the run alone
+/// unless this binding was the last to start one from a hotkey.
+fn hold(app: &AppHandle, binding: &HotkeyBinding, down: bool) {
+    let running = lock_hotkeys(&app.state()).running.clone();
+    if !down && running.as_deref() != Some(binding.id.as_str()) {
+        return;
+    }
+    run(app, binding, HotkeyAction::Hold, down);
+}
+
+fn run(app: &AppHandle, binding: &HotkeyBinding, action: HotkeyAction, start: bool) {
+    // A run already going would make the start a no-op
+    if engine::current(app).is_active() {
+        let _ = stop_action(app.clone());
+    }
+    let error = if start {
+        start_action(app.clone(), binding.settings.clone(), binding.label.clone()).err()
+    } else {
+        None
+    };
+    let started = start && error.is_none();
+    lock_hotkeys(&app.state()).running = started.then(|| binding.id.clone());
+
+    let _ = app.emit(
+        "hotkey-triggered",
+        HotkeyTriggered {
+            id: binding.id.clone(),
+            action,
+            started,
+            error,
+        },
+    );
+}
+
+/// A physical input the hooks watch for, for bindings the plugin can't take.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HookInput {
+    Key(u16),
+    Mouse(MouseButton),
+}
+
+/// "Mouse4"/"XButton1", "Mouse5"/"XButton2" and "Mouse3"/"MiddleClick" name
+/// the mouse buttons that can be bound; the left and right buttons can't.
+fn mouse_button(name: &str) -> Option<MouseButton> {
+    match name.trim().to_lowercase().as_str() {
+        "mouse4" | "xbutton1" | "mousex1" => Some(MouseButton::X1),
+        "mouse5" | "xbutton2" | "mousex2" => Some(MouseButton::X2),
+        "mouse3" | "middleclick" | "mousemiddle" => Some(MouseButton::Middle),
+        _ => None,
+    }
+}
+
+/// A bound action, by its index in the list handed to `spawn_dispatch`,
+/// and whether the input just went down.
+type Press = (usize, bool);
+
+/// Runs the presses for `bound` one after another on their own thread.
+/// Neither the plugin's callbacks (on the main thread) nor the hook
+/// callbacks can wait on a run starting or stopping. Each binding is
+/// debounced on its own, so two hotkeys pressed together both count. Lives
+/// as long as something can still send.
+fn spawn_dispatch(
+    app: AppHandle,
+    bound: Vec<(HotkeyAction, HotkeyBinding)>,
+    presses: mpsc::Receiver<Press>,
+) {
+    thread::spawn(move || {
+        let mut last_press: Vec<Option<Instant>> = vec![None; bound.len()];
+        for (index, down) in presses {
+            let (action, binding) = &bound[index];
+            match action {
+                HotkeyAction::Hold => hold(&app, binding, down),
+                _ if down => {
+                    let now = Instant::now();
+                    if last_press[index].is_some_and(|at| now - at < PRESS_DEBOUNCE) {
+                        continue;
+                    }
+                    last_press[index] = Some(now);
+                    trigger(&app, binding, *action);
+                }
+                _ => {}
+            }
+        }
+    });
+}
+
+/// Listens for the hook bindings: hold keys, and mouse buttons for any
+/// action, each with the index of its bound action. Needs the hook hotkeys
+/// permission.
+fn listen_hooks(
+    app: &AppHandle,
+    hooked: Vec<(HookInput, usize)>,
+    presses: Sender<Press>,
+) -> Result<Vec<ListenerGuard>, String> {
+    // Physically held inputs, so OS auto-repeat doesn't restart the run
+    let held = Mutex::new(HashSet::new());
+    let wants_keys = hooked.iter().any(|(i, _)| matches!(i, HookInput::Key(_)));
+    let wants_mouse = hooked.iter().any(|(i, _)| matches!(i, HookInput::Mouse(_)));
+    let on_input = move |input: HookInput, down: bool| {
+        let Some(&(_, index)) = hooked.iter().find(|(i, _)| *i == input) else {
+            return;
+        };
+        let mut held = held.lock().unwrap_or_else(|e| e.into_inner());
+        let changed = if down {
+            held.insert(index)
+        } else {
+            held.remove(&index)
+        };
+        if changed {
+            let _ = presses.send((index, down));
+        }
+    };
+    let on_input = Arc::new(on_input);
+
+    // A run sending the same input mustn't stop itself, hence only physical
+    // events. The mouse hook sees every move, so it's only installed if a
+    // button is bound. Nothing is swallowed: a bound key or side button is
+    // often one the game or app uses too (holding E both runs the config
+    // and interacts), and swallowing would take it away everywhere
+    let mut listeners = Vec::new();
+    if wants_keys {
+        let on_input = Arc::clone(&on_input);
+        listeners.push(permissions::add_key_listener(
+            app,
+            Feature::HookHotkeys,
+            move |event| {
+                if !event.injected {
+                    on_input(HookInput::Key(event.vk), event.down);
+                }
+                false
+            },
+        )?);
+    }
+    if wants_mouse {
+        listeners.push(permissions::add_mouse_listener(
+            app,
+            Feature::HookHotkeys,
+            move |event| {
+                let (button, down) = match event.kind {
+                    MouseKind::Down(button) => (button, true),
+                    MouseKind::Up(button) => (button, false),
+                    _ => return false,
+                };
+                if !event.injected {
+                    on_input(HookInput::Mouse(button), down);
+                }
+                false
+            },
+        )?);
+    }
+    Ok(listeners)
+}
+
+/// Stops listening for hold keys and mouse-button hotkeys, e.g. when their
+/// permission is revoked. Plugin hotkeys stay registered.
+pub fn drop_hook_listeners(app: &AppHandle) {
+    lock_hotkeys(&app.state()).hook_listeners.clear();
+}
+
+/// Unregisters what `register_hotkeys` registered. The plugin waits on the
+/// main thread, so it isn't called with the state lock held.
+fn unregister_all(app: &AppHandle) {
+    let state = app.state::<Mutex<HotkeyState>>();
+    let registered = {
+        let mut hotkeys = lock_hotkeys(&state);
+        hotkeys.hook_listeners.clear();
+        std::mem::take(&mut hotkeys.registered)
+    };
+    for shortcut in registered {
+        let _ = app.global_shortcut().unregister(shortcut);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+/// Registers every binding's start/stop/toggle hotkeys, replacing whatever
+/// was registered before. A key bound more than once goes to its first use.
+/// Returns the hotkeys that couldn't be registered; the rest still are.
+/// Hold keys are single keys from the key table, without modifiers; other
+/// names come back as conflicts. Any of them can instead be a mouse button:
+/// "Mouse3" (middle), "Mouse4" or "Mouse5". Both kinds still reach the
+/// focused app, and need the hook hotkeys permission; without it they come
+/// back as conflicts.
+/// Async so it's off the main thread the plugin waits on. Presses are
+/// handled on a thread of their own, since stopping a run waits for it to
+/// release its inputs.
+#[tauri::command]
+pub async fn register_hotkeys(
+    app: AppHandle,
+    bindings: Vec<HotkeyBinding>,
+) -> Result<Vec<HotkeyConflict>, String> {
+    if safe_mode() {
+        return Err("Hotkeys are disabled in safe mode".into());
+    }
+    unregister_all(&app);
+
+    let (presses, queued) = mpsc::channel::<Press>();
+    let mut bound = Vec::new();
+    let mut registered = Vec::new();
+    let mut conflicts = Vec::new();
+    let mut hooked: Vec<(HookInput, usize)> = Vec::new();
+    let mut hooked_keys = Vec::new();
+    for binding in bindings {
+        let HotkeySettings {
+            start,
+            stop,
+            toggle,
+            hold,
+        } = binding.hotkeys.clone();
+        for (key, action) in [
+            (start, HotkeyAction::Start),
+            (stop, HotkeyAction::Stop),
+            (toggle, HotkeyAction::Toggle),
+            (hold, HotkeyAction::Hold),
+        ] {
+            let Some(key) = key.filter(|k| !k.trim().is_empty()) else {
+                continue;
+            };
+            let input = match (mouse_button(&key), action) {
+                (Some(button), _) => Some(HookInput::Mouse(button)),
+                (None, HotkeyAction::Hold) => match keys::parse(&key) {
+                    Ok(vk) => Some(HookInput::Key(vk)),
+                    Err(error) => {
+                        conflicts.push(HotkeyConflict { hotkey: key, error });
+                        continue;
+                    }
+                },
+                (None, _) => None,
+            };
+            if let Some(input) = input {
+                if !hooked.iter().any(|(i, _)| *i == input) {
+                    hooked.push((input, bound.len()));
+                    hooked_keys.push(key);
+                    bound.push((action, binding.clone()));
+                }
+                continue;
+            }
+            let shortcut = match key.parse::<Shortcut>() {
+                Ok(shortcut) => shortcut,
+                Err(e) => {
+                    conflicts.push(HotkeyConflict {
+                        hotkey: key,
+                        error: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+            if registered.contains(&shortcut) {
+                continue;
+            }
+            let index = bound.len();
+            let sender = presses.clone();
+            let result = app
+                .global_shortcut()
+                .on_shortcut(shortcut, move |_, _, event| {
+                    if event.state == ShortcutState::Pressed {
+                        let _ = sender.send((index, true));
+                    }
+                });
+            match result {
+                Ok(()) => {
+                    registered.push(shortcut);
+                    bound.push((action, binding.clone()));
+                }
+                Err(e) => conflicts.push(HotkeyConflict {
+                    hotkey: key,
+                    error: e.to_string(),
+                }),
+            }
+        }
+    }
+    spawn_dispatch(app.clone(), bound, queued);
+    let hook_listeners = if hooked.is_empty() {
+        Vec::new()
+    } else {
+        listen_hooks(&app, hooked, presses).unwrap_or_else(|error| {
+            conflicts.extend(hooked_keys.into_iter().map(|hotkey| HotkeyConflict {
+                hotkey,
+                error: error.clone(),
+            }));
+            Vec::new()
+        })
+    };
+    let state = app.state::<Mutex<HotkeyState>>();
+    let mut hotkeys = lock_hotkeys(&state);
+    hotkeys.registered = registered;
+    hotkeys.hook_listeners = hook_listeners;
+    Ok(conflicts)
+}
+
+#[tauri::command]
+pub async fn unregister_hotkeys(app: AppHandle) {
+    unregister_all(&app);
+}
@@ -0,0 +1,212 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{
+    engine, hooks, release_held_inputs, safe_mode, sleep_unless_stopped, start_worker,
+    stop_worker_for, AutoInputSettings,
+};
+
+// ---------------------------------------------------------------------------
+// Idle-triggered runs
+//
+// Starts a run once the user has been away for a while and stops it the
+// moment they're back. Idleness comes from GetLastInputInfo, which injected
+// input resets too, so while our run is going the user coming back is spotted
+// through the hooks instead, which can tell physical input from ours.
+// ---------------------------------------------------------------------------
+
+const IDLE_POLL: Duration = Duration::from_secs(1);
+/// How quickly a triggered run notices the user is back
+const RETURN_POLL: Duration = Duration::from_millis(50);
+
+#[derive(Default)]
+pub struct IdleTriggerState {
+    cancel: Option<Arc<AtomicBool>>,
+    info: Option<IdleTriggerInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdleTriggerInfo {
+    pub idle_minutes: u64,
+}
+
+fn lock_trigger(state: &Mutex<IdleTriggerState>) -> std::sync::MutexGuard<'_, IdleTriggerState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Whether the idle trigger will start a run.
+pub fn is_armed(app: &AppHandle) -> bool {
+    lock_trigger(&app.state::<Mutex<IdleTriggerState>>())
+        .info
+        .is_some()
+}
+
+/// Runs one triggered run from start to the user coming back (or the run
+/// ending on its own). Returns false if the trigger was cancelled.
+fn run_until_return(app: &AppHandle, settings: &AutoInputSettings, cancel: &AtomicBool) -> bool {
+    let back = Arc::new(AtomicBool::new(false));
+    let _keys = {
+        let back = Arc::clone(&back);
+        hooks::add_key_listener(move |event| {
+            if !event.injected {
+                back.store(true, Ordering::Release);
+            }
+            false
+        })
+    };
+    let _mouse = {
+        let back = Arc::clone(&back);
+        hooks::add_mouse_listener(move |event| {
+            if !event.injected {
+                back.store(true, Ordering::Release);
+            }
+            false
+        })
+    };
+
+    if let Err(error) = start_worker(app, settings.clone()) {
+        let _ = app.emit("idle-trigger-failed", error);
+        return true;
+    }
+    let _ = app.emit("idle-started", ());
+    loop {
+        if back.load(Ordering::Acquire) {
+            stop_worker_for(app, "user-active");
+            release_held_inputs();
+            return true;
+        }
+        if !engine::current(app).is_active() {
+            return true;
+        }
+        if !sleep_unless_stopped(RETURN_POLL, cancel) {
+            return false;
+        }
+    }
+}
+
+fn spawn_trigger(
+    app: AppHandle,
+    settings: AutoInputSettings,
+    idle_after: Duration,
+    cancel: Arc<AtomicBool>,
+) {
+    thread::spawn(move || {
+        // Once per stretch of idleness. A start that failed isn't retried
+        // until the user has been back; a run that ended on its own reset
+        // the idle time with its input, so it starts again after another
+        // full idle period
+        let mut fired = false;
+        loop {
+            let idle = imp::idle_for() >= idle_after;
+            fired &= idle;
+            // Someone else's run isn't ours to stop when the user returns
+            if idle && !fired && !engine::current(&app).is_active() {
+                fired = true;
+                if !run_until_return(&app, &settings, &cancel) {
+                    return;
+                }
+            }
+            if !sleep_unless_stopped(IDLE_POLL, &cancel) {
+                return;
+            }
+        }
+    });
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use std::time::Duration;
+
+    use windows::Win32::System::SystemInformation::GetTickCount;
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    /// Time since the last input of any kind, injected included.
+    pub fn idle_for() -> Duration {
+        unsafe {
+            let mut info = LASTINPUTINFO {
+                cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+                dwTime: 0,
+            };
+            if !GetLastInputInfo(&mut info).as_bool() {
+                return Duration::ZERO;
+            }
+            // Both wrap after 49.7 days, which the wrapping subtraction absorbs
+            Duration::from_millis(GetTickCount().wrapping_sub(info.dwTime) as u64)
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod imp {
+    use std::time::Duration;
+
+    pub fn idle_for() -> Duration {
+        Duration::ZERO
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+/// Starts `settings` whenever the user has been inactive for `idle_minutes`,
+/// stopping it as soon as they touch the mouse or keyboard. Replaces any
+/// existing trigger.
+#[tauri::command]
+pub fn arm_idle_trigger(
+    app: AppHandle,
+    state: tauri::State<'_, Mutex<IdleTriggerState>>,
+    settings: AutoInputSettings,
+    idle_minutes: u64,
+) -> Result<IdleTriggerInfo, String> {
+    if safe_mode() {
+        return Err("Idle triggers are disabled in safe mode".into());
+    }
+    if idle_minutes == 0 {
+        return Err("Idle time must be at least a minute".into());
+    }
+    let info = IdleTriggerInfo { idle_minutes };
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let mut trigger = lock_trigger(&state);
+        if let Some(old) = trigger.cancel.replace(Arc::clone(&cancel)) {
+            old.store(true, Ordering::Release);
+        }
+        trigger.info = Some(info.clone());
+    }
+    engine::schedule_changed(&app);
+    spawn_trigger(
+        app,
+        settings,
+        Duration::from_secs(idle_minutes * 60),
+        cancel,
+    );
+    Ok(info)
+}
+
+/// Stops watching for idleness. A triggered run keeps going.
+#[tauri::command]
+pub fn disarm_idle_trigger(app: AppHandle, state: tauri::State<'_, Mutex<IdleTriggerState>>) {
+    {
+        let mut trigger = lock_trigger(&state);
+        if let Some(cancel) = trigger.cancel.take() {
+            cancel.store(true, Ordering::Release);
+        }
+        trigger.info = None;
+    }
+    engine::schedule_changed(&app);
+}
+
+#[tauri::command]
+pub fn get_idle_trigger(
+    state: tauri::State<'_, Mutex<IdleTriggerState>>,
+) -> Option<IdleTriggerInfo> {
+    lock_trigger(&state).info.clone()
+}
@@ -0,0 +1,115 @@
+// ---------------------------------------------------------------------------
+// Key names
+//
+// The names the key capture field produces ("E", "Space", "Up", "PageUp",
+// "F13"), plus a few common spellings, mapped to Windows virtual-key codes.
+// Punctuation follows the US layout. Anything else is refused rather than
+// guessed at, so a binding never quietly lands on the wrong key.
+// ---------------------------------------------------------------------------
+
+/// Virtual-key code for a key name, case-insensitively; None if unknown.
+pub fn vk_from_name(name: &str) -> Option<u16> {
+    let name = name.trim().to_lowercase();
+    if let [c] = name.as_bytes() {
+        return match c {
+            b'a'..=b'z' | b'0'..=b'9' => Some(c.to_ascii_uppercase() as u16),
+            b';' => Some(0xBA),
+            b'=' => Some(0xBB),
+            b',' => Some(0xBC),
+            b'-' => Some(0xBD),
+            b'.' => Some(0xBE),
+            b'/' => Some(0xBF),
+            b'`' => Some(0xC0),
+            b'[' => Some(0xDB),
+            b'\\' => Some(0xDC),
+            b']' => Some(0xDD),
+            b'\'' => Some(0xDE),
+            _ => None,
+        };
+    }
+    if let Some(n) = name.strip_prefix('f').and_then(|n| n.parse::<u16>().ok()) {
+        return (1..=24).contains(&n).then(|| 0x70 + n - 1);
+    }
+    if let Some(n) = name
+        .strip_prefix("numpad")
+        .and_then(|n| n.parse::<u16>().ok())
+    {
+        return (n <= 9).then(|| 0x60 + n);
+    }
+    let vk = match name.as_str() {
+        "backspace" => 0x08,
+        "tab" => 0x09,
+        "enter" | "return" => 0x0D,
+        "shift" => 0x10,
+        "ctrl" | "control" => 0x11,
+        "alt" | "menu" => 0x12,
+        "pause" => 0x13,
+        "capslock" => 0x14,
+        "escape" | "esc" => 0x1B,
+        "space" | "spacebar" => 0x20,
+        "pageup" | "pgup" => 0x21,
+        "pagedown" | "pgdn" => 0x22,
+        "end" => 0x23,
+        "home" => 0x24,
+        "left" | "arrowleft" => 0x25,
+        "up" | "arrowup" => 0x26,
+        "right" | "arrowright" => 0x27,
+        "down" | "arrowdown" => 0x28,
+        "printscreen" | "prtsc" => 0x2C,
+        "insert" | "ins" => 0x2D,
+        "delete" | "del" => 0x2E,
+        "cmd" | "meta" | "win" => 0x5B,
+        "contextmenu" | "apps" => 0x5D,
+        "multiply" => 0x6A,
+        "add" => 0x6B,
+        "subtract" => 0x6D,
+        "decimal" => 0x6E,
+        "divide" => 0x6F,
+        "numlock" => 0x90,
+        "scrolllock" | "scroll" => 0x91,
+        "lshift" => 0xA0,
+        "rshift" => 0xA1,
+        "lctrl" => 0xA2,
+        "rctrl" => 0xA3,
+        "lalt" => 0xA4,
+        "ralt" => 0xA5,
+        _ => return None,
+    };
+    Some(vk)
+}
+
+/// Like `vk_from_name`, with an error naming the key for the UI.
+pub fn parse(name: &str) -> Result<u16, String> {
+    vk_from_name(name).ok_or_else(|| format!("Unknown key \"{}\"", name.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_from_the_key_capture_field() {
+        assert_eq!(vk_from_name("E"), Some(0x45));
+        assert_eq!(vk_from_name("7"), Some(0x37));
+        assert_eq!(vk_from_name("Space"), Some(0x20));
+        assert_eq!(vk_from_name("Up"), Some(0x26));
+        assert_eq!(vk_from_name("Home"), Some(0x24));
+        assert_eq!(vk_from_name("PageUp"), Some(0x21));
+        assert_eq!(vk_from_name("F1"), Some(0x70));
+        assert_eq!(vk_from_name("F13"), Some(0x7C));
+        assert_eq!(vk_from_name("CapsLock"), Some(0x14));
+        assert_eq!(vk_from_name("ScrollLock"), Some(0x91));
+        assert_eq!(vk_from_name(" ctrl "), Some(0x11));
+        assert_eq!(vk_from_name("-"), Some(0xBD));
+    }
+
+    #[test]
+    fn unknown_names_are_refused_not_guessed() {
+        for name in [
+            "", "Ctrl+E", "F0", "F25", "Numpad10", "Hyper", "!", "Mouse4",
+        ] {
+            assert_eq!(vk_from_name(name), None, "{name}");
+        }
+        assert_eq!(parse("Ctrl+E"), Err("Unknown key \"Ctrl+E\"".into()));
+    }
+}
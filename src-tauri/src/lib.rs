@@ -1,48 +1,241 @@
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
-use std::thread::{self, JoinHandle};
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use serde::{Deserialize, Serialize};
-use tauri::menu::{MenuBuilder, MenuItemBuilder};
+use tauri::menu::{Menu, MenuBuilder, MenuItemBuilder};
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Emitter, Manager, RunEvent, WindowEvent};
+
+use engine::EngineState;
+
+mod alerts;
+mod anti_afk;
+mod capture;
+mod checkpoint;
+mod compat;
+mod conditions;
+mod diagnostics;
+mod engine;
+mod expander;
+mod foreground;
+#[cfg(all(feature = "dev-harness", not(target_os = "windows")))]
+mod harness;
+mod hooks;
+mod hot_corner;
+mod hotkeys;
+mod idle_trigger;
+mod keys;
+mod library;
+mod limits;
+mod monitors;
+mod ocr;
+mod overlay;
+mod permissions;
+mod picker;
+mod process_watch;
+mod remap;
+mod scheduler;
+mod storage;
+mod system_events;
+mod usage;
+mod vision;
+mod window;
 
 // ---------------------------------------------------------------------------
 // Win32 input module — only compiled on Windows
 // ---------------------------------------------------------------------------
 
+/// Injected events since the last check, and how many Windows refused
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SendStats {
+    pub sent: u64,
+    pub rejected: u64,
+    /// Win32 error code from the last refusal
+    pub last_error: u32,
+}
+
+impl SendStats {
+    fn reason(&self) -> String {
+        match self.last_error {
+            // ERROR_ACCESS_DENIED
+            5 => "Blocked by Windows: the target app may be running as administrator".into(),
+            0 => "Input was blocked by another program or by Windows".into(),
+            code => format!("Input was rejected by Windows (error {code})"),
+        }
+    }
+}
+
+/// Mouse button to inject, resolved from its settings name once per run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClickButton {
+    Left,
+    Right,
+    Middle,
+}
+
+impl ClickButton {
+    pub fn parse(name: &str) -> Self {
+        match name {
+            "right" => Self::Right,
+            "middle" => Self::Middle,
+            _ => Self::Left,
+        }
+    }
+}
+
+// `--safe-mode` starts with hotkeys, schedules, text expansions and key
+// remaps off, to recover from a profile that starts clicking at launch
+const SAFE_MODE_ARG: &str = "--safe-mode";
+
+static SAFE_MODE: OnceLock<bool> = OnceLock::new();
+
+pub fn safe_mode() -> bool {
+    *SAFE_MODE.get_or_init(|| std::env::args().skip(1).any(|arg| arg == SAFE_MODE_ARG))
+}
+
+// Every injected event carries this in dwExtraInfo, so hooks (ours or other
+// programs') can tell AutoInput's input from other injected input. ASCII "AUIN".
+const DEFAULT_INJECTION_SIGNATURE: usize = 0x4155_494E;
+const INJECTION_SIGNATURE_KEY: &str = "injectionSignature";
+
+static INJECTION_SIGNATURE: AtomicUsize = AtomicUsize::new(DEFAULT_INJECTION_SIGNATURE);
+
+pub fn injection_signature() -> usize {
+    INJECTION_SIGNATURE.load(Ordering::Relaxed)
+}
+
+/// Whether a hooked event with this dwExtraInfo was injected by us.
+pub fn is_own_event(extra_info: usize) -> bool {
+    extra_info == injection_signature()
+}
+
+/// Reads a custom signature from the store, if one was set.
+fn load_injection_signature(app: &AppHandle) {
+    let state = app.state::<Mutex<storage::Storage>>();
+    let custom = storage::lock_storage(&state)
+        .read_store()
+        .get(INJECTION_SIGNATURE_KEY)
+        .and_then(|v| v.as_u64())
+        .filter(|&v| v != 0);
+    if let Some(value) = custom {
+        INJECTION_SIGNATURE.store(value as usize, Ordering::Relaxed);
+    }
+}
+
 #[cfg(target_os = "windows")]
 mod win_input {
+    use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+    use windows::core::{w, HSTRING, PCWSTR, PWSTR};
+
+    use windows::Win32::Foundation::{CloseHandle, GetLastError, HANDLE, POINT};
+    use windows::Win32::Security::{
+        GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY,
+    };
+    use windows::Win32::System::Diagnostics::Debug::MessageBeep;
+    use windows::Win32::System::Power::{
+        SetThreadExecutionState, ES_CONTINUOUS, ES_DISPLAY_REQUIRED, ES_SYSTEM_REQUIRED,
+    };
+    use windows::Win32::System::StationsAndDesktops::{
+        CloseDesktop, GetUserObjectInformationW, OpenInputDesktop, DESKTOP_CONTROL_FLAGS,
+        DESKTOP_READOBJECTS, UOI_NAME,
+    };
+    use windows::Win32::System::Threading::{
+        GetCurrentProcess, GetCurrentThread, OpenProcess, OpenProcessToken,
+        QueryFullProcessImageNameW, SetThreadPriority, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION, THREAD_PRIORITY_NORMAL, THREAD_PRIORITY_TIME_CRITICAL,
+    };
     use windows::Win32::UI::Input::KeyboardAndMouse::{
-        SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, INPUT_MOUSE, KEYBDINPUT, KEYEVENTF_KEYUP,
-        KEYEVENTF_SCANCODE, MOUSEEVENTF_ABSOLUTE, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP,
-        MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP, MOUSEEVENTF_MOVE, MOUSEEVENTF_RIGHTDOWN,
-        MOUSEEVENTF_RIGHTUP, MOUSEINPUT, VIRTUAL_KEY, VK_BACK, VK_CONTROL, VK_DELETE, VK_ESCAPE,
-        VK_F1, VK_F10, VK_F11, VK_F12, VK_F2, VK_F3, VK_F4, VK_F5, VK_F6, VK_F7, VK_F8, VK_F9,
-        VK_MENU, VK_RETURN, VK_SHIFT, VK_SPACE, VK_TAB,
+        BlockInput, GetAsyncKeyState, SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, INPUT_MOUSE,
+        KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP, KEYEVENTF_SCANCODE, KEYEVENTF_UNICODE,
+        MOUSEEVENTF_ABSOLUTE, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, MOUSEEVENTF_MIDDLEDOWN,
+        MOUSEEVENTF_MIDDLEUP, MOUSEEVENTF_MOVE, MOUSEEVENTF_MOVE_NOCOALESCE, MOUSEEVENTF_RIGHTDOWN,
+        MOUSEEVENTF_RIGHTUP, MOUSEEVENTF_VIRTUALDESK, MOUSEINPUT, MOUSE_EVENT_FLAGS, VK_LCONTROL,
+        VK_LMENU, VK_LSHIFT, VK_RCONTROL, VK_RETURN, VK_RMENU, VK_RSHIFT,
     };
+    use windows::Win32::UI::Input::XboxController::{XInputGetState, XINPUT_STATE};
+    use windows::Win32::UI::Shell::ShellExecuteW;
     use windows::Win32::UI::WindowsAndMessaging::GetSystemMetrics;
-    use windows::Win32::UI::WindowsAndMessaging::{SM_CXSCREEN, SM_CYSCREEN};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetCursorPos, GetForegroundWindow, GetWindowThreadProcessId, MB_ICONEXCLAMATION,
+        SM_CXSCREEN, SM_CXVIRTUALSCREEN, SM_CYSCREEN, SM_CYVIRTUALSCREEN, SM_REMOTESESSION,
+        SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN, SW_SHOWNORMAL,
+    };
 
-    fn send(inputs: &[INPUT]) {
-        unsafe {
-            SendInput(inputs, std::mem::size_of::<INPUT>() as i32);
+    pub use windows::Win32::UI::Input::KeyboardAndMouse::VIRTUAL_KEY;
+
+    use super::hooks::TEST_SINK_EXTRA_INFO;
+    use super::{injection_signature, keys, ClickButton, SendStats};
+
+    static SENT: AtomicU64 = AtomicU64::new(0);
+    static REJECTED: AtomicU64 = AtomicU64::new(0);
+    static LAST_ERROR: AtomicU32 = AtomicU32::new(0);
+
+    // Stamps the injection signature into the caller's inputs in place, so
+    // sending stays allocation-free
+    fn send(inputs: &mut [INPUT]) {
+        send_marked(inputs, injection_signature());
+    }
+
+    fn send_marked(inputs: &mut [INPUT], signature: usize) {
+        for input in inputs.iter_mut() {
+            unsafe {
+                match input.r#type {
+                    INPUT_MOUSE => input.Anonymous.mi.dwExtraInfo = signature,
+                    INPUT_KEYBOARD => input.Anonymous.ki.dwExtraInfo = signature,
+                    _ => {}
+                }
+            }
+        }
+        let accepted = unsafe { SendInput(inputs, std::mem::size_of::<INPUT>() as i32) };
+        SENT.fetch_add(inputs.len() as u64, Ordering::Relaxed);
+        let rejected = inputs.len() as u64 - accepted as u64;
+        if rejected > 0 {
+            REJECTED.fetch_add(rejected, Ordering::Relaxed);
+            LAST_ERROR.store(unsafe { GetLastError() }.0, Ordering::Relaxed);
         }
     }
 
-    // Absolute mouse coordinates use 0-65535 normalized range
-    fn normalize_coords(x: i32, y: i32) -> (i32, i32) {
+    // Counts since the last call, for noticing input that Windows refuses
+    pub fn take_send_stats() -> SendStats {
+        SendStats {
+            sent: SENT.swap(0, Ordering::Relaxed),
+            rejected: REJECTED.swap(0, Ordering::Relaxed),
+            last_error: LAST_ERROR.swap(0, Ordering::Relaxed),
+        }
+    }
+
+    // Absolute mouse coordinates use 0-65535 normalized range. With
+    // MOUSEEVENTF_VIRTUALDESK that range spans every monitor, whose origin
+    // can be negative when one sits left of or above the primary.
+    pub fn normalize_coords(x: i32, y: i32) -> (i32, i32) {
         unsafe {
-            let cx = GetSystemMetrics(SM_CXSCREEN);
-            let cy = GetSystemMetrics(SM_CYSCREEN);
+            let vx = GetSystemMetrics(SM_XVIRTUALSCREEN);
+            let vy = GetSystemMetrics(SM_YVIRTUALSCREEN);
+            let cx = GetSystemMetrics(SM_CXVIRTUALSCREEN) as i64;
+            let cy = GetSystemMetrics(SM_CYVIRTUALSCREEN) as i64;
             if cx == 0 || cy == 0 {
                 return (0, 0);
             }
-            let nx = (x * 65535 + cx / 2) / cx;
-            let ny = (y * 65535 + cy / 2) / cy;
-            (nx, ny)
+            // i64: a wide desktop times 65535 overflows i32
+            let nx = ((x - vx) as i64 * 65535 + cx / 2) / cx;
+            let ny = ((y - vy) as i64 * 65535 + cy / 2) / cy;
+            (nx as i32, ny as i32)
+        }
+    }
+
+    pub fn screen_size() -> (i32, i32) {
+        unsafe { (GetSystemMetrics(SM_CXSCREEN), GetSystemMetrics(SM_CYSCREEN)) }
+    }
+
+    pub fn cursor_pos() -> (i32, i32) {
+        let mut pt = POINT::default();
+        unsafe {
+            let _ = GetCursorPos(&mut pt);
         }
+        (pt.x, pt.y)
     }
 
     pub fn move_mouse_abs(x: i32, y: i32) {
@@ -53,21 +246,26 @@ mod win_input {
                 mi: MOUSEINPUT {
                     dx: nx,
                     dy: ny,
-                    dwFlags: MOUSEEVENTF_MOVE | MOUSEEVENTF_ABSOLUTE,
+                    dwFlags: MOUSEEVENTF_MOVE | MOUSEEVENTF_ABSOLUTE | MOUSEEVENTF_VIRTUALDESK,
                     ..Default::default()
                 },
             },
         };
-        send(&[input]);
+        send(&mut [input]);
     }
 
-    pub fn mouse_click(button: &str) {
-        let (down, up) = match button {
-            "right" => (MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP),
-            "middle" => (MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP),
-            _ => (MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP),
-        };
-        let inputs = [
+    // Down and up flags for a button
+    fn button_flags(button: ClickButton) -> (MOUSE_EVENT_FLAGS, MOUSE_EVENT_FLAGS) {
+        match button {
+            ClickButton::Left => (MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP),
+            ClickButton::Right => (MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP),
+            ClickButton::Middle => (MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP),
+        }
+    }
+
+    pub fn mouse_click(button: ClickButton) {
+        let (down, up) = button_flags(button);
+        let mut inputs = [
             INPUT {
                 r#type: INPUT_MOUSE,
                 Anonymous: INPUT_0 {
@@ -87,30 +285,32 @@ mod win_input {
                 },
             },
         ];
-        send(&inputs);
+        send(&mut inputs);
     }
 
-    pub fn move_mouse_rel(dx: i32, dy: i32) {
+    // `no_coalesce` keeps Windows from merging rapid moves, which would drop
+    // distance at high rates
+    pub fn move_mouse_rel(dx: i32, dy: i32, no_coalesce: bool) {
+        let mut flags = MOUSEEVENTF_MOVE;
+        if no_coalesce {
+            flags |= MOUSEEVENTF_MOVE_NOCOALESCE;
+        }
         let input = INPUT {
             r#type: INPUT_MOUSE,
             Anonymous: INPUT_0 {
                 mi: MOUSEINPUT {
                     dx,
                     dy,
-                    dwFlags: MOUSEEVENTF_MOVE,
+                    dwFlags: flags,
                     ..Default::default()
                 },
             },
         };
-        send(&[input]);
+        send(&mut [input]);
     }
 
-    pub fn mouse_down(button: &str) {
-        let flag = match button {
-            "right" => MOUSEEVENTF_RIGHTDOWN,
-            "middle" => MOUSEEVENTF_MIDDLEDOWN,
-            _ => MOUSEEVENTF_LEFTDOWN,
-        };
+    pub fn mouse_down(button: ClickButton) {
+        let (flag, _) = button_flags(button);
         let input = INPUT {
             r#type: INPUT_MOUSE,
             Anonymous: INPUT_0 {
@@ -120,15 +320,11 @@ mod win_input {
                 },
             },
         };
-        send(&[input]);
+        send(&mut [input]);
     }
 
-    pub fn mouse_up(button: &str) {
-        let flag = match button {
-            "right" => MOUSEEVENTF_RIGHTUP,
-            "middle" => MOUSEEVENTF_MIDDLEUP,
-            _ => MOUSEEVENTF_LEFTUP,
-        };
+    pub fn mouse_up(button: ClickButton) {
+        let (_, flag) = button_flags(button);
         let input = INPUT {
             r#type: INPUT_MOUSE,
             Anonymous: INPUT_0 {
@@ -138,42 +334,19 @@ mod win_input {
                 },
             },
         };
-        send(&[input]);
+        send(&mut [input]);
     }
 
+    // Names outside the key table fall back to their first character. Bindings
+    // that must be exact go through `keys::parse` instead
     pub fn resolve_vk(name: &str) -> VIRTUAL_KEY {
-        match name.to_lowercase().as_str() {
-            "space" | "spacebar" => VK_SPACE,
-            "enter" | "return" => VK_RETURN,
-            "tab" => VK_TAB,
-            "escape" | "esc" => VK_ESCAPE,
-            "shift" => VK_SHIFT,
-            "control" | "ctrl" => VK_CONTROL,
-            "alt" => VK_MENU,
-            "backspace" => VK_BACK,
-            "delete" | "del" => VK_DELETE,
-            "f1" => VK_F1,
-            "f2" => VK_F2,
-            "f3" => VK_F3,
-            "f4" => VK_F4,
-            "f5" => VK_F5,
-            "f6" => VK_F6,
-            "f7" => VK_F7,
-            "f8" => VK_F8,
-            "f9" => VK_F9,
-            "f10" => VK_F10,
-            "f11" => VK_F11,
-            "f12" => VK_F12,
-            other => {
-                // Single character → virtual key code (uppercase ASCII)
-                if let Some(c) = other.chars().next() {
-                    let upper = c.to_ascii_uppercase() as u16;
-                    VIRTUAL_KEY(upper)
-                } else {
-                    VK_SPACE
-                }
-            }
-        }
+        let guess = || {
+            name.trim()
+                .chars()
+                .next()
+                .map_or(0x20, |c| c.to_ascii_uppercase() as u16)
+        };
+        VIRTUAL_KEY(keys::vk_from_name(name).unwrap_or_else(guess))
     }
 
     pub fn key_down(vk: VIRTUAL_KEY) {
@@ -199,7 +372,7 @@ mod win_input {
                 },
             },
         };
-        send(&[input]);
+        send(&mut [input]);
     }
 
     pub fn key_up(vk: VIRTUAL_KEY) {
@@ -213,32 +386,353 @@ mod win_input {
                 },
             },
         };
-        send(&[input]);
+        send(&mut [input]);
     }
 
     pub fn key_press(vk: VIRTUAL_KEY) {
         key_down(vk);
         key_up(vk);
     }
+
+    // Types arbitrary text independent of the keyboard layout. Newlines go
+    // out as Enter since most apps ignore a Unicode LF.
+    pub fn type_text(text: &str) {
+        let key = |vk: VIRTUAL_KEY, scan: u16, flags: KEYBD_EVENT_FLAGS| INPUT {
+            r#type: INPUT_KEYBOARD,
+            Anonymous: INPUT_0 {
+                ki: KEYBDINPUT {
+                    wVk: vk,
+                    wScan: scan,
+                    dwFlags: flags,
+                    ..Default::default()
+                },
+            },
+        };
+        let mut inputs = Vec::with_capacity(text.len() * 2);
+        for c in text.chars() {
+            match c {
+                '\r' => {}
+                '\n' => {
+                    inputs.push(key(VK_RETURN, 0, KEYBD_EVENT_FLAGS(0)));
+                    inputs.push(key(VK_RETURN, 0, KEYEVENTF_KEYUP));
+                }
+                _ => {
+                    let mut units = [0u16; 2];
+                    for &unit in c.encode_utf16(&mut units).iter() {
+                        inputs.push(key(VIRTUAL_KEY(0), unit, KEYEVENTF_UNICODE));
+                        inputs.push(key(
+                            VIRTUAL_KEY(0),
+                            unit,
+                            KEYEVENTF_UNICODE | KEYEVENTF_KEYUP,
+                        ));
+                    }
+                }
+            }
+        }
+        send(&mut inputs);
+    }
+
+    // Zero-distance relative move pair: goes through the same SendInput path as
+    // a click (down + up) without touching any window. Marked so our hooks
+    // don't hand it to listeners.
+    pub fn test_sink_event() {
+        let input = INPUT {
+            r#type: INPUT_MOUSE,
+            Anonymous: INPUT_0 {
+                mi: MOUSEINPUT {
+                    dwFlags: MOUSEEVENTF_MOVE,
+                    ..Default::default()
+                },
+            },
+        };
+        send_marked(&mut [input, input], TEST_SINK_EXTRA_INFO);
+    }
+
+    // Executable file name of the foreground window's process, lowercased
+    // (e.g. "game.exe"). None for protected processes we can't open.
+    pub fn foreground_process_name() -> Option<String> {
+        unsafe {
+            let hwnd = GetForegroundWindow();
+            let mut pid = 0u32;
+            GetWindowThreadProcessId(hwnd, Some(&mut pid));
+            if pid == 0 {
+                return None;
+            }
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+            let mut buf = [0u16; 260];
+            let mut len = buf.len() as u32;
+            let ok = QueryFullProcessImageNameW(
+                process,
+                PROCESS_NAME_WIN32,
+                PWSTR(buf.as_mut_ptr()),
+                &mut len,
+            );
+            let _ = CloseHandle(process);
+            ok.ok()?;
+            let path = String::from_utf16_lossy(&buf[..len as usize]);
+            let name = path.rsplit('\\').next().unwrap_or(&path);
+            Some(name.to_lowercase())
+        }
+    }
+
+    // Shift/Ctrl/Alt keys physically down right now, as a fixed array so
+    // the click loop can poll it without allocating. Win is left out:
+    // lifting it on its own would pop the Start menu.
+    pub fn held_modifiers() -> [Option<VIRTUAL_KEY>; 6] {
+        [
+            VK_LSHIFT,
+            VK_RSHIFT,
+            VK_LCONTROL,
+            VK_RCONTROL,
+            VK_LMENU,
+            VK_RMENU,
+        ]
+        .map(|vk| (unsafe { GetAsyncKeyState(vk.0 as i32) } as u16 & 0x8000 != 0).then_some(vk))
+    }
+
+    // Whether Windows reports the key or mouse button (VK_LBUTTON etc.) down
+    pub fn is_key_down(vk: u16) -> bool {
+        unsafe { GetAsyncKeyState(vk as i32) as u16 & 0x8000 != 0 }
+    }
+
+    // Right stick of the first connected XInput controller
+    pub fn gamepad_right_stick() -> Option<(i16, i16)> {
+        (0..4).find_map(|index| {
+            let mut state = XINPUT_STATE::default();
+            // ERROR_SUCCESS
+            (unsafe { XInputGetState(index, &mut state) } == 0)
+                .then_some((state.Gamepad.sThumbRX, state.Gamepad.sThumbRY))
+        })
+    }
+
+    // Whether input currently goes to the normal "Default" desktop. UAC
+    // prompts, the lock screen and the screensaver switch to a secure desktop
+    // that we can't open (or that has another name), where SendInput is
+    // silently dropped.
+    pub fn on_default_desktop() -> bool {
+        unsafe {
+            let Ok(desk) = OpenInputDesktop(DESKTOP_CONTROL_FLAGS(0), false, DESKTOP_READOBJECTS)
+            else {
+                return false;
+            };
+            let mut buf = [0u16; 32];
+            let mut needed = 0u32;
+            let named = GetUserObjectInformationW(
+                HANDLE(desk.0),
+                UOI_NAME,
+                Some(buf.as_mut_ptr().cast()),
+                std::mem::size_of_val(&buf) as u32,
+                Some(&mut needed),
+            );
+            let _ = CloseDesktop(desk);
+            if named.is_err() {
+                return false;
+            }
+            let len = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+            String::from_utf16_lossy(&buf[..len]).eq_ignore_ascii_case("default")
+        }
+    }
+
+    // Whether this process runs with an elevated (administrator) token. UIPI
+    // drops our input to elevated windows unless it does.
+    pub fn is_elevated() -> bool {
+        unsafe {
+            let mut token = HANDLE::default();
+            if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token).is_err() {
+                return false;
+            }
+            let mut elevation = TOKEN_ELEVATION::default();
+            let mut len = 0u32;
+            let ok = GetTokenInformation(
+                token,
+                TokenElevation,
+                Some((&mut elevation as *mut TOKEN_ELEVATION).cast()),
+                std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+                &mut len,
+            );
+            let _ = CloseHandle(token);
+            ok.is_ok() && elevation.TokenIsElevated != 0
+        }
+    }
+
+    // Starts another copy of this executable, with the same arguments,
+    // through the UAC "runas" verb. Fails if the user declines the prompt.
+    pub fn relaunch_elevated() -> Result<(), String> {
+        let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+        let args = std::env::args()
+            .skip(1)
+            .map(|a| format!("\"{}\"", a.replace('"', "\\\"")))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let result = unsafe {
+            ShellExecuteW(
+                None,
+                w!("runas"),
+                &HSTRING::from(exe.as_path()),
+                &HSTRING::from(args),
+                PCWSTR::null(),
+                SW_SHOWNORMAL,
+            )
+        };
+        // Values above 32 mean success
+        if result.0 as usize <= 32 {
+            return Err("Could not restart as administrator".into());
+        }
+        Ok(())
+    }
+
+    // Physical input stays blocked until the same thread unblocks it or exits
+    pub fn block_input(block: bool) {
+        unsafe {
+            let _ = BlockInput(block);
+        }
+    }
+
+    // Whether this session is displayed over Remote Desktop
+    pub fn is_remote_session() -> bool {
+        unsafe { GetSystemMetrics(SM_REMOTESESSION) != 0 }
+    }
+
+    // The system's warning sound, for monitor-only alerts
+    pub fn alert_sound() {
+        unsafe {
+            let _ = MessageBeep(MB_ICONEXCLAMATION);
+        }
+    }
+
+    // Keeps the machine and display from going idle while the calling thread
+    // runs, until `allow_sleep` or the thread exits
+    pub fn keep_awake() {
+        unsafe {
+            SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED | ES_DISPLAY_REQUIRED);
+        }
+    }
+
+    pub fn allow_sleep() {
+        unsafe {
+            SetThreadExecutionState(ES_CONTINUOUS);
+        }
+    }
+
+    // Applies to the calling thread only, so it ends with the worker
+    pub fn raise_thread_priority() {
+        unsafe {
+            let _ = SetThreadPriority(GetCurrentThread(), THREAD_PRIORITY_TIME_CRITICAL);
+        }
+    }
+
+    pub fn reset_thread_priority() {
+        unsafe {
+            let _ = SetThreadPriority(GetCurrentThread(), THREAD_PRIORITY_NORMAL);
+        }
+    }
 }
 
 // No-op stubs for non-Windows (macOS dev builds)
 #[cfg(not(target_os = "windows"))]
 #[allow(non_camel_case_types, dead_code)]
 mod win_input {
+    use super::ClickButton;
+
     #[derive(Clone, Copy)]
     pub struct VIRTUAL_KEY(pub u16);
-    pub fn move_mouse_abs(_x: i32, _y: i32) {}
-    pub fn move_mouse_rel(_dx: i32, _dy: i32) {}
-    pub fn mouse_click(_button: &str) {}
-    pub fn mouse_down(_button: &str) {}
-    pub fn mouse_up(_button: &str) {}
-    pub fn resolve_vk(_name: &str) -> VIRTUAL_KEY {
-        VIRTUAL_KEY(0)
+
+    /// An input the stub was asked to send, logged under `dev-harness`
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Sent {
+        MoveAbs(i32, i32),
+        MoveRel(i32, i32),
+        Click(ClickButton),
+        Down(ClickButton),
+        Up(ClickButton),
+        KeyDown(u16),
+        KeyUp(u16),
+        Text(String),
+    }
+
+    fn record(event: Sent) {
+        #[cfg(feature = "dev-harness")]
+        crate::harness::record(event);
+        #[cfg(not(feature = "dev-harness"))]
+        let _ = event;
+    }
+
+    pub fn screen_size() -> (i32, i32) {
+        (0, 0)
+    }
+    pub fn normalize_coords(_x: i32, _y: i32) -> (i32, i32) {
+        (0, 0)
+    }
+    pub fn cursor_pos() -> (i32, i32) {
+        (0, 0)
+    }
+    pub fn move_mouse_abs(x: i32, y: i32) {
+        record(Sent::MoveAbs(x, y));
+    }
+    pub fn move_mouse_rel(dx: i32, dy: i32, _no_coalesce: bool) {
+        record(Sent::MoveRel(dx, dy));
+    }
+    pub fn mouse_click(button: ClickButton) {
+        record(Sent::Click(button));
+    }
+    pub fn mouse_down(button: ClickButton) {
+        record(Sent::Down(button));
+    }
+    pub fn mouse_up(button: ClickButton) {
+        record(Sent::Up(button));
+    }
+    /// Single letters and digits map as they do on Windows, which is all
+    /// the harness needs
+    pub fn resolve_vk(name: &str) -> VIRTUAL_KEY {
+        VIRTUAL_KEY(super::keys::vk_from_name(name).unwrap_or(0))
+    }
+    pub fn take_send_stats() -> super::SendStats {
+        super::SendStats::default()
+    }
+    pub fn key_down(vk: VIRTUAL_KEY) {
+        record(Sent::KeyDown(vk.0));
+    }
+    pub fn key_up(vk: VIRTUAL_KEY) {
+        record(Sent::KeyUp(vk.0));
+    }
+    pub fn key_press(vk: VIRTUAL_KEY) {
+        key_down(vk);
+        key_up(vk);
+    }
+    pub fn type_text(text: &str) {
+        record(Sent::Text(text.into()));
+    }
+    pub fn test_sink_event() {}
+    pub fn foreground_process_name() -> Option<String> {
+        None
+    }
+    pub fn gamepad_right_stick() -> Option<(i16, i16)> {
+        None
+    }
+    pub fn held_modifiers() -> [Option<VIRTUAL_KEY>; 6] {
+        [None; 6]
+    }
+    pub fn is_key_down(_vk: u16) -> bool {
+        false
     }
-    pub fn key_down(_vk: VIRTUAL_KEY) {}
-    pub fn key_up(_vk: VIRTUAL_KEY) {}
-    pub fn key_press(_vk: VIRTUAL_KEY) {}
+    pub fn on_default_desktop() -> bool {
+        true
+    }
+    pub fn is_remote_session() -> bool {
+        false
+    }
+    pub fn alert_sound() {}
+    pub fn is_elevated() -> bool {
+        false
+    }
+    pub fn relaunch_elevated() -> Result<(), String> {
+        Err("Elevation is only supported on Windows".into())
+    }
+    pub fn block_input(_block: bool) {}
+    pub fn keep_awake() {}
+    pub fn allow_sleep() {}
+    pub fn raise_thread_priority() {}
+    pub fn reset_thread_priority() {}
 }
 
 // ---------------------------------------------------------------------------
@@ -246,18 +740,26 @@ mod win_input {
 // ---------------------------------------------------------------------------
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct AutoInputSettings {
     pub hours: u64,
     pub minutes: u64,
     pub seconds: u64,
     pub milliseconds: u64,
 
+    /// Tempo-based rate for rhythm games and music software: when non-zero,
+    /// one input per beat divided into `subdivision` (1 = quarter notes,
+    /// 2 = eighths, 3 = triplets, 4 = sixteenths) replaces the interval above
+    pub bpm: u32,
+    pub subdivision: u32,
+
     pub mouse_button: String,
     pub click_type: String,
 
     pub repeat_mode: String,
     pub repeat_count: u64,
+    /// Wall-clock run length for `repeat_mode: "duration"`
+    pub repeat_duration_secs: u64,
 
     pub location_mode: String,
     pub fixed_x: i32,
@@ -271,6 +773,166 @@ pub struct AutoInputSettings {
 
     pub hold_key: String,
     pub key_mode: String,
+
+    pub realtime_priority: bool,
+    pub precision_mode: bool,
+    pub interval_jitter_ms: u64,
+    pub position_jitter_px: u32,
+    pub travel_duration_ms: u64,
+
+    pub micro_pause_enabled: bool,
+    pub micro_pause_min_ms: u64,
+    pub micro_pause_max_ms: u64,
+    pub micro_pause_every_min_secs: u64,
+    pub micro_pause_every_max_secs: u64,
+
+    pub nudge_enabled: bool,
+
+    pub work_minutes: u64,
+    pub rest_minutes: u64,
+
+    /// Countdown before the first input, to switch to the target window
+    pub start_delay_ms: u64,
+
+    /// Force-stop after this long regardless of repeat mode; 0 disables
+    pub max_runtime_minutes: u64,
+
+    /// Ceiling on inputs per second, ignored when `unlimited_cps` is set. The
+    /// default only catches floods from a mistyped interval
+    pub max_cps: u32,
+    pub unlimited_cps: bool,
+
+    /// Abort when the physical cursor is pushed into a screen corner
+    pub failsafe_enabled: bool,
+
+    /// What to do when the user moves the mouse off a fixed click target:
+    /// "pause" until it's left alone, "stop", or "off". Off unless asked
+    /// for, since the UI doesn't offer it yet
+    pub interference_mode: String,
+
+    /// Hold off injecting while the user types, until they've been quiet
+    /// for `typing_quiet_ms`
+    pub pause_while_typing: bool,
+    pub typing_quiet_ms: u64,
+
+    /// `gamepad-mouse`: cursor speed in px/s at full right-stick tilt, and
+    /// the fraction of stick travel ignored around center
+    pub gamepad_speed: f64,
+    pub gamepad_deadzone: f64,
+
+    /// Physically held Shift/Ctrl/Alt during clicks and key taps: "off",
+    /// "release" them around each input, or "wait" until they're let go
+    pub modifier_handling: String,
+
+    /// In fixed-location click mode, move the cursor back to where it was
+    /// after each click so the user can keep working
+    pub restore_cursor: bool,
+
+    /// What to do while the session is remote (RDP): "pause" until it's
+    /// local again, "continue", or "stop"
+    pub remote_session_mode: String,
+
+    /// Block physical keyboard and mouse input for the run, so the user can't
+    /// interleave with it. Needs administrator rights.
+    pub block_input: bool,
+
+    /// Save progress of count-limited runs so they can be resumed after a
+    /// restart or crash
+    pub checkpoint_enabled: bool,
+
+    /// Deliver every relative move (drag-hold, gamepad mouse) instead of
+    /// letting Windows coalesce them, which loses distance at high speeds
+    pub no_coalesce_moves: bool,
+
+    /// Monitor id from `list_monitors` that `fixed_x`/`fixed_y` are relative
+    /// to; empty means global screen pixels
+    pub fixed_monitor: String,
+
+    /// `fixed_x`/`fixed_y` are logical (DPI-scaled) pixels, as captured at
+    /// 100% scaling, converted to physical pixels for their monitor when
+    /// injecting
+    pub logical_coords: bool,
+
+    /// How the fixed target is stored: "pixels" (`fixed_x`/`fixed_y`) or
+    /// "percent" of the monitor's size (`fixed_percent_x`/`fixed_percent_y`),
+    /// which survives resolution changes
+    pub coordinate_mode: String,
+    pub fixed_percent_x: f64,
+    pub fixed_percent_y: f64,
+
+    /// Hours of the day this profile may start in, as "HH:MM"; both empty
+    /// means any time. The window may run past midnight.
+    pub active_from: String,
+    pub active_until: String,
+    /// Starts outside those hours: "refuse", or "queue" until they open
+    pub outside_hours_mode: String,
+
+    /// Hold off injecting until the pixel at `start_color_x`/`start_color_y`
+    /// is within `start_color_tolerance` of `start_color` ("color-match") or
+    /// isn't ("color-mismatch"), until `start_image` is on screen ("image",
+    /// which also pauses whenever the image goes away), or until the text
+    /// region reads as `start_text` ("text"); "off" starts right away
+    pub start_condition: String,
+    pub start_color_x: i32,
+    pub start_color_y: i32,
+    /// `#RRGGBB`
+    pub start_color: String,
+    pub start_color_tolerance: u8,
+
+    /// Stop once the average color of the `stop_color_size`-pixel square
+    /// centered on `stop_color_x`/`stop_color_y` drifts more than
+    /// `stop_color_tolerance` per channel from what it was when injecting
+    /// began
+    pub stop_on_color_change: bool,
+    pub stop_color_x: i32,
+    pub stop_color_y: i32,
+    pub stop_color_size: u32,
+    pub stop_color_tolerance: u8,
+
+    /// Template for `location_mode: "image"`: a PNG, base64 or as a `data:`
+    /// URL. Searched for before every click, which lands on its center
+    pub target_image: String,
+    /// Mean per-pixel difference, 0-1, still counted as the image
+    pub image_tolerance: f64,
+
+    /// Template for `start_condition: "image"`, in the same form as
+    /// `target_image`
+    pub start_image: String,
+    /// Longest wait on the start condition, at the start or while paused on
+    /// it, before the run is stopped with `condition-timeout`; 0 waits
+    /// indefinitely
+    pub condition_timeout_secs: u64,
+
+    /// Screen rectangle read for `start_condition: "text"` and `stop_text`
+    pub text_x: i32,
+    pub text_y: i32,
+    pub text_width: i32,
+    pub text_height: i32,
+    /// Case-insensitive regular expressions matched against the region's
+    /// text; a non-empty `stop_text` stops the run once it matches
+    pub start_text: String,
+    pub stop_text: String,
+
+    /// Only inject while this window has focus, pausing otherwise: a handle
+    /// from `list_windows`, or a case-insensitive title pattern. Empty
+    /// injects whatever has focus
+    pub target_window: String,
+    /// Bring `target_window` to the foreground when injecting begins
+    /// ("start"), and also whenever it loses focus rather than pausing
+    /// ("always"); "off" leaves focus alone
+    pub auto_focus: String,
+
+    /// `anti-afk`: how far the cursor wiggles out and back each round, 0 for
+    /// no wiggle
+    pub anti_afk_wiggle_px: u32,
+    /// `anti-afk`: comma-separated keys, one tapped at random each round
+    pub anti_afk_keys: String,
+
+    /// `monitor`: play the system warning sound with each alert
+    pub monitor_sound: bool,
+    /// Play the warning sound whenever a start or stop condition or a
+    /// process watch fires; `trigger-fired` is emitted either way
+    pub trigger_sound: bool,
 }
 
 impl Default for AutoInputSettings {
@@ -280,10 +942,13 @@ impl Default for AutoInputSettings {
             minutes: 0,
             seconds: 0,
             milliseconds: 20,
+            bpm: 0,
+            subdivision: 1,
             mouse_button: "left".into(),
             click_type: "single".into(),
             repeat_mode: "infinite".into(),
             repeat_count: 10,
+            repeat_duration_secs: 60,
             location_mode: "current".into(),
             fixed_x: 0,
             fixed_y: 0,
@@ -294,6 +959,69 @@ impl Default for AutoInputSettings {
             drag_direction_y: -1.0,
             hold_key: "e".into(),
             key_mode: "hold".into(),
+            realtime_priority: false,
+            precision_mode: false,
+            interval_jitter_ms: 0,
+            position_jitter_px: 0,
+            travel_duration_ms: 0,
+            micro_pause_enabled: false,
+            micro_pause_min_ms: 1000,
+            micro_pause_max_ms: 3000,
+            micro_pause_every_min_secs: 30,
+            micro_pause_every_max_secs: 90,
+            nudge_enabled: false,
+            work_minutes: 0,
+            rest_minutes: 0,
+            start_delay_ms: 0,
+            max_runtime_minutes: 240,
+            max_cps: 1000,
+            unlimited_cps: false,
+            failsafe_enabled: true,
+            interference_mode: "off".into(),
+            pause_while_typing: false,
+            typing_quiet_ms: 1000,
+            gamepad_speed: 1200.0,
+            gamepad_deadzone: 0.15,
+            modifier_handling: "off".into(),
+            restore_cursor: false,
+            remote_session_mode: "pause".into(),
+            block_input: false,
+            checkpoint_enabled: false,
+            no_coalesce_moves: false,
+            fixed_monitor: String::new(),
+            logical_coords: false,
+            coordinate_mode: "pixels".into(),
+            fixed_percent_x: 50.0,
+            fixed_percent_y: 50.0,
+            active_from: String::new(),
+            active_until: String::new(),
+            outside_hours_mode: "refuse".into(),
+            start_condition: "off".into(),
+            start_color_x: 0,
+            start_color_y: 0,
+            start_color: String::new(),
+            start_color_tolerance: 10,
+            stop_on_color_change: false,
+            stop_color_x: 0,
+            stop_color_y: 0,
+            stop_color_size: 1,
+            stop_color_tolerance: 10,
+            target_image: String::new(),
+            image_tolerance: 0.1,
+            start_image: String::new(),
+            condition_timeout_secs: 0,
+            text_x: 0,
+            text_y: 0,
+            text_width: 200,
+            text_height: 50,
+            start_text: String::new(),
+            stop_text: String::new(),
+            target_window: String::new(),
+            auto_focus: "off".into(),
+            anti_afk_wiggle_px: 3,
+            anti_afk_keys: String::new(),
+            monitor_sound: true,
+            trigger_sound: false,
         }
     }
 }
@@ -304,6 +1032,9 @@ pub struct HotkeySettings {
     pub start: Option<String>,
     pub stop: Option<String>,
     pub toggle: Option<String>,
+    /// Runs only while this key is physically held: pressing it starts, and
+    /// releasing it stops
+    pub hold: Option<String>,
 }
 
 impl Default for HotkeySettings {
@@ -312,87 +1043,1441 @@ impl Default for HotkeySettings {
             start: None,
             stop: None,
             toggle: None,
+            hold: None,
         }
     }
 }
 
-// ---------------------------------------------------------------------------
-// Internal state
-// ---------------------------------------------------------------------------
+/// Payload of the `action-stats` event, emitted about once per second while
+/// a click or key-repeat action runs.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionStats {
+    /// Inputs actually sent per second over the last window
+    pub cps: f64,
+    pub total: u64,
+}
 
-struct InputState {
-    stop: Option<Arc<AtomicBool>>,
-    done: Arc<AtomicBool>,
-    handle: Option<JoinHandle<()>>,
+/// Payload of the `settings-nudged` event: the live values after a nudge.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NudgeUpdate {
+    pub fixed_x: i32,
+    pub fixed_y: i32,
+    pub interval_ms: u64,
 }
 
-impl Default for InputState {
-    fn default() -> Self {
-        Self {
-            stop: None,
-            done: Arc::new(AtomicBool::new(true)),
-            handle: None,
-        }
-    }
+/// Payload of `rest-started`: how long the break lasts.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestPhase {
+    pub duration_ms: u64,
 }
 
-// ---------------------------------------------------------------------------
-// Helpers
-// ---------------------------------------------------------------------------
+/// Payload of `countdown-tick`: time left before the first input.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountdownTick {
+    pub remaining_ms: u64,
+}
 
-fn calc_interval_ms(s: &AutoInputSettings) -> u64 {
-    s.milliseconds + s.seconds * 1000 + s.minutes * 60_000 + s.hours * 3_600_000
+/// Payload of `action-stopped`. `reason` is set when the backend ended the
+/// run on its own, e.g. "timeout", "failsafe", "session" or "suspend".
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionStopped {
+    pub reason: Option<&'static str>,
+    /// The label the run was started with, if any
+    pub label: Option<String>,
 }
 
-fn lock_state(state: &Mutex<InputState>) -> std::sync::MutexGuard<'_, InputState> {
-    state.lock().unwrap_or_else(|e| e.into_inner())
+/// Payload of `action-queued`: a start outside the profile's allowed hours
+/// was put off until they open.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionQueued {
+    /// Unix time in milliseconds
+    pub starts_at: i64,
+    /// The label the run will start with, if any
+    pub label: Option<String>,
+}
+
+/// Payload of `input-error`, emitted at most once per stats window while
+/// SendInput is refusing events.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InputError {
+    pub reason: String,
+    pub rejected: u64,
+    pub sent: u64,
+}
+
+/// Emits `input-error` if any injected events were refused since the last
+/// check.
+fn report_input_errors(app: &AppHandle) {
+    let stats = win_input::take_send_stats();
+    if stats.rejected > 0 {
+        diagnostics::log(&format!(
+            "{} inputs rejected ({} sent): {}",
+            stats.rejected,
+            stats.sent,
+            stats.reason()
+        ));
+        let _ = app.emit(
+            "input-error",
+            InputError {
+                reason: stats.reason(),
+                rejected: stats.rejected,
+                sent: stats.sent,
+            },
+        );
+    }
+}
+
+/// Payload of `condition-timeout`: the start condition wasn't met within
+/// `condition_timeout_secs`, and the run was stopped.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConditionTimeout {
+    /// Whether injecting had begun, i.e. it was waiting to resume
+    pub started: bool,
+}
+
+/// Payload of `action-paused`: "interference", "typing", "secure-desktop",
+/// "remote-session", "condition" or "unfocused".
+/// `action-resumed` has no payload.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionPaused {
+    pub reason: &'static str,
 }
 
 // ---------------------------------------------------------------------------
-// Tauri commands
+// Internal state
 // ---------------------------------------------------------------------------
 
-#[tauri::command]
-fn start_action(
-    app: AppHandle,
-    state: tauri::State<'_, Mutex<InputState>>,
-    settings: AutoInputSettings,
-) -> Result<(), String> {
-    let mut st = lock_state(&state);
+#[derive(Default)]
+struct InputState {
+    stop: Option<Arc<AtomicBool>>,
+    handle: Option<RunHandle>,
+    /// Why the backend stopped the current run, if it did; first reason wins
+    stop_reason: Arc<OnceLock<&'static str>>,
+    idle: Arc<IdleWait>,
+    /// Settings of the most recent run, for `repeat_last_run`
+    last_settings: Option<AutoInputSettings>,
+    /// Free-text label of the current or most recent run
+    label: Option<String>,
+}
 
-    // Clean up finished thread
-    if st.done.load(Ordering::Acquire) {
-        if let Some(handle) = st.handle.take() {
-            let _ = handle.join();
-        }
-        st.stop = None;
+type Job = Box<dyn FnOnce() + Send>;
+
+/// The parked worker thread, waiting for its next run. Starting a run hands
+/// it the job instead of spawning a thread, so a hotkey start begins
+/// injecting within a few milliseconds.
+static STANDBY: Mutex<Option<Sender<Job>>> = Mutex::new(None);
+
+/// Signals when a run on the standby thread has finished.
+struct RunHandle(mpsc::Receiver<()>);
+
+impl RunHandle {
+    /// Waits for the run to end. Also returns if it panicked, since the
+    /// sender is dropped while unwinding.
+    fn join(self) {
+        let _ = self.0.recv();
     }
 
-    if st.handle.is_some() {
-        return Ok(());
+    /// Like `join`, giving up after `timeout`.
+    fn join_within(self, timeout: Duration) {
+        let _ = self.0.recv_timeout(timeout);
     }
+}
 
-    let interval = calc_interval_ms(&settings);
-    // Hold modes don't use interval — only validate for click/repeat modes
-    let is_hold_mode = (settings.action_type == "click" && settings.mouse_mode == "hold")
-        || (settings.action_type == "hold-key" && settings.key_mode == "hold");
-    if interval == 0 && !is_hold_mode {
-        return Err("Interval must be greater than 0".into());
+fn spawn_standby() -> Sender<Job> {
+    let (tx, rx) = mpsc::channel::<Job>();
+    thread::spawn(move || {
+        for job in rx {
+            job();
+            // Runs may have raised it; the next one might not want it
+            win_input::reset_thread_priority();
+        }
+    });
+    tx
+}
+
+/// Parks a worker thread ahead of the first run.
+fn warm_standby() {
+    let mut standby = STANDBY.lock().unwrap_or_else(|e| e.into_inner());
+    if standby.is_none() {
+        *standby = Some(spawn_standby());
     }
+}
 
-    if settings.action_type == "hold-key" && settings.hold_key.is_empty() {
-        return Err("No key selected".into());
+/// Runs `job` on the standby thread, replacing it if an earlier run
+/// panicked and took it down.
+fn run_on_standby(job: impl FnOnce() + Send + 'static) -> RunHandle {
+    let (done_tx, done_rx) = mpsc::channel();
+    let mut job: Job = Box::new(move || {
+        job();
+        let _ = done_tx.send(());
+    });
+    let mut standby = STANDBY.lock().unwrap_or_else(|e| e.into_inner());
+    loop {
+        let tx = standby.get_or_insert_with(spawn_standby);
+        match tx.send(job) {
+            Ok(()) => break,
+            Err(mpsc::SendError(returned)) => {
+                job = returned;
+                *standby = None;
+            }
+        }
     }
+    RunHandle(done_rx)
+}
 
-    let stop = Arc::new(AtomicBool::new(false));
-    let stop_clone = Arc::clone(&stop);
+/// Sleeps out long intervals on a condvar instead of polling, so the worker
+/// only wakes for a stop or a live interval change, and publishes when it
+/// will next fire for `get_status`.
+#[derive(Default)]
+struct IdleWait {
+    lock: Mutex<()>,
+    cvar: Condvar,
+    /// Unix ms of the next input while sleeping a long interval; 0 otherwise
+    next_fire_ms: AtomicU64,
+}
 
-    let done = Arc::new(AtomicBool::new(false));
-    let done_clone = Arc::clone(&done);
+impl IdleWait {
+    /// Wakes the sleeper to re-check `stop` and its deadline. Taking the lock
+    /// first means a wake can't slip in between its check and its wait.
+    fn wake(&self) {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        self.cvar.notify_all();
+    }
 
-    let app_handle = app.clone();
+    fn next_fire_ms(&self) -> Option<u64> {
+        Some(self.next_fire_ms.load(Ordering::Relaxed)).filter(|&ms| ms > 0)
+    }
+
+    /// Sleeps until `deadline()` passes, re-reading it after every wake.
+    /// Returns false if interrupted by `stop`.
+    fn sleep_until(&self, stop: &AtomicBool, deadline: impl Fn() -> Instant) -> bool {
+        let mut guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let finished = loop {
+            if stop.load(Ordering::Acquire) {
+                break false;
+            }
+            let now = Instant::now();
+            let at = deadline();
+            if now >= at {
+                break true;
+            }
+            let left = at - now;
+            self.next_fire_ms.store(
+                storage::unix_now_ms() + left.as_millis() as u64,
+                Ordering::Relaxed,
+            );
+            guard = self
+                .cvar
+                .wait_timeout(guard, left)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        };
+        self.next_fire_ms.store(0, Ordering::Relaxed);
+        finished
+    }
+}
+
+/// Keeps the system and display awake for as long as it's alive.
+struct StayAwake;
+
+impl StayAwake {
+    fn new() -> Self {
+        win_input::keep_awake();
+        Self
+    }
+}
+
+impl Drop for StayAwake {
+    fn drop(&mut self) {
+        win_input::allow_sleep();
+    }
+}
+
+/// Keeps physical keyboard and mouse input from reaching apps while alive.
+/// Windows only lets the blocking thread unblock, and unblocks by itself when
+/// that thread exits, so a stop from any path (including the panic hotkey
+/// ending the worker) lifts it. Ctrl+Alt+Del always does too.
+struct BlockedInput;
+
+impl BlockedInput {
+    fn new() -> Self {
+        win_input::block_input(true);
+        Self
+    }
+}
+
+impl Drop for BlockedInput {
+    fn drop(&mut self) {
+        win_input::block_input(false);
+    }
+}
+
+/// A key or mouse button we're holding down.
+#[derive(Debug, Clone, PartialEq)]
+enum Held {
+    Key(u16),
+    Button(ClickButton),
+}
+
+impl Held {
+    fn release(&self) {
+        match self {
+            Held::Key(vk) => win_input::key_up(win_input::VIRTUAL_KEY(*vk)),
+            Held::Button(button) => win_input::mouse_up(*button),
+        }
+    }
+
+    /// Virtual-key code `GetAsyncKeyState` reports it under.
+    fn vk(&self) -> u16 {
+        match self {
+            Held::Key(vk) => *vk,
+            Held::Button(ClickButton::Left) => 0x01,
+            Held::Button(ClickButton::Right) => 0x02,
+            Held::Button(ClickButton::Middle) => 0x04,
+        }
+    }
+}
+
+/// Everything currently held down by a worker, so exit and panic paths can
+/// let go even when the worker never gets to.
+static HELD: Mutex<Vec<Held>> = Mutex::new(Vec::new());
+
+/// Presses on creation and releases on drop, including when a panic unwinds
+/// through the worker.
+struct HeldInput(Held);
+
+impl HeldInput {
+    fn key(vk: win_input::VIRTUAL_KEY) -> Self {
+        win_input::key_down(vk);
+        Self::register(Held::Key(vk.0))
+    }
+
+    fn button(button: ClickButton) -> Self {
+        win_input::mouse_down(button);
+        Self::register(Held::Button(button))
+    }
+
+    fn register(held: Held) -> Self {
+        note_pressed(held.clone());
+        HELD.lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(held.clone());
+        Self(held)
+    }
+}
+
+impl Drop for HeldInput {
+    fn drop(&mut self) {
+        let mut held = HELD.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(i) = held.iter().position(|h| *h == self.0) {
+            held.remove(i);
+            self.0.release();
+        }
+    }
+}
+
+/// Releases everything still held. Uses `try_lock` since it also runs from
+/// the panic hook, which may fire on a thread that holds the lock.
+fn release_held_inputs() {
+    if let Ok(mut held) = HELD.try_lock() {
+        for h in held.drain(..) {
+            h.release();
+        }
+    }
+}
+
+/// Every key and button the current run has pressed, checked when it ends.
+static PRESSED: Mutex<Vec<Held>> = Mutex::new(Vec::new());
+
+fn note_pressed(held: Held) {
+    let mut pressed = PRESSED.lock().unwrap_or_else(|e| e.into_inner());
+    if !pressed.contains(&held) {
+        pressed.push(held);
+    }
+}
+
+/// Catches stuck input that slipped past the release paths, e.g. an up event
+/// Windows dropped: anything the run pressed that's still reported down gets
+/// another release, unless the user is the one holding it. Returns the
+/// virtual-key codes released again. `try_lock` for the same reason as
+/// `release_held_inputs`.
+fn verify_released() -> Vec<u16> {
+    let Ok(mut pressed) = PRESSED.try_lock() else {
+        return Vec::new();
+    };
+    let mut released = Vec::new();
+    for h in pressed.drain(..) {
+        // Without the hook's word on it, a press could be the user's
+        let stuck = win_input::is_key_down(h.vk()) && hooks::physically_down(h.vk()) == Some(false);
+        if stuck {
+            h.release();
+            released.push(h.vk());
+        }
+    }
+    released
+}
+
+/// Payload of `inputs-released-again`: keys and buttons (virtual-key codes)
+/// still down after the run ended, which got a second release.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleasedAgain {
+    pub vks: Vec<u16>,
+}
+
+/// Settings the worker re-reads every tick so they can be nudged mid-run.
+struct LiveTuning {
+    x: AtomicI32,
+    y: AtomicI32,
+    interval_ms: AtomicU64,
+}
+
+impl LiveTuning {
+    fn new(settings: &AutoInputSettings, interval_ms: u64) -> Self {
+        Self {
+            x: AtomicI32::new(settings.fixed_x),
+            y: AtomicI32::new(settings.fixed_y),
+            interval_ms: AtomicU64::new(interval_ms),
+        }
+    }
+
+    fn snapshot(&self) -> NudgeUpdate {
+        NudgeUpdate {
+            fixed_x: self.x.load(Ordering::Relaxed),
+            fixed_y: self.y.load(Ordering::Relaxed),
+            interval_ms: self.interval_ms.load(Ordering::Relaxed),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+const TRAY_ID: &str = "main";
+
+/// Posted to the main thread rather than made from the caller: tray calls
+/// block until the main thread gets to them, and the worker calling this may
+/// be what the main thread is waiting on.
+fn set_tray_status(app: &AppHandle, status: Option<&str>) {
+    let tooltip = match status {
+        Some(status) => format!("AutoInput — {status}"),
+        None => "AutoInput".into(),
+    };
+    let handle = app.clone();
+    let _ = app.run_on_main_thread(move || {
+        if let Some(tray) = handle.tray_by_id(TRAY_ID) {
+            let _ = tray.set_tooltip(Some(tooltip));
+        }
+    });
+}
+
+fn build_tray_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
+    let show_i = MenuItemBuilder::with_id("show", "Show AutoInput").build(app)?;
+    let hide_i = MenuItemBuilder::with_id("hide", "Minimize to Tray").build(app)?;
+    let run_i = library::run_submenu(app)?;
+    let repeat_i = MenuItemBuilder::with_id("repeat", "Repeat Last Run").build(app)?;
+    let quit_i = MenuItemBuilder::with_id("quit", "Exit").build(app)?;
+    MenuBuilder::new(app)
+        .items(&[&show_i, &hide_i, &run_i, &repeat_i, &quit_i])
+        .build()
+}
+
+/// Rebuilds the tray menu so its "Run" submenu matches the saved configs.
+fn refresh_tray_menu(app: &AppHandle) {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        if let Ok(menu) = build_tray_menu(app) {
+            let _ = tray.set_menu(Some(menu));
+        }
+    }
+}
+
+const STATS_WINDOW: Duration = Duration::from_secs(1);
+
+fn calc_interval_ms(s: &AutoInputSettings) -> u64 {
+    if s.bpm > 0 {
+        let per_minute = s.bpm as f64 * s.subdivision.max(1) as f64;
+        return (60_000.0 / per_minute).round() as u64;
+    }
+    s.milliseconds + s.seconds * 1000 + s.minutes * 60_000 + s.hours * 3_600_000
+}
+
+const STOP_POLL: Duration = Duration::from_millis(50);
+const CONDITION_POLL: Duration = Duration::from_millis(50);
+/// How often a run checks a start condition that has to keep holding
+const CONDITION_RECHECK: Duration = Duration::from_millis(250);
+/// How soon to look again when the target image isn't on screen
+const IMAGE_RETRY: Duration = Duration::from_millis(100);
+/// Pauses at least this long are slept on `IdleWait` rather than the timer
+const IDLE_WAIT_AFTER: Duration = Duration::from_secs(1);
+const GAMEPAD_TICK: Duration = Duration::from_millis(8);
+
+/// Sleeps for `dur` in short slices so a stop request is honored promptly.
+/// Returns false if interrupted by `stop`.
+fn sleep_unless_stopped(dur: Duration, stop: &AtomicBool) -> bool {
+    let deadline = Instant::now() + dur;
+    loop {
+        if stop.load(Ordering::Acquire) {
+            return false;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return true;
+        }
+        thread::sleep(STOP_POLL.min(deadline - now));
+    }
+}
+
+const FAILSAFE_MARGIN: i32 = 2;
+
+/// True when `(x, y)` lies within a couple of pixels of a corner of the
+/// virtual screen, the rectangle around every monitor. The primary monitor's
+/// own corners don't count when another monitor continues past them.
+fn in_screen_corner(x: i32, y: i32) -> bool {
+    let screen = monitors::virtual_screen();
+    if screen.width == 0 || screen.height == 0 {
+        return false;
+    }
+    let near_edge = |v: i32, start: i32, size: i32| {
+        v <= start + FAILSAFE_MARGIN || v >= start + size - 1 - FAILSAFE_MARGIN
+    };
+    near_edge(x, screen.x, screen.width) && near_edge(y, screen.y, screen.height)
+}
+
+/// Polls the cursor until the run ends. Slamming it into a corner sets
+/// `tripped` and stops the worker. A cursor already in a corner when the run
+/// starts (say, from a hot corner) has to leave it first.
+fn spawn_failsafe(
+    stop: Arc<AtomicBool>,
+    done: Arc<AtomicBool>,
+    tripped: Arc<AtomicBool>,
+    idle: Arc<IdleWait>,
+) {
+    thread::spawn(move || {
+        let (x, y) = win_input::cursor_pos();
+        let mut armed = !in_screen_corner(x, y);
+        while !stop.load(Ordering::Acquire) && !done.load(Ordering::Acquire) {
+            let (x, y) = win_input::cursor_pos();
+            let cornered = in_screen_corner(x, y);
+            armed |= !cornered;
+            if armed && cornered {
+                tripped.store(true, Ordering::Release);
+                stop.store(true, Ordering::Release);
+                idle.wake();
+                return;
+            }
+            thread::sleep(STOP_POLL);
+        }
+    });
+}
+
+const INTERFERENCE_TOLERANCE_PX: i32 = 3;
+const INTERFERENCE_RESUME: Duration = Duration::from_secs(2);
+
+/// True if the cursor is no longer where we left it.
+fn cursor_moved_from((x, y): (i32, i32)) -> bool {
+    let (cx, cy) = win_input::cursor_pos();
+    (cx - x).abs() > INTERFERENCE_TOLERANCE_PX || (cy - y).abs() > INTERFERENCE_TOLERANCE_PX
+}
+
+/// Waits until the user has left the mouse alone for `INTERFERENCE_RESUME`.
+/// Returns false if stopped meanwhile.
+fn wait_for_idle_cursor(stop: &AtomicBool) -> bool {
+    let mut last = win_input::cursor_pos();
+    let mut still_since = Instant::now();
+    while still_since.elapsed() < INTERFERENCE_RESUME {
+        if !sleep_unless_stopped(STOP_POLL, stop) {
+            return false;
+        }
+        let pos = win_input::cursor_pos();
+        if pos != last {
+            last = pos;
+            still_since = Instant::now();
+        }
+    }
+    true
+}
+
+/// `modifier_handling` setting, resolved once per run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ModifierHandling {
+    Off,
+    Release,
+    Wait,
+}
+
+impl ModifierHandling {
+    fn parse(mode: &str) -> Self {
+        match mode {
+            "release" => Self::Release,
+            "wait" => Self::Wait,
+            _ => Self::Off,
+        }
+    }
+}
+
+/// `interference_mode` setting, resolved once per run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Interference {
+    Pause,
+    Stop,
+    Off,
+}
+
+impl Interference {
+    fn parse(mode: &str) -> Self {
+        match mode {
+            "pause" => Self::Pause,
+            "stop" => Self::Stop,
+            _ => Self::Off,
+        }
+    }
+}
+
+/// Runs `inject` with physically held modifiers out of the way, so a held
+/// Shift doesn't turn clicks into shift-clicks. `Release` lifts them around
+/// the input and presses them again after; `Wait` holds off until they're
+/// let go. Returns false if stopped while waiting.
+fn inject_without_modifiers(
+    mode: ModifierHandling,
+    stop: &AtomicBool,
+    inject: impl FnOnce(),
+) -> bool {
+    match mode {
+        ModifierHandling::Release => {
+            let held = win_input::held_modifiers();
+            for vk in held.into_iter().flatten() {
+                win_input::key_up(vk);
+            }
+            inject();
+            for vk in held.into_iter().flatten() {
+                win_input::key_down(vk);
+            }
+        }
+        ModifierHandling::Wait => {
+            while win_input::held_modifiers().iter().any(Option::is_some) {
+                if !sleep_unless_stopped(STOP_POLL, stop) {
+                    return false;
+                }
+            }
+            inject();
+        }
+        ModifierHandling::Off => inject(),
+    }
+    true
+}
+
+/// Emits `action-paused` with `reason`, polls until `paused` turns false and
+/// emits `action-resumed`. Returns false if stopped while waiting.
+fn pause_while(
+    app: &AppHandle,
+    reason: &'static str,
+    stop: &AtomicBool,
+    mut paused: impl FnMut() -> bool,
+) -> bool {
+    engine::transition(app, EngineState::Paused);
+    let _ = app.emit("action-paused", ActionPaused { reason });
+    let mut resumed = true;
+    while paused() {
+        if !sleep_unless_stopped(STOP_POLL, stop) {
+            resumed = false;
+            break;
+        }
+    }
+    engine::transition(app, EngineState::Running);
+    let _ = app.emit("action-resumed", ());
+    resumed
+}
+
+enum GateWait {
+    Met,
+    Stopped,
+    TimedOut,
+}
+
+/// Polls `gate` until it's met, the run stops, or `timeout` runs out.
+fn wait_for_gate(
+    gate: &mut conditions::Gate,
+    timeout: Option<Duration>,
+    mut stopped: impl FnMut() -> bool,
+) -> GateWait {
+    let deadline = timeout.map(|t| Instant::now() + t);
+    while !gate.met() {
+        if stopped() {
+            return GateWait::Stopped;
+        }
+        if deadline.is_some_and(|t| Instant::now() >= t) {
+            return GateWait::TimedOut;
+        }
+        thread::sleep(CONDITION_POLL);
+    }
+    GateWait::Met
+}
+
+const DESKTOP_RECHECK: Duration = Duration::from_millis(250);
+
+/// Caches whether input reaches the normal desktop and whether the session
+/// is remote, re-checking at most every `DESKTOP_RECHECK` so fast runs aren't
+/// querying the system per input.
+struct DesktopWatch {
+    checked_at: Option<Instant>,
+    secure: bool,
+    remote: bool,
+}
+
+impl DesktopWatch {
+    fn new() -> Self {
+        Self {
+            checked_at: None,
+            secure: false,
+            remote: false,
+        }
+    }
+
+    fn refresh(&mut self) {
+        if self
+            .checked_at
+            .is_none_or(|t| t.elapsed() >= DESKTOP_RECHECK)
+        {
+            self.secure = !win_input::on_default_desktop();
+            self.remote = win_input::is_remote_session();
+            self.checked_at = Some(Instant::now());
+        }
+    }
+
+    /// Whether a UAC prompt, lock screen or similar has the input desktop.
+    fn secure(&mut self) -> bool {
+        self.refresh();
+        self.secure
+    }
+
+    /// Whether the session is being viewed or controlled over RDP.
+    fn remote(&mut self) -> bool {
+        self.refresh();
+        self.remote
+    }
+}
+
+/// `remote_session_mode` setting, resolved once per run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RemotePolicy {
+    Pause,
+    Continue,
+    Stop,
+}
+
+impl RemotePolicy {
+    fn parse(mode: &str) -> Self {
+        match mode {
+            "continue" => Self::Continue,
+            "stop" => Self::Stop,
+            _ => Self::Pause,
+        }
+    }
+}
+
+/// Tracks when the user last pressed a physical key.
+struct TypingMonitor {
+    epoch: Instant,
+    /// Milliseconds after `epoch` of the last key press, plus one; 0 if none
+    last_key_ms: Arc<AtomicU64>,
+    _listener: hooks::ListenerGuard,
+}
+
+impl TypingMonitor {
+    fn start() -> Self {
+        let epoch = Instant::now();
+        let last_key_ms = Arc::new(AtomicU64::new(0));
+        let last = Arc::clone(&last_key_ms);
+        let listener = hooks::add_key_listener(move |event| {
+            // Injected typing from other programs (on-screen keyboards,
+            // remote tools) still counts as the user typing
+            if !is_own_event(event.extra_info) && event.down {
+                last.store(epoch.elapsed().as_millis() as u64 + 1, Ordering::Relaxed);
+            }
+            false
+        });
+        Self {
+            epoch,
+            last_key_ms,
+            _listener: listener,
+        }
+    }
+
+    fn typed_within(&self, quiet: Duration) -> bool {
+        match self.last_key_ms.load(Ordering::Relaxed) {
+            0 => false,
+            last => {
+                self.epoch
+                    .elapsed()
+                    .saturating_sub(Duration::from_millis(last - 1))
+                    < quiet
+            }
+        }
+    }
+}
+
+/// Random value in `[a, b]`, whichever order the bounds come in.
+fn random_between(a: u64, b: u64) -> u64 {
+    fastrand::u64(a.min(b)..=a.max(b))
+}
+
+/// Sleeps for `dur`. In precision mode the last millisecond is spent spinning
+/// on `Instant`, since OS sleep granularity overshoots short intervals.
+fn precise_sleep(dur: Duration, precise: bool) {
+    if !precise {
+        thread::sleep(dur);
+        return;
+    }
+    let deadline = Instant::now() + dur;
+    let coarse = dur.saturating_sub(Duration::from_millis(1));
+    if !coarse.is_zero() {
+        thread::sleep(coarse);
+    }
+    while Instant::now() < deadline {
+        std::hint::spin_loop();
+    }
+}
+
+/// Base interval plus a normally distributed offset (σ = jitter / 3, so nearly
+/// all samples land within ±jitter), clamped to that range and to at least 1ms.
+fn jittered_interval(base_ms: u64, jitter_ms: u64) -> Duration {
+    if jitter_ms == 0 {
+        return Duration::from_millis(base_ms);
+    }
+    let jitter = jitter_ms as f64;
+    let offset = (gaussian() * jitter / 3.0).clamp(-jitter, jitter);
+    Duration::from_secs_f64((base_ms as f64 + offset).max(1.0) / 1000.0)
+}
+
+/// Uniformly random point inside a circle of `radius` around (x, y).
+fn jittered_point(x: i32, y: i32, radius: u32) -> (i32, i32) {
+    if radius == 0 {
+        return (x, y);
+    }
+    // sqrt keeps the density uniform over the disk instead of bunching at the center
+    let r = radius as f64 * fastrand::f64().sqrt();
+    let theta = std::f64::consts::TAU * fastrand::f64();
+    (
+        x + (r * theta.cos()).round() as i32,
+        y + (r * theta.sin()).round() as i32,
+    )
+}
+
+const TRAVEL_STEP: Duration = Duration::from_millis(5);
+
+/// Moves the cursor to (x, y). With a travel duration it glides there along a
+/// slightly curved, eased path instead of warping, since some apps ignore
+/// clicks that follow an instant jump. Gives up early if `stop` is raised.
+fn move_to(x: i32, y: i32, travel_ms: u64, stop: &AtomicBool) {
+    if travel_ms == 0 {
+        win_input::move_mouse_abs(x, y);
+        return;
+    }
+
+    let (sx, sy) = win_input::cursor_pos();
+    let (sx, sy, ex, ey) = (sx as f64, sy as f64, x as f64, y as f64);
+    let (dx, dy) = (ex - sx, ey - sy);
+    // Control points a third and two thirds along the line, pushed sideways
+    // by a random fraction of its length so each path bends differently
+    let bend = || (fastrand::f64() - 0.5) * 0.3;
+    let (b1, b2) = (bend(), bend());
+    let c1 = (sx + dx / 3.0 - dy * b1, sy + dy / 3.0 + dx * b1);
+    let c2 = (sx + dx * 2.0 / 3.0 - dy * b2, sy + dy * 2.0 / 3.0 + dx * b2);
+
+    let start = Instant::now();
+    let duration = Duration::from_millis(travel_ms);
+    loop {
+        if stop.load(Ordering::Acquire) {
+            return;
+        }
+        let t = (start.elapsed().as_secs_f64() / duration.as_secs_f64()).min(1.0);
+        // Ease in-out so the cursor accelerates away and settles onto the target
+        let e = t * t * (3.0 - 2.0 * t);
+        let u = 1.0 - e;
+        let px = u * u * u * sx + 3.0 * u * u * e * c1.0 + 3.0 * u * e * e * c2.0 + e * e * e * ex;
+        let py = u * u * u * sy + 3.0 * u * u * e * c1.1 + 3.0 * u * e * e * c2.1 + e * e * e * ey;
+        win_input::move_mouse_abs(px.round() as i32, py.round() as i32);
+        if t >= 1.0 {
+            return;
+        }
+        thread::sleep(TRAVEL_STEP);
+    }
+}
+
+// Standard normal sample via Box–Muller
+fn gaussian() -> f64 {
+    let u1 = fastrand::f64().max(f64::MIN_POSITIVE);
+    let u2 = fastrand::f64();
+    (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+}
+
+/// Ctrl+Alt+Arrows move the fixed target by 1px (10px with Shift);
+/// Ctrl+Alt+PageUp/PageDown shorten/lengthen the interval by 10%.
+/// Matching keys are swallowed so they don't reach the target app.
+fn nudge_listener(
+    app: AppHandle,
+    live: Arc<LiveTuning>,
+    idle: Arc<IdleWait>,
+) -> Result<hooks::ListenerGuard, String> {
+    let handle = app.clone();
+    permissions::add_key_listener(&handle, permissions::Feature::LiveTuning, move |ev| {
+        if !ev.down || ev.injected {
+            return false;
+        }
+        let m = hooks::modifiers();
+        if !(m.ctrl && m.alt) {
+            return false;
+        }
+        let step = if m.shift { 10 } else { 1 };
+        let scale_interval = |faster: bool| {
+            let _ = live
+                .interval_ms
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |ms| {
+                    let delta = (ms / 10).max(1);
+                    Some(if faster {
+                        ms.saturating_sub(delta).max(1)
+                    } else {
+                        ms + delta
+                    })
+                });
+            // A worker sleeping out a long interval picks up the new one
+            idle.wake();
+        };
+        match ev.vk {
+            hooks::VK_LEFT => {
+                live.x.fetch_sub(step, Ordering::Relaxed);
+            }
+            hooks::VK_RIGHT => {
+                live.x.fetch_add(step, Ordering::Relaxed);
+            }
+            hooks::VK_UP => {
+                live.y.fetch_sub(step, Ordering::Relaxed);
+            }
+            hooks::VK_DOWN => {
+                live.y.fetch_add(step, Ordering::Relaxed);
+            }
+            hooks::VK_PRIOR => scale_interval(true),
+            hooks::VK_NEXT => scale_interval(false),
+            _ => return false,
+        }
+        let _ = app.emit("settings-nudged", live.snapshot());
+        true
+    })
+}
+
+fn lock_state(state: &Mutex<InputState>) -> std::sync::MutexGuard<'_, InputState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// ---------------------------------------------------------------------------
+// Tick loop
+//
+// The click/key loop's own pacing: send, count, wait out the interval, then
+// any micro-pause or rest that has come up. Everything the worker checks
+// between ticks (typing, focus, conditions, where to click) stays with the
+// worker. Time only comes from a `Clock` and the app only through a
+// `TickHost`, so the harness runs this on a fake clock against the stub sink.
+// ---------------------------------------------------------------------------
+
+/// Time as the tick loop sees it.
+trait Clock {
+    fn now(&self) -> Instant;
+    /// Waits out the pause after a tick, unless stopped first. `base_ms` is
+    /// the interval the pause was jittered from, which live tuning may change
+    /// in the meantime.
+    fn pause(&self, pause: Duration, base_ms: u64, stop: &AtomicBool) -> bool;
+    /// Sits out a micro-pause or rest, unless stopped first.
+    fn sleep(&self, dur: Duration, stop: &AtomicBool) -> bool;
+}
+
+/// What the tick loop reports to and takes its live settings from.
+trait TickHost {
+    /// The interval to pace the next tick by
+    fn interval_ms(&self) -> u64;
+    /// Any further floor on the pause, from per-app rate limits
+    fn min_tick(&mut self, inputs: u64) -> Duration;
+    /// A tick went out: ticks so far, and how many inputs this one sent
+    fn ticked(&mut self, count: u64, inputs: u64);
+    /// A micro-pause ended
+    fn idled(&mut self);
+    /// A work/rest rest starts (Some) or ends (None)
+    fn resting(&mut self, rest: Option<Duration>);
+}
+
+struct TickLoop {
+    is_click: bool,
+    clicks: u64,
+    button: ClickButton,
+    key: win_input::VIRTUAL_KEY,
+    modifier_handling: ModifierHandling,
+    jitter_ms: u64,
+    /// Floor on the pause so a typo'd interval can't flood the OS input
+    /// queue and lock up the machine
+    min_tick: Duration,
+    repeat_count: u64,
+    /// Every `(min, max)` seconds, for `(min, max)` milliseconds
+    micro_pause: Option<((u64, u64), (u64, u64))>,
+    micro_pause_at: Instant,
+    /// Run for the first, then pause for the second
+    work_rest: Option<(Duration, Duration)>,
+    work_until: Option<Instant>,
+    count: u64,
+}
+
+impl TickLoop {
+    /// Resolves everything string-typed up front so ticks do no string
+    /// comparisons or allocations. `repeat_count` 0 runs until stopped.
+    fn new(settings: &AutoInputSettings, repeat_count: u64, now: Instant) -> Self {
+        let is_click = settings.action_type == "click";
+        let clicks = if settings.click_type == "double" {
+            2
+        } else {
+            1
+        };
+        let inputs_per_tick = if is_click { clicks } else { 1 };
+        let min_tick = if settings.unlimited_cps {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(inputs_per_tick as f64 / settings.max_cps.max(1) as f64)
+        };
+        let micro_pause = settings.micro_pause_enabled.then_some((
+            (
+                settings.micro_pause_every_min_secs,
+                settings.micro_pause_every_max_secs,
+            ),
+            (settings.micro_pause_min_ms, settings.micro_pause_max_ms),
+        ));
+        let work_rest = (settings.work_minutes > 0 && settings.rest_minutes > 0).then(|| {
+            (
+                Duration::from_secs(settings.work_minutes * 60),
+                Duration::from_secs(settings.rest_minutes * 60),
+            )
+        });
+        let mut ticks = Self {
+            is_click,
+            clicks,
+            button: ClickButton::parse(&settings.mouse_button),
+            key: win_input::resolve_vk(&settings.hold_key),
+            modifier_handling: ModifierHandling::parse(&settings.modifier_handling),
+            jitter_ms: settings.interval_jitter_ms,
+            min_tick,
+            repeat_count,
+            micro_pause,
+            micro_pause_at: now,
+            work_rest,
+            work_until: work_rest.map(|(work, _)| now + work),
+            count: 0,
+        };
+        ticks.schedule_micro_pause(now);
+        ticks
+    }
+
+    fn inputs_per_tick(&self) -> u64 {
+        if self.is_click {
+            self.clicks
+        } else {
+            1
+        }
+    }
+
+    /// Occasional longer breaks so long runs don't tick like a metronome
+    fn schedule_micro_pause(&mut self, now: Instant) {
+        if let Some(((min_secs, max_secs), _)) = self.micro_pause {
+            self.micro_pause_at = now + Duration::from_secs(random_between(min_secs, max_secs));
+        }
+    }
+
+    /// Sends one tick's inputs. None if stopped while waiting on modifiers.
+    fn send(&self, stop: &AtomicBool) -> Option<u64> {
+        let injected = inject_without_modifiers(self.modifier_handling, stop, || {
+            if self.is_click {
+                for _ in 0..self.clicks {
+                    win_input::mouse_click(self.button);
+                }
+            } else {
+                win_input::key_press(self.key);
+            }
+        });
+        injected.then(|| self.inputs_per_tick())
+    }
+
+    /// Counts a tick that sent `inputs` and waits until the next is due.
+    /// False once the run should end: stopped, or `repeat_count` reached.
+    fn wait(
+        &mut self,
+        inputs: u64,
+        clock: &impl Clock,
+        host: &mut impl TickHost,
+        stop: &AtomicBool,
+    ) -> bool {
+        self.count += 1;
+        host.ticked(self.count, inputs);
+        if self.repeat_count > 0 && self.count >= self.repeat_count {
+            return false;
+        }
+
+        let base_ms = host.interval_ms();
+        let pause = jittered_interval(base_ms, self.jitter_ms)
+            .max(self.min_tick)
+            .max(host.min_tick(self.inputs_per_tick()));
+        if !clock.pause(pause, base_ms, stop) {
+            return false;
+        }
+
+        if let Some((_, (min_ms, max_ms))) = self.micro_pause {
+            if clock.now() >= self.micro_pause_at {
+                let pause_ms = random_between(min_ms, max_ms);
+                if !clock.sleep(Duration::from_millis(pause_ms), stop) {
+                    return false;
+                }
+                host.idled();
+                self.schedule_micro_pause(clock.now());
+            }
+        }
+
+        if let (Some((work, rest)), Some(until)) = (self.work_rest, self.work_until) {
+            if clock.now() >= until {
+                host.resting(Some(rest));
+                let rested = clock.sleep(rest, stop);
+                host.resting(None);
+                if !rested {
+                    return false;
+                }
+                self.work_until = Some(clock.now() + work);
+            }
+        }
+        true
+    }
+
+    /// `send` then `wait`, for callers with nothing to do in between.
+    fn tick(&mut self, clock: &impl Clock, host: &mut impl TickHost, stop: &AtomicBool) -> bool {
+        match self.send(stop) {
+            Some(inputs) => self.wait(inputs, clock, host, stop),
+            None => false,
+        }
+    }
+}
+
+/// The worker's clock. Long pauses wait on `idle`, so a stop or a live
+/// nudge cuts them short, and never run past the run's deadlines.
+struct WallClock<'a> {
+    idle: &'a IdleWait,
+    live: &'a LiveTuning,
+    deadlines: [Option<Instant>; 2],
+    precise: bool,
+}
+
+impl Clock for WallClock<'_> {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn pause(&self, pause: Duration, base_ms: u64, stop: &AtomicBool) -> bool {
+        if pause < IDLE_WAIT_AFTER {
+            precise_sleep(pause, self.precise);
+            return true;
+        }
+        let slept_from = Instant::now();
+        self.idle.sleep_until(stop, || {
+            // A nudge mid-wait rescales the whole pause
+            let now_ms = self.live.interval_ms.load(Ordering::Relaxed);
+            let at = slept_from + pause.mul_f64(now_ms as f64 / base_ms.max(1) as f64);
+            self.deadlines.into_iter().flatten().fold(at, Instant::min)
+        })
+    }
+
+    fn sleep(&self, dur: Duration, stop: &AtomicBool) -> bool {
+        sleep_unless_stopped(dur, stop)
+    }
+}
+
+/// The worker's side of the tick loop: live tuning, per-app limits, stats,
+/// checkpoints, usage and the rest phase events.
+struct WorkerTicks<'a> {
+    app: &'a AppHandle,
+    live: &'a LiveTuning,
+    meter: &'a usage::UsageMeter,
+    app_limit: limits::ProcessLimiter,
+    checkpoint: Option<checkpoint::Checkpointer>,
+    total_inputs: u64,
+    window_start: Instant,
+    window_inputs: u64,
+}
+
+impl TickHost for WorkerTicks<'_> {
+    fn interval_ms(&self) -> u64 {
+        self.live.interval_ms.load(Ordering::Relaxed)
+    }
+
+    fn min_tick(&mut self, inputs: u64) -> Duration {
+        self.app_limit.min_tick(inputs)
+    }
+
+    fn ticked(&mut self, count: u64, inputs: u64) {
+        self.window_inputs += inputs;
+        let window = self.window_start.elapsed();
+        if window >= STATS_WINDOW {
+            self.total_inputs += self.window_inputs;
+            let _ = self.app.emit(
+                "action-stats",
+                ActionStats {
+                    cps: self.window_inputs as f64 / window.as_secs_f64(),
+                    total: self.total_inputs,
+                },
+            );
+            self.window_start = Instant::now();
+            self.window_inputs = 0;
+            report_input_errors(self.app);
+        }
+        if let Some(checkpoint) = self.checkpoint.as_mut() {
+            checkpoint.progress(count);
+        }
+    }
+
+    fn idled(&mut self) {
+        self.meter.idle();
+    }
+
+    fn resting(&mut self, rest: Option<Duration>) {
+        match rest {
+            Some(rest) => {
+                let _ = self.app.emit(
+                    "rest-started",
+                    RestPhase {
+                        duration_ms: rest.as_millis() as u64,
+                    },
+                );
+                set_tray_status(self.app, Some("resting"));
+                engine::transition(self.app, EngineState::Paused);
+            }
+            None => {
+                engine::transition(self.app, EngineState::Running);
+                set_tray_status(self.app, None);
+                let _ = self.app.emit("rest-ended", ());
+                self.meter.idle();
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Worker
+// ---------------------------------------------------------------------------
+
+/// Starts the worker thread for `settings`. No-op if one is already running.
+/// Shared by the `start_action` command and backend-initiated starts.
+fn start_worker(app: &AppHandle, settings: AutoInputSettings) -> Result<(), String> {
+    start_worker_from(app, settings, 0, None)
+}
+
+/// Starts a run that continues an earlier one which had already done
+/// `offset` inputs, so checkpoints keep counting from there.
+fn start_worker_from(
+    app: &AppHandle,
+    mut settings: AutoInputSettings,
+    offset: u64,
+    label: Option<String>,
+) -> Result<(), String> {
+    let state = app.state::<Mutex<InputState>>();
+    let mut st = lock_state(&state);
+
+    if engine::current(app).is_active() {
+        return Ok(());
+    }
+    // The previous run has ended or is just wrapping up
+    if let Some(handle) = st.handle.take() {
+        handle.join();
+    }
+    st.stop = None;
+
+    if settings.bpm > 0 && !(1..=4).contains(&settings.subdivision) {
+        return Err("Subdivision must be between 1 and 4".into());
+    }
+
+    let interval = calc_interval_ms(&settings);
+    // Hold and monitor modes don't use interval — only validate for click/repeat modes
+    let is_hold_mode = (settings.action_type == "click" && settings.mouse_mode == "hold")
+        || (settings.action_type == "hold-key" && settings.key_mode == "hold")
+        || settings.action_type == "gamepad-mouse"
+        || settings.action_type == "monitor";
+    if interval == 0 && !is_hold_mode {
+        return Err("Interval must be greater than 0".into());
+    }
+
+    if settings.repeat_mode == "duration" && settings.repeat_duration_secs == 0 {
+        return Err("Duration must be greater than 0".into());
+    }
+
+    if usage::limit_reached(app) {
+        return Err("Daily usage limit reached, try again tomorrow".into());
+    }
+
+    // Outside the allowed hours: when to queue the start for instead, with
+    // the settings as given, since the checks below resolve some in place
+    let mut queue_for = None;
+    if let Some(window) =
+        scheduler::ActivityWindow::parse(&settings.active_from, &settings.active_until)?
+    {
+        let now = chrono::Local::now();
+        if !window.contains(now.time()) {
+            if settings.outside_hours_mode != "queue" {
+                return Err(format!(
+                    "Outside this profile's allowed hours ({})",
+                    window.describe()
+                ));
+            }
+            let at = window
+                .next_open(now)
+                .ok_or("Couldn't work out when the allowed hours open")?;
+            queue_for = Some((at, settings.clone()));
+        }
+    }
+
+    if settings.action_type == "hold-key" && settings.hold_key.is_empty() {
+        return Err("No key selected".into());
+    }
+
+    if settings.block_input && !permissions::allowed(app, permissions::Feature::InputBlocking) {
+        return Err("Input blocking hasn't been allowed in permissions".into());
+    }
+
+    if settings.nudge_enabled {
+        permissions::check(app, permissions::Feature::LiveTuning)?;
+    }
+
+    if settings.block_input && !win_input::is_elevated() {
+        return Err("Blocking user input requires running as administrator".into());
+    }
+
+    if settings.location_mode == "fixed" && settings.coordinate_mode == "percent" {
+        let (x, y) = monitors::percent_to_pixels(
+            &settings.fixed_monitor,
+            settings.fixed_percent_x,
+            settings.fixed_percent_y,
+        )?;
+        settings.fixed_x = x;
+        settings.fixed_y = y;
+        // Already in this resolution's pixels, nothing to scale
+        settings.logical_coords = false;
+    }
+
+    let start_condition = conditions::StartCondition::from_settings(&settings)?;
+    let target_image = vision::Template::from_settings(&settings)?;
+    let stop_text = conditions::TextProbe::stop_from_settings(&settings)?;
+    let target_window = window::TargetWindow::from_settings(&settings)?;
+    let auto_focus = window::AutoFocus::parse(&settings.auto_focus);
+    let anti_afk = anti_afk::AntiAfk::from_settings(&settings)?;
+    let alert_watch = alerts::AlertWatch::from_settings(
+        &settings,
+        !start_condition.is_immediate(),
+        target_window.as_ref(),
+    )?;
+    if auto_focus != window::AutoFocus::Off && target_window.is_none() {
+        return Err("Auto-focus needs a target window".into());
+    }
+
+    // Resolved once per run; the target stays put if displays move mid-run
+    let mapping = if settings.location_mode == "fixed" {
+        monitors::TargetMapping::resolve(
+            &settings.fixed_monitor,
+            settings.logical_coords,
+            settings.fixed_x,
+            settings.fixed_y,
+        )?
+    } else {
+        monitors::TargetMapping::IDENTITY
+    };
+
+    // Only queued once everything above says it would start
+    if let Some((at, settings)) = queue_for {
+        // The queued start takes this lock again when it fires
+        drop(st);
+        let info = scheduler::queue_start(app, settings, label.clone(), at)?;
+        let _ = app.emit(
+            "action-queued",
+            ActionQueued {
+                starts_at: info.next_fire,
+                label,
+            },
+        );
+        return Ok(());
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_clone = Arc::clone(&stop);
+
+    let done = Arc::new(AtomicBool::new(false));
+    let done_clone = Arc::clone(&done);
+
+    let stop_reason = Arc::new(OnceLock::new());
+    let stop_reason_clone = Arc::clone(&stop_reason);
+
+    let idle = Arc::new(IdleWait::default());
+    let idle_clone = Arc::clone(&idle);
+
+    let app_handle = app.clone();
+    let last_settings = settings.clone();
+    let label_clone = label.clone();
+
+    // Monitor-only runs never inject, so they only ever wait
+    let after_countdown = if start_condition.is_immediate() && alert_watch.is_none() {
+        EngineState::Running
+    } else {
+        EngineState::Waiting
+    };
+    engine::transition(
+        app,
+        if settings.start_delay_ms > 0 {
+            EngineState::CountingDown
+        } else {
+            after_countdown
+        },
+    );
+    diagnostics::log(&format!("run started: {}", settings.action_type));
+    let handle = run_on_standby(move || {
+        let _watch = engine::PanicWatch(app_handle.clone());
+        if settings.realtime_priority {
+            win_input::raise_thread_priority();
+        }
+        // Sleep or a blanked display mid-run would strand the inputs
+        let _awake = StayAwake::new();
+
+        // One tick per second; the last step sleeps off any sub-second remainder
+        let mut remaining = Duration::from_millis(settings.start_delay_ms);
+        while !remaining.is_zero() {
+            let _ = app_handle.emit(
+                "countdown-tick",
+                CountdownTick {
+                    remaining_ms: remaining.as_millis() as u64,
+                },
+            );
+            let step = remaining.min(Duration::from_secs(1));
+            if !sleep_unless_stopped(step, &stop_clone) {
+                done_clone.store(true, Ordering::Release);
+                let _ = app_handle.emit(
+                    "action-stopped",
+                    ActionStopped {
+                        reason: stop_reason_clone.get().copied(),
+                        label: label_clone.clone(),
+                    },
+                );
+                engine::settle(&app_handle);
+                return;
+            }
+            remaining -= step;
+        }
+        engine::transition(&app_handle, after_countdown);
 
-    let handle = thread::spawn(move || {
         let is_click = settings.action_type == "click";
         let is_hold = settings.key_mode == "hold";
         let is_mouse_hold = settings.mouse_mode == "hold";
@@ -401,17 +2486,220 @@ fn start_action(
         } else {
             0
         };
+        let meter = usage::UsageMeter::start(app_handle.clone());
+        // Drop counts from before this run (text expansion, remaps)
+        win_input::take_send_stats();
+        let run_until = (settings.repeat_mode == "duration")
+            .then(|| Instant::now() + Duration::from_secs(settings.repeat_duration_secs));
+        // Global guard against forgotten runs, independent of the repeat mode
+        let hard_deadline = (settings.max_runtime_minutes > 0)
+            .then(|| Instant::now() + Duration::from_secs(settings.max_runtime_minutes * 60));
+        let timed_out = || hard_deadline.is_some_and(|t| Instant::now() >= t);
+        let color_watch = conditions::ColorWatch::from_settings(&settings);
+        let text_watch = stop_text.map(conditions::TextWatch::new);
+        let should_stop = || {
+            if stop_clone.load(Ordering::Acquire) {
+                return true;
+            }
+            if color_watch.as_ref().is_some_and(|w| w.changed()) {
+                if stop_reason_clone.set("color-changed").is_ok() {
+                    let label = label_clone.clone();
+                    alerts::trigger_fired(&app_handle, &settings, "color-change", label);
+                }
+                return true;
+            }
+            if text_watch.as_ref().is_some_and(|w| w.matched()) {
+                if stop_reason_clone.set("text-matched").is_ok() {
+                    let label = label_clone.clone();
+                    alerts::trigger_fired(&app_handle, &settings, "stop-text", label);
+                }
+                return true;
+            }
+            let now = Instant::now();
+            run_until.is_some_and(|t| now >= t)
+                || hard_deadline.is_some_and(|t| now >= t)
+                || !meter.active()
+        };
+        let failsafe = Arc::new(AtomicBool::new(false));
+        // A fixed target in a corner would trip the failsafe on every click
+        let (target_x, target_y) = mapping.apply(settings.fixed_x, settings.fixed_y);
+        let corner_target =
+            settings.location_mode == "fixed" && in_screen_corner(target_x, target_y);
+        if settings.failsafe_enabled && !corner_target {
+            spawn_failsafe(
+                Arc::clone(&stop_clone),
+                Arc::clone(&done_clone),
+                Arc::clone(&failsafe),
+                Arc::clone(&idle_clone),
+            );
+        }
+
+        // Mouse presses can only be told apart from the user's own while the
+        // mouse hook is following the buttons
+        let _buttons = is_click.then(hooks::track_buttons);
+        let finish = || {
+            let released = verify_released();
+            if !released.is_empty() {
+                diagnostics::log(&format!("released stuck inputs again: {released:?}"));
+                let _ = app_handle.emit("inputs-released-again", ReleasedAgain { vks: released });
+            }
+            done_clone.store(true, Ordering::Release);
+            report_input_errors(&app_handle);
+            if timed_out() {
+                let _ = app_handle.emit("action-timeout", ());
+            }
+            if failsafe.load(Ordering::Acquire) {
+                let _ = app_handle.emit("action-failsafe", ());
+            }
+            if meter.exhausted() {
+                let _ = app_handle.emit("usage-limit-reached", ());
+            }
+            let reason = stop_reason_clone
+                .get()
+                .copied()
+                .or_else(|| timed_out().then_some("timeout"))
+                .or_else(|| failsafe.load(Ordering::Acquire).then_some("failsafe"))
+                .or_else(|| meter.exhausted().then_some("usage-limit"));
+            diagnostics::log(&format!("run stopped: {}", reason.unwrap_or("user")));
+            let _ = app_handle.emit(
+                "action-stopped",
+                ActionStopped {
+                    reason,
+                    label: label_clone.clone(),
+                },
+            );
+            engine::settle(&app_handle);
+        };
+
+        let gate_timeout = (settings.condition_timeout_secs > 0)
+            .then(|| Duration::from_secs(settings.condition_timeout_secs));
+        let condition_timed_out = |started: bool| {
+            let _ = stop_reason_clone.set("condition-timeout");
+            let _ = app_handle.emit("condition-timeout", ConditionTimeout { started });
+        };
+        let mut gate = start_condition.into_gate();
+        if let Some(watch) = &alert_watch {
+            // Watching isn't active use either
+            let watching = || {
+                meter.idle();
+                should_stop()
+            };
+            watch.run(
+                &app_handle,
+                &mut gate,
+                label_clone.clone(),
+                repeat_count,
+                watching,
+            );
+            finish();
+            return;
+        }
+        if !matches!(gate, conditions::Gate::Immediately) {
+            match wait_for_gate(&mut gate, gate_timeout, &should_stop) {
+                GateWait::Met => {
+                    let label = label_clone.clone();
+                    alerts::trigger_fired(&app_handle, &settings, gate.name(), label);
+                }
+                GateWait::Stopped => {
+                    finish();
+                    return;
+                }
+                GateWait::TimedOut => {
+                    condition_timed_out(false);
+                    finish();
+                    return;
+                }
+            }
+            // Waiting isn't active use
+            meter.idle();
+            engine::transition(&app_handle, EngineState::Running);
+        }
+        let mut gate_checked = Instant::now();
+
+        if let Some(watch) = &color_watch {
+            watch.begin();
+        }
+        if let Some(watch) = &text_watch {
+            watch.begin();
+        }
+
+        let mut focus = target_window.map(window::FocusWatch::new);
+        if auto_focus != window::AutoFocus::Off {
+            if let Some(focus) = focus.as_mut() {
+                focus.refocus();
+            }
+        }
+
+        // Blocked from here rather than from the start, so the user can still
+        // switch windows and set up the start condition
+        let _blocked = settings.block_input.then(BlockedInput::new);
+
+        // Gamepad mode: right stick drives relative mouse movement
+        if settings.action_type == "gamepad-mouse" {
+            let deadzone = settings.gamepad_deadzone.clamp(0.0, 0.95);
+            let speed = settings.gamepad_speed.max(0.0);
+            // Sub-pixel motion carried over so slow tilts still move
+            let (mut carry_x, mut carry_y) = (0.0_f64, 0.0_f64);
+            let mut last_tick = Instant::now();
+            while !should_stop() {
+                precise_sleep(GAMEPAD_TICK, settings.precision_mode);
+                let dt = last_tick.elapsed().as_secs_f64();
+                last_tick = Instant::now();
+
+                let Some((rx, ry)) = win_input::gamepad_right_stick() else {
+                    continue;
+                };
+                let (x, y) = (rx as f64 / 32767.0, ry as f64 / 32767.0);
+                let magnitude = x.hypot(y).min(1.0);
+                if magnitude <= deadzone {
+                    continue;
+                }
+                // Rescale so movement starts from zero at the deadzone edge
+                let scaled = (magnitude - deadzone) / (1.0 - deadzone);
+                let step = speed * scaled * dt / magnitude;
+                // Stick up is positive, screen Y grows downward
+                carry_x += x * step;
+                carry_y -= y * step;
+                let (dx, dy) = (carry_x.trunc(), carry_y.trunc());
+                carry_x -= dx;
+                carry_y -= dy;
+                if dx != 0.0 || dy != 0.0 {
+                    win_input::move_mouse_rel(dx as i32, dy as i32, settings.no_coalesce_moves);
+                }
+            }
+            finish();
+            return;
+        }
+
+        // Anti-AFK: a small nudge every (jittered) interval, nothing between
+        if let Some(anti_afk) = &anti_afk {
+            let mut rounds: u64 = 0;
+            'rounds: loop {
+                let due = Instant::now() + jittered_interval(interval, settings.interval_jitter_ms);
+                while Instant::now() < due {
+                    if should_stop() {
+                        break 'rounds;
+                    }
+                    thread::sleep(STOP_POLL);
+                }
+                anti_afk.nudge(settings.no_coalesce_moves);
+                rounds += 1;
+                if repeat_count > 0 && rounds >= repeat_count {
+                    break;
+                }
+            }
+            finish();
+            return;
+        }
 
         // Key-hold mode: press down, wait for stop, release
         if !is_click && is_hold {
-            let vk = win_input::resolve_vk(&settings.hold_key);
-            win_input::key_down(vk);
-            while !stop_clone.load(Ordering::Acquire) {
+            let held = HeldInput::key(win_input::resolve_vk(&settings.hold_key));
+            while !should_stop() {
                 thread::sleep(Duration::from_millis(50));
             }
-            win_input::key_up(vk);
-            done_clone.store(true, Ordering::Release);
-            let _ = app_handle.emit("action-stopped", ());
+            drop(held);
+            finish();
             return;
         }
 
@@ -445,82 +2733,504 @@ fn start_action(
             let sleep_us = (sleep_secs * 1_000_000.0).round().max(200.0) as u64; // floor at 200µs
 
             if settings.location_mode == "fixed" {
-                win_input::move_mouse_abs(settings.fixed_x, settings.fixed_y);
+                let (x, y) = mapping.apply(settings.fixed_x, settings.fixed_y);
+                move_to(x, y, settings.travel_duration_ms, &stop_clone);
             }
-            win_input::mouse_down(&settings.mouse_button);
-            while !stop_clone.load(Ordering::Acquire) {
+            let held = HeldInput::button(ClickButton::parse(&settings.mouse_button));
+            while !should_stop() {
                 if dx != 0 || dy != 0 {
-                    win_input::move_mouse_rel(dx, dy);
+                    win_input::move_mouse_rel(dx, dy, settings.no_coalesce_moves);
                 }
-                thread::sleep(Duration::from_micros(sleep_us));
+                precise_sleep(Duration::from_micros(sleep_us), settings.precision_mode);
             }
-            win_input::mouse_up(&settings.mouse_button);
-            done_clone.store(true, Ordering::Release);
-            let _ = app_handle.emit("action-stopped", ());
+            drop(held);
+            finish();
             return;
         }
 
-        let mut count: u64 = 0;
-        while !stop_clone.load(Ordering::Acquire) {
-            if is_click {
-                if settings.location_mode == "fixed" {
-                    win_input::move_mouse_abs(settings.fixed_x, settings.fixed_y);
+        let live = Arc::new(LiveTuning::new(&settings, interval));
+        // Checked before starting, so this only fails if it was revoked since
+        let _nudge = settings.nudge_enabled.then(|| {
+            nudge_listener(
+                app_handle.clone(),
+                Arc::clone(&live),
+                Arc::clone(&idle_clone),
+            )
+            .ok()
+        });
+        let typing = settings.pause_while_typing.then(TypingMonitor::start);
+        let mut desktop = DesktopWatch::new();
+        let remote_policy = RemotePolicy::parse(&settings.remote_session_mode);
+        let typing_quiet = Duration::from_millis(settings.typing_quiet_ms);
+
+        let mut ticks = TickLoop::new(&settings, repeat_count, Instant::now());
+        note_pressed(if is_click {
+            Held::Button(ticks.button)
+        } else {
+            Held::Key(ticks.key.0)
+        });
+        let fixed = settings.location_mode == "fixed";
+        let mut finder = target_image.map(vision::Finder::new);
+        let interference = Interference::parse(&settings.interference_mode);
+
+        // Where we last left the cursor, to notice the user taking the mouse
+        let mut placed: Option<(i32, i32)> = None;
+
+        let checkpoint = (settings.checkpoint_enabled && repeat_count > 0).then(|| {
+            checkpoint::Checkpointer::start(
+                app_handle.clone(),
+                &settings,
+                offset,
+                repeat_count,
+                label_clone.clone(),
+            )
+        });
+        let clock = WallClock {
+            idle: &idle_clone,
+            live: &live,
+            deadlines: [run_until, hard_deadline],
+            precise: settings.precision_mode,
+        };
+        let mut host = WorkerTicks {
+            app: &app_handle,
+            live: &live,
+            meter: &meter,
+            app_limit: limits::ProcessLimiter::load(&app_handle),
+            checkpoint,
+            total_inputs: 0,
+            window_start: Instant::now(),
+            window_inputs: 0,
+        };
+        while !should_stop() {
+            let typed = || {
+                typing
+                    .as_ref()
+                    .is_some_and(|t| t.typed_within(typing_quiet))
+            };
+            if typed() {
+                let resumed = pause_while(&app_handle, "typing", &stop_clone, typed);
+                meter.idle();
+                if !resumed {
+                    break;
+                }
+            }
+
+            if gate.holds_run() && gate_checked.elapsed() >= CONDITION_RECHECK {
+                gate_checked = Instant::now();
+                if !gate.met() {
+                    engine::transition(&app_handle, EngineState::Paused);
+                    let _ = app_handle.emit(
+                        "action-paused",
+                        ActionPaused {
+                            reason: "condition",
+                        },
+                    );
+                    let waited = wait_for_gate(&mut gate, gate_timeout, &should_stop);
+                    engine::transition(&app_handle, EngineState::Running);
+                    meter.idle();
+                    match waited {
+                        GateWait::Met => {
+                            let _ = app_handle.emit("action-resumed", ());
+                        }
+                        GateWait::Stopped => break,
+                        GateWait::TimedOut => {
+                            condition_timed_out(true);
+                            break;
+                        }
+                    }
                 }
+            }
 
-                let clicks = if settings.click_type == "double" {
-                    2
-                } else {
-                    1
+            if let Some(focus) = focus.as_mut() {
+                // Each check refocuses when it's "always", so the pause only
+                // lasts while the window can't be brought back
+                let mut focused = || {
+                    focus.focused() || (auto_focus == window::AutoFocus::Always && focus.refocus())
                 };
-                for _ in 0..clicks {
-                    win_input::mouse_click(&settings.mouse_button);
+                if !focused() {
+                    let resumed = pause_while(&app_handle, "unfocused", &stop_clone, || !focused());
+                    meter.idle();
+                    if !resumed {
+                        break;
+                    }
                 }
-            } else {
-                // Key repeat mode — tap at interval
-                let vk = win_input::resolve_vk(&settings.hold_key);
-                win_input::key_press(vk);
             }
 
-            count += 1;
-            if repeat_count > 0 && count >= repeat_count {
-                break;
+            // SendInput is silently dropped while a UAC prompt is up
+            if desktop.secure() {
+                let resumed = pause_while(&app_handle, "secure-desktop", &stop_clone, || {
+                    !win_input::on_default_desktop()
+                });
+                desktop = DesktopWatch::new();
+                meter.idle();
+                if !resumed {
+                    break;
+                }
+            }
+
+            // Injected input over RDP may land on the wrong console
+            if remote_policy != RemotePolicy::Continue && desktop.remote() {
+                if remote_policy == RemotePolicy::Stop {
+                    let _ = stop_reason_clone.set("remote-session");
+                    break;
+                }
+                let resumed = pause_while(
+                    &app_handle,
+                    "remote-session",
+                    &stop_clone,
+                    win_input::is_remote_session,
+                );
+                desktop = DesktopWatch::new();
+                meter.idle();
+                if !resumed {
+                    break;
+                }
             }
 
-            thread::sleep(Duration::from_millis(interval));
+            if is_click {
+                // Searched afresh every click so a target that moves is
+                // still hit
+                let found = finder.as_mut().map(|f| f.locate());
+                if found == Some(None) {
+                    if !sleep_unless_stopped(IMAGE_RETRY, &stop_clone) {
+                        break;
+                    }
+                    continue;
+                }
+                // Where the user had the cursor, to put it back after clicking
+                let mut origin = None;
+                if fixed || found.is_some() {
+                    if placed.is_some_and(cursor_moved_from) {
+                        match interference {
+                            Interference::Stop => {
+                                let _ = app_handle.emit("action-interfered", ());
+                                break;
+                            }
+                            Interference::Pause => {
+                                engine::transition(&app_handle, EngineState::Paused);
+                                let _ = app_handle.emit(
+                                    "action-paused",
+                                    ActionPaused {
+                                        reason: "interference",
+                                    },
+                                );
+                                let resumed = wait_for_idle_cursor(&stop_clone);
+                                engine::transition(&app_handle, EngineState::Running);
+                                let _ = app_handle.emit("action-resumed", ());
+                                meter.idle();
+                                if !resumed {
+                                    break;
+                                }
+                            }
+                            Interference::Off => {}
+                        }
+                    }
+                    let (x, y) = found.flatten().unwrap_or_else(|| {
+                        mapping.apply(
+                            live.x.load(Ordering::Relaxed),
+                            live.y.load(Ordering::Relaxed),
+                        )
+                    });
+                    let (x, y) = jittered_point(x, y, settings.position_jitter_px);
+                    if settings.restore_cursor {
+                        origin = Some(win_input::cursor_pos());
+                    }
+                    move_to(x, y, settings.travel_duration_ms, &stop_clone);
+                    if stop_clone.load(Ordering::Acquire) {
+                        break;
+                    }
+                    // The user moving the cursor is expected when it's handed
+                    // back to them after every click
+                    if !settings.restore_cursor {
+                        placed = Some((x, y));
+                    }
+                }
+
+                let sent = ticks.send(&stop_clone);
+                if let Some((x, y)) = origin {
+                    win_input::move_mouse_abs(x, y);
+                }
+                let Some(sent) = sent else {
+                    break;
+                };
+                if !ticks.wait(sent, &clock, &mut host, &stop_clone) {
+                    break;
+                }
+            } else if !ticks.tick(&clock, &mut host, &stop_clone) {
+                // Key repeat mode — tap at interval
+                break;
+            }
         }
 
-        done_clone.store(true, Ordering::Release);
-        let _ = app_handle.emit("action-stopped", ());
+        if let Some(checkpoint) = host.checkpoint {
+            // Worth resuming after quitting or sleeping, not after the user
+            // stopped it
+            let interrupted = matches!(
+                stop_reason_clone.get().copied(),
+                Some("exit" | "suspend" | "session")
+            );
+            checkpoint.end(ticks.count, interrupted);
+        }
+        finish();
     });
 
     st.stop = Some(stop);
-    st.done = done;
     st.handle = Some(handle);
+    st.stop_reason = stop_reason;
+    st.idle = idle;
+    st.last_settings = Some(last_settings);
+    st.label = label;
 
     Ok(())
 }
 
-#[tauri::command]
-fn stop_action(state: tauri::State<'_, Mutex<InputState>>) -> Result<(), String> {
+/// Like `stop_worker`, recording `reason` in the `action-stopped` event.
+fn stop_worker_for(app: &AppHandle, reason: &'static str) {
+    {
+        let state = app.state::<Mutex<InputState>>();
+        let _ = lock_state(&state).stop_reason.set(reason);
+    }
+    stop_worker(app);
+}
+
+/// Signals the worker to stop and waits for it to release its inputs.
+fn stop_worker(app: &AppHandle) {
+    if let Some(handle) = signal_stop(app) {
+        handle.join();
+    }
+}
+
+/// How long quitting waits for the run to release its inputs. Quitting runs
+/// on the main thread, which mustn't hang on a worker that's stuck; held
+/// inputs get released on exit regardless.
+const EXIT_STOP_WAIT: Duration = Duration::from_secs(2);
+
+/// Stops the run for quitting, waiting at most `EXIT_STOP_WAIT` for it.
+fn stop_worker_for_exit(app: &AppHandle) {
+    {
+        let state = app.state::<Mutex<InputState>>();
+        let _ = lock_state(&state).stop_reason.set("exit");
+    }
+    if let Some(handle) = signal_stop(app) {
+        handle.join_within(EXIT_STOP_WAIT);
+    }
+}
+
+/// Tells the worker to stop without waiting for it, handing back its handle
+/// to wait on. Waiting happens outside the state lock, so the worker
+/// finishing up is never stuck behind whoever is waiting.
+fn signal_stop(app: &AppHandle) -> Option<RunHandle> {
+    let state = app.state::<Mutex<InputState>>();
     let mut st = lock_state(&state);
 
-    if let Some(stop) = &st.stop {
+    if let Some(stop) = st.stop.take() {
+        engine::transition(app, EngineState::Stopping);
         stop.store(true, Ordering::Release);
+        st.idle.wake();
     }
+    st.handle.take()
+}
 
-    if let Some(handle) = st.handle.take() {
-        let _ = handle.join();
+const ESCAPE_PRESSES: usize = 3;
+const ESCAPE_WINDOW: Duration = Duration::from_millis(800);
+
+/// Ctrl+Alt+End, or Escape three times in quick succession, stops
+/// everything. Registered through our own hook rather than the user's
+/// hotkeys so it works even when the webview is frozen.
+fn panic_listener(app: AppHandle) -> hooks::ListenerGuard {
+    let escapes = Mutex::new(Vec::<Instant>::with_capacity(ESCAPE_PRESSES));
+    // Holding Escape auto-repeats; only count fresh presses
+    let escape_held = AtomicBool::new(false);
+    hooks::add_key_listener(move |event| {
+        if event.injected {
+            return false;
+        }
+        let repeat =
+            event.vk == hooks::VK_ESCAPE && escape_held.swap(event.down, Ordering::Relaxed);
+        if !event.down || repeat {
+            return false;
+        }
+        let mods = hooks::modifiers();
+        let combo = event.vk == hooks::VK_END && mods.ctrl && mods.alt;
+        let triple_escape = event.vk == hooks::VK_ESCAPE && {
+            let mut presses = escapes.lock().unwrap_or_else(|e| e.into_inner());
+            presses.retain(|t| t.elapsed() < ESCAPE_WINDOW);
+            presses.push(Instant::now());
+            let hit = presses.len() >= ESCAPE_PRESSES;
+            if hit {
+                presses.clear();
+            }
+            hit
+        };
+        if !combo && !triple_escape {
+            return false;
+        }
+        // Stopping joins the worker, which mustn't block the hook thread
+        let app = app.clone();
+        thread::spawn(move || {
+            scheduler::cancel_schedule(app.clone(), app.state());
+            stop_worker(&app);
+            remap::release_latched_keys(&app);
+            let _ = app.emit("panic-stop", ());
+        });
+        // Escape still reaches the focused app; the combo has no other use
+        combo
+    })
+}
+
+/// Input into a lock screen, another session or a sleeping machine goes
+/// nowhere, and anything we hold down would still be stuck on return, so
+/// stop outright.
+fn on_system_event(app: &AppHandle, event: system_events::SystemEvent) {
+    use system_events::SystemEvent;
+    match event {
+        SystemEvent::SessionLocked | SystemEvent::SessionDisconnected => {
+            stop_worker_for(app, "session");
+            release_held_inputs();
+            remap::release_latched_keys(app);
+        }
+        // Runs before the handler returns, so buttons are up before sleep
+        SystemEvent::Suspending => {
+            stop_worker_for(app, "suspend");
+            release_held_inputs();
+            remap::release_latched_keys(app);
+        }
     }
+}
 
-    st.stop = None;
+/// Emits `foreground-changed` with the window that now has focus.
+fn on_foreground_change(app: &AppHandle, window: window::WindowInfo) {
+    library::switch_profile_for(app, &window);
+    let _ = app.emit("foreground-changed", window);
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+/// `label` is free text naming this run, e.g. "overnight fishing"; it's
+/// echoed in `action-stopped`, `get_status` and diagnostics.
+#[tauri::command]
+fn start_action(
+    app: AppHandle,
+    settings: AutoInputSettings,
+    label: Option<String>,
+) -> Result<(), String> {
+    let label = label
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty());
+    start_worker_from(&app, settings, 0, label)
+}
+
+/// Restarts the most recent run with the exact same settings.
+#[tauri::command]
+fn repeat_last_run(app: AppHandle) -> Result<(), String> {
+    let (settings, label) = {
+        let state = app.state::<Mutex<InputState>>();
+        let st = lock_state(&state);
+        (st.last_settings.clone(), st.label.clone())
+    };
+    start_worker_from(&app, settings.ok_or("Nothing has run yet")?, 0, label)
+}
 
+#[tauri::command]
+fn stop_action(app: AppHandle) -> Result<(), String> {
+    stop_worker(&app);
     Ok(())
 }
 
 #[tauri::command]
-fn is_running(state: tauri::State<'_, Mutex<InputState>>) -> bool {
+fn is_running(app: AppHandle) -> bool {
+    engine::current(&app).is_running()
+}
+
+/// What the engine is doing and, while it's sleeping out a long interval,
+/// when its next input is due (Unix ms).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunStatus {
+    pub state: EngineState,
+    pub running: bool,
+    pub next_fire_ms: Option<u64>,
+    pub label: Option<String>,
+}
+
+#[tauri::command]
+fn get_status(app: AppHandle, state: tauri::State<'_, Mutex<InputState>>) -> RunStatus {
+    let engine_state = engine::current(&app);
+    let running = engine_state.is_running();
     let st = lock_state(&state);
-    st.handle.is_some() && !st.done.load(Ordering::Acquire)
+    RunStatus {
+        state: engine_state,
+        running,
+        next_fire_ms: st.idle.next_fire_ms().filter(|_| running),
+        label: st.label.clone().filter(|_| running),
+    }
+}
+
+/// Whether the app was launched with `--safe-mode`; the frontend skips
+/// registering hotkeys.
+#[tauri::command]
+fn is_safe_mode() -> bool {
+    safe_mode()
+}
+
+/// The dwExtraInfo value stamped on every event AutoInput injects.
+#[tauri::command]
+fn get_injection_signature() -> u64 {
+    injection_signature() as u64
+}
+
+/// Sets a custom signature; None or 0 goes back to the default. Returns the
+/// signature now in effect.
+#[tauri::command]
+fn set_injection_signature(
+    storage: tauri::State<'_, Mutex<storage::Storage>>,
+    value: Option<u64>,
+) -> Result<u64, String> {
+    let value = value.filter(|&v| v != 0);
+    if value.is_some_and(|v| v > usize::MAX as u64) {
+        return Err("Signature is too large for this platform".into());
+    }
+    storage::lock_storage(&storage).write_key(INJECTION_SIGNATURE_KEY, serde_json::json!(value))?;
+    let signature = value.map_or(DEFAULT_INJECTION_SIGNATURE, |v| v as usize);
+    INJECTION_SIGNATURE.store(signature, Ordering::Relaxed);
+    Ok(signature as u64)
+}
+
+#[tauri::command]
+fn is_elevated() -> bool {
+    win_input::is_elevated()
+}
+
+/// Restarts the app as administrator so it can click into elevated windows.
+/// This instance exits once the elevated one has been launched. The data
+/// directory is unlocked first so the new copy doesn't open it read-only,
+/// and locked again if the launch is cancelled.
+#[tauri::command]
+fn relaunch_elevated(
+    app: AppHandle,
+    storage: tauri::State<'_, Mutex<storage::Storage>>,
+) -> Result<(), String> {
+    if win_input::is_elevated() {
+        return Err("Already running as administrator".into());
+    }
+    stop_worker(&app);
+    let held = {
+        let mut storage = storage::lock_storage(&storage);
+        let held = !storage.is_read_only();
+        storage.release();
+        held
+    };
+    // Blocks on the consent prompt, so not under the storage lock
+    if let Err(e) = win_input::relaunch_elevated() {
+        if held {
+            storage::lock_storage(&storage).reacquire();
+        }
+        return Err(e);
+    }
+    app.exit(0);
+    Ok(())
 }
 
 #[tauri::command]
@@ -531,31 +3241,179 @@ fn show_main_window(app: AppHandle) {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Diagnostics
+// ---------------------------------------------------------------------------
+
+const BENCHMARK_DEFAULT_SAMPLES: usize = 200;
+/// Wall time a benchmark may take, whatever the interval
+const BENCHMARK_MAX_DURATION: Duration = Duration::from_secs(2);
+const BENCHMARK_MIN_SAMPLES: u32 = 5;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TimingReport {
+    target_ms: f64,
+    /// The configured interval was too long to sample within the time limit,
+    /// so `target_ms` is a shorter one with the same sleep path
+    shortened: bool,
+    samples: usize,
+    min_ms: f64,
+    avg_ms: f64,
+    max_ms: f64,
+    /// 99th percentile of |actual - target|
+    p99_jitter_ms: f64,
+}
+
+/// Runs the worker's sleep loop without injecting anything and reports how
+/// close the achieved intervals get to the configured one. Never takes much
+/// longer than `BENCHMARK_MAX_DURATION`.
+#[tauri::command(async)]
+fn benchmark_timing(
+    settings: AutoInputSettings,
+    samples: Option<usize>,
+) -> Result<TimingReport, String> {
+    let interval = calc_interval_ms(&settings);
+    if interval == 0 {
+        return Err("Interval must be greater than 0".into());
+    }
+    let target =
+        Duration::from_millis(interval).min(BENCHMARK_MAX_DURATION / BENCHMARK_MIN_SAMPLES);
+    let shortened = target < Duration::from_millis(interval);
+    let budget = (BENCHMARK_MAX_DURATION.as_nanos() / target.as_nanos()) as usize;
+    let samples = samples
+        .unwrap_or(BENCHMARK_DEFAULT_SAMPLES)
+        .clamp(BENCHMARK_MIN_SAMPLES as usize, budget);
+
+    // Dedicated thread so a raised priority doesn't leak into the runtime pool
+    let measured = thread::spawn(move || {
+        if settings.realtime_priority {
+            win_input::raise_thread_priority();
+        }
+        let mut intervals = Vec::with_capacity(samples);
+        let start = Instant::now();
+        let mut last = start;
+        for _ in 0..samples {
+            precise_sleep(target, settings.precision_mode);
+            let now = Instant::now();
+            intervals.push(now - last);
+            last = now;
+            // Oversleeping can't stretch the run past the limit either
+            if now - start >= BENCHMARK_MAX_DURATION {
+                break;
+            }
+        }
+        intervals
+    })
+    .join()
+    .map_err(|_| "Benchmark thread panicked".to_string())?;
+
+    let to_ms = |d: Duration| d.as_secs_f64() * 1000.0;
+    let target_ms = to_ms(target);
+    let actual: Vec<f64> = measured.iter().map(|d| to_ms(*d)).collect();
+    let mut jitter: Vec<f64> = actual.iter().map(|ms| (ms - target_ms).abs()).collect();
+    jitter.sort_by(f64::total_cmp);
+    let p99_index = ((jitter.len() as f64 * 0.99).ceil() as usize).saturating_sub(1);
+
+    Ok(TimingReport {
+        target_ms,
+        shortened,
+        samples: actual.len(),
+        min_ms: actual.iter().copied().fold(f64::INFINITY, f64::min),
+        avg_ms: actual.iter().sum::<f64>() / actual.len() as f64,
+        max_ms: actual.iter().copied().fold(0.0, f64::max),
+        p99_jitter_ms: jitter[p99_index],
+    })
+}
+
+const CPS_TEST_DURATION: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CpsReport {
+    /// Single clicks per second the injection path sustained
+    max_cps: f64,
+    /// Same, for double-click mode (two clicks per iteration)
+    max_double_cps: f64,
+}
+
+/// Injects harmless no-op events back to back for a second to find how many
+/// clicks per second this machine can actually push through SendInput.
+#[tauri::command(async)]
+fn measure_max_cps(app: AppHandle) -> Result<CpsReport, String> {
+    if engine::current(&app).is_active() {
+        return Err("Stop the running action before measuring".into());
+    }
+
+    let (count, elapsed) = thread::spawn(|| {
+        let start = Instant::now();
+        let mut count: u64 = 0;
+        while start.elapsed() < CPS_TEST_DURATION {
+            win_input::test_sink_event();
+            count += 1;
+        }
+        (count, start.elapsed())
+    })
+    .join()
+    .map_err(|_| "CPS test thread panicked".to_string())?;
+
+    let max_cps = count as f64 / elapsed.as_secs_f64();
+    Ok(CpsReport {
+        max_cps,
+        max_double_cps: max_cps / 2.0,
+    })
+}
+
 // ---------------------------------------------------------------------------
 // App entry
 // ---------------------------------------------------------------------------
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    monitors::enable_dpi_awareness();
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        release_held_inputs();
+        let _ = verify_released();
+        diagnostics::record_crash(info);
+        default_hook(info);
+    }));
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::new().build())
         .setup(|app| {
+            diagnostics::init(app.handle());
+            app.manage(Mutex::new(storage::Storage::open(app.handle())));
+            storage::spawn_watcher(app.handle().clone());
+            permissions::load(app.handle());
+            // Both stay off in safe mode; see `permissions`
+            expander::load(app.handle());
+            remap::load(app.handle());
+            usage::load(app.handle());
+            load_injection_signature(app.handle());
+            warm_standby();
+            // Lives for the whole process
+            std::mem::forget(panic_listener(app.handle().clone()));
+            let handle = app.handle().clone();
+            system_events::watch(move |event| on_system_event(&handle, event));
+            let handle = app.handle().clone();
+            foreground::watch(move |window| on_foreground_change(&handle, window));
+
             #[cfg(desktop)]
             {
                 app.handle()
                     .plugin(tauri_plugin_global_shortcut::Builder::new().build())?;
 
-                let show_i = MenuItemBuilder::with_id("show", "Show AutoInput").build(app)?;
-                let hide_i = MenuItemBuilder::with_id("hide", "Minimize to Tray").build(app)?;
-                let quit_i = MenuItemBuilder::with_id("quit", "Exit").build(app)?;
-                let menu = MenuBuilder::new(app)
-                    .items(&[&show_i, &hide_i, &quit_i])
-                    .build()?;
+                let menu = build_tray_menu(app.handle())?;
 
-                let _tray = TrayIconBuilder::new()
+                let _tray = TrayIconBuilder::with_id(TRAY_ID)
                     .icon(app.default_window_icon().unwrap().clone())
-                    .tooltip("AutoInput")
+                    .tooltip(if safe_mode() {
+                        "AutoInput (safe mode)"
+                    } else {
+                        "AutoInput"
+                    })
                     .menu(&menu)
                     .show_menu_on_left_click(false)
                     .on_menu_event(|app, event| match event.id().as_ref() {
@@ -571,10 +3429,17 @@ pub fn run() {
                                 let _ = w.hide();
                             }
                         }
+                        "repeat" => {
+                            let _ = repeat_last_run(app.clone());
+                        }
                         "quit" => {
                             app.exit(0);
                         }
-                        _ => {}
+                        id => {
+                            if let Some(config_id) = id.strip_prefix(library::RUN_PREFIX) {
+                                let _ = library::run_saved(app, config_id);
+                            }
+                        }
                     })
                     .on_tray_icon_event(|tray, event| {
                         if let TrayIconEvent::Click {
@@ -597,12 +3462,135 @@ pub fn run() {
             Ok(())
         })
         .manage(Mutex::new(InputState::default()))
+        .manage(Mutex::new(engine::EngineState::default()))
+        .manage(Mutex::new(scheduler::SchedulerState::default()))
+        .manage(Mutex::new(expander::ExpanderState::default()))
+        .manage(Mutex::new(remap::RemapState::default()))
+        .manage(Mutex::new(usage::UsageState::default()))
+        .manage(Mutex::new(permissions::FeaturePermissions::default()))
+        .manage(Mutex::new(library::ProfileSwitchState::default()))
+        .manage(Mutex::new(process_watch::ProcessWatchState::default()))
+        .manage(Mutex::new(idle_trigger::IdleTriggerState::default()))
+        .manage(Mutex::new(hot_corner::HotCornerState::default()))
+        .manage(Mutex::new(hotkeys::HotkeyState::default()))
+        .on_window_event(|window, event| {
+            if window.label() == "main" && matches!(event, WindowEvent::Destroyed) {
+                stop_worker_for_exit(window.app_handle());
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             start_action,
+            repeat_last_run,
             stop_action,
             is_running,
+            get_status,
+            checkpoint::get_resumable_run,
+            checkpoint::discard_resumable_run,
+            checkpoint::resume_run,
+            library::search_items,
+            library::list_tags,
+            library::run_saved_config,
+            library::get_active_profile,
+            process_watch::watch_process,
+            process_watch::unwatch_process,
+            process_watch::get_process_watch,
+            idle_trigger::arm_idle_trigger,
+            idle_trigger::disarm_idle_trigger,
+            idle_trigger::get_idle_trigger,
+            hot_corner::arm_hot_corner,
+            hot_corner::disarm_hot_corner,
+            hot_corner::get_hot_corner,
+            hotkeys::register_hotkeys,
+            hotkeys::unregister_hotkeys,
+            overlay::show_debug_overlay,
+            overlay::hide_debug_overlay,
+            monitors::list_monitors,
+            monitors::get_screen_metrics,
+            compat::check_profile_compat,
+            picker::pick_position,
+            picker::pick_color,
+            capture::sample_frame_rate,
+            capture::get_pixel_color,
+            capture::capture_region,
+            ocr::read_screen_text,
+            conditions::test_trigger,
+            window::list_windows,
+            window::list_controls,
+            anti_afk::list_presets,
+            window::control_at,
+            capture::start_cursor_tracking,
+            capture::stop_cursor_tracking,
+            get_injection_signature,
+            is_safe_mode,
+            permissions::get_feature_permissions,
+            permissions::set_feature_permission,
+            set_injection_signature,
+            is_elevated,
+            relaunch_elevated,
             show_main_window,
+            benchmark_timing,
+            measure_max_cps,
+            diagnostics::export_diagnostics,
+            expander::get_text_expansions,
+            expander::set_text_expansions,
+            limits::get_process_rate_limits,
+            limits::set_process_rate_limits,
+            remap::get_toggle_keys,
+            remap::set_toggle_keys,
+            remap::get_wheel_keys,
+            remap::set_wheel_keys,
+            scheduler::schedule_action,
+            scheduler::cancel_schedule,
+            scheduler::get_schedule,
+            usage::get_usage_today,
+            usage::set_daily_limit,
+            storage::get_data_dir,
+            storage::set_data_dir,
+            storage::read_store,
+            storage::write_store,
+            storage::list_backups,
+            storage::restore_backup,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| match event {
+            // Same path as `stop_action`, so the worker winds down cleanly
+            // rather than being cut off mid-input
+            RunEvent::ExitRequested { .. } => {
+                scheduler::cancel_schedule(app.clone(), app.state());
+                stop_worker_for_exit(app);
+            }
+            RunEvent::Exit => {
+                // Threads die with the process, so let go of held inputs now
+                release_held_inputs();
+                remap::release_latched_keys(app);
+                if let Some(storage) = app.try_state::<Mutex<storage::Storage>>() {
+                    storage::lock_storage(&storage).release();
+                }
+            }
+            _ => {}
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interference_mode_parse() {
+        assert_eq!(Interference::parse("pause"), Interference::Pause);
+        assert_eq!(Interference::parse("stop"), Interference::Stop);
+        assert_eq!(Interference::parse("off"), Interference::Off);
+        assert_eq!(Interference::parse(""), Interference::Off);
+        assert_eq!(Interference::parse("Pause"), Interference::Off);
+    }
+
+    #[test]
+    fn interference_is_off_by_default() {
+        let settings = AutoInputSettings::default();
+        assert_eq!(
+            Interference::parse(&settings.interference_mode),
+            Interference::Off
+        );
+    }
 }
@@ -0,0 +1,242 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use regex_lite::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::menu::{MenuItemBuilder, Submenu, SubmenuBuilder};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::engine;
+use crate::storage::{self, Storage};
+use crate::window::WindowInfo;
+use crate::AutoInputSettings;
+
+// ---------------------------------------------------------------------------
+// Saved automations
+//
+// The frontend owns the saved configs (`appState.configs` in the store, plus
+// any from the shared directory); this reads them so they can be searched by
+// name and tag and started from the tray, where the "Run" submenu groups them
+// by tag.
+// ---------------------------------------------------------------------------
+
+const UNTAGGED: &str = "Untagged";
+pub const RUN_PREFIX: &str = "run:";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedItem {
+    pub id: String,
+    pub name: String,
+    /// Always "config" for now
+    pub kind: &'static str,
+    pub tags: Vec<String>,
+}
+
+fn saved_configs(store: &Value) -> impl Iterator<Item = (SavedItem, &Value)> {
+    store
+        .pointer("/appState/configs")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|config| {
+            let item = SavedItem {
+                id: config.get("id")?.as_str()?.to_string(),
+                name: config.get("name")?.as_str()?.to_string(),
+                kind: "config",
+                tags: config
+                    .get("tags")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect(),
+            };
+            Some((item, config))
+        })
+}
+
+/// The store as the frontend sees it, including shared configs.
+fn read_with_shared(storage: &Mutex<Storage>) -> Value {
+    let storage = storage::lock_storage(storage);
+    storage.with_shared(storage.read_store())
+}
+
+fn has_tag(item: &SavedItem, tag: &str) -> bool {
+    item.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+}
+
+/// Starts the saved config with `id`.
+pub fn run_saved(app: &AppHandle, id: &str) -> Result<(), String> {
+    let store = read_with_shared(&app.state::<Mutex<Storage>>());
+    let config = saved_configs(&store)
+        .find(|(item, _)| item.id == id)
+        .map(|(_, config)| config.clone())
+        .ok_or("Saved config not found")?;
+    // Configs share the settings field names, so they deserialize directly
+    let settings: AutoInputSettings = serde_json::from_value(config).map_err(|e| e.to_string())?;
+    crate::start_worker(app, settings)
+}
+
+/// Tray submenu with one entry per saved config, grouped by tag. A config
+/// with several tags shows up under each.
+pub fn run_submenu(app: &AppHandle) -> tauri::Result<Submenu<tauri::Wry>> {
+    let store = read_with_shared(&app.state::<Mutex<Storage>>());
+    let mut groups: BTreeMap<String, Vec<SavedItem>> = BTreeMap::new();
+    for (item, _) in saved_configs(&store) {
+        if item.tags.is_empty() {
+            groups.entry(UNTAGGED.into()).or_default().push(item);
+            continue;
+        }
+        for tag in &item.tags {
+            groups.entry(tag.clone()).or_default().push(item.clone());
+        }
+    }
+
+    let mut menu = SubmenuBuilder::new(app, "Run");
+    if groups.is_empty() {
+        let empty = MenuItemBuilder::with_id("run-empty", "No saved configs")
+            .enabled(false)
+            .build(app)?;
+        return menu.item(&empty).build();
+    }
+    // Skip the extra level when nothing is tagged
+    if groups.len() == 1 && groups.contains_key(UNTAGGED) {
+        for item in &groups[UNTAGGED] {
+            menu = menu.text(format!("{RUN_PREFIX}{}", item.id), &item.name);
+        }
+        return menu.build();
+    }
+    for (tag, items) in &groups {
+        let mut group = SubmenuBuilder::new(app, tag);
+        for item in items {
+            group = group.text(format!("{RUN_PREFIX}{}", item.id), &item.name);
+        }
+        menu = menu.item(&group.build()?);
+    }
+    menu.build()
+}
+
+// ---------------------------------------------------------------------------
+// Per-application profiles
+//
+// A saved config can name an executable (`switchApp`, e.g. "game.exe") or a
+// window title pattern (`switchTitle`). When a matching window comes to the
+// foreground that config becomes the active profile, and `profile-switched`
+// tells the frontend to load it. Switching to an unmatched window keeps the
+// current profile, and nothing switches while a run is going.
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Default)]
+pub struct ProfileSwitchState {
+    active: Option<String>,
+}
+
+/// Payload of `profile-switched`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSwitched {
+    pub id: String,
+    pub name: String,
+    /// The config asks for its hotkeys to be registered when it's switched to
+    pub arm_hotkeys: bool,
+}
+
+fn config_str<'a>(config: &'a Value, key: &str) -> &'a str {
+    config.get(key).and_then(Value::as_str).unwrap_or("").trim()
+}
+
+fn switches_for(config: &Value, window: &WindowInfo) -> bool {
+    let app = config_str(config, "switchApp");
+    if !app.is_empty() && app.eq_ignore_ascii_case(&window.process_name) {
+        return true;
+    }
+    let title = config_str(config, "switchTitle");
+    !title.is_empty()
+        && Regex::new(&format!("(?i){title}")).is_ok_and(|pattern| pattern.is_match(&window.title))
+}
+
+/// Makes the first saved config associated with `window` the active
+/// profile, if it isn't already.
+pub fn switch_profile_for(app: &AppHandle, window: &WindowInfo) {
+    if engine::current(app).is_active() {
+        return;
+    }
+    let store = read_with_shared(&app.state::<Mutex<Storage>>());
+    let Some((item, config)) = saved_configs(&store).find(|(_, c)| switches_for(c, window)) else {
+        return;
+    };
+    {
+        let state = app.state::<Mutex<ProfileSwitchState>>();
+        let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.active.as_deref() == Some(item.id.as_str()) {
+            return;
+        }
+        state.active = Some(item.id.clone());
+    }
+    let _ = app.emit(
+        "profile-switched",
+        ProfileSwitched {
+            arm_hotkeys: config
+                .get("armHotkeysOnSwitch")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            id: item.id,
+            name: item.name,
+        },
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+/// Saved items whose name or a tag contains `query` (case-insensitive) and
+/// that carry every tag in `tags`. An empty query matches everything.
+#[tauri::command]
+pub fn search_items(
+    storage: tauri::State<'_, Mutex<Storage>>,
+    query: String,
+    tags: Vec<String>,
+) -> Vec<SavedItem> {
+    let store = read_with_shared(&storage);
+    let query = query.trim().to_lowercase();
+    saved_configs(&store)
+        .map(|(item, _)| item)
+        .filter(|item| tags.iter().all(|tag| has_tag(item, tag)))
+        .filter(|item| {
+            query.is_empty()
+                || item.name.to_lowercase().contains(&query)
+                || item.tags.iter().any(|t| t.to_lowercase().contains(&query))
+        })
+        .collect()
+}
+
+/// Every tag in use, sorted, for filter chips.
+#[tauri::command]
+pub fn list_tags(storage: tauri::State<'_, Mutex<Storage>>) -> Vec<String> {
+    let store = read_with_shared(&storage);
+    let mut tags: Vec<String> = saved_configs(&store)
+        .flat_map(|(item, _)| item.tags)
+        .collect();
+    tags.sort_by_key(|t| t.to_lowercase());
+    tags.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    tags
+}
+
+#[tauri::command]
+pub fn run_saved_config(app: AppHandle, id: String) -> Result<(), String> {
+    run_saved(&app, &id)
+}
+
+/// Id of the config last switched to for the foreground app, if any.
+#[tauri::command]
+pub fn get_active_profile(state: tauri::State<'_, Mutex<ProfileSwitchState>>) -> Option<String> {
+    state
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .active
+        .clone()
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Manager};
+
+use crate::storage::{self, Storage};
+use crate::win_input;
+
+// ---------------------------------------------------------------------------
+// Per-application rate limits
+//
+// Maximum inputs per second keyed by executable name, stored outside any
+// profile so an imported profile can't raise them. The worker applies the
+// limit of whichever process owns the foreground window.
+// ---------------------------------------------------------------------------
+
+const STORE_KEY: &str = "processRateLimits";
+const RECHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+fn read_limits(storage: &Storage) -> HashMap<String, u32> {
+    storage
+        .read_store()
+        .get(STORE_KEY)
+        .cloned()
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Caches the foreground process's limit, re-resolving it at most every
+/// `RECHECK_INTERVAL` so the click loop isn't querying processes per input.
+pub struct ProcessLimiter {
+    limits: HashMap<String, u32>,
+    checked_at: Option<Instant>,
+    current: Option<u32>,
+}
+
+impl ProcessLimiter {
+    pub fn load(app: &AppHandle) -> Self {
+        let limits = read_limits(&storage::lock_storage(&app.state::<Mutex<Storage>>()));
+        Self {
+            limits,
+            checked_at: None,
+            current: None,
+        }
+    }
+
+    /// Minimum time per tick of `inputs` inputs allowed in the foreground app.
+    pub fn min_tick(&mut self, inputs: u64) -> Duration {
+        if self.limits.is_empty() {
+            return Duration::ZERO;
+        }
+        if self
+            .checked_at
+            .is_none_or(|t| t.elapsed() >= RECHECK_INTERVAL)
+        {
+            self.current = win_input::foreground_process_name()
+                .and_then(|name| self.limits.get(&name).copied());
+            self.checked_at = Some(Instant::now());
+        }
+        match self.current {
+            Some(max_cps) => Duration::from_secs_f64(inputs as f64 / max_cps.max(1) as f64),
+            None => Duration::ZERO,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub fn get_process_rate_limits(storage: tauri::State<'_, Mutex<Storage>>) -> HashMap<String, u32> {
+    read_limits(&storage::lock_storage(&storage))
+}
+
+/// Replaces the limits. Keys are executable names such as "game.exe" and are
+/// matched case-insensitively; takes effect from the next run.
+#[tauri::command]
+pub fn set_process_rate_limits(
+    storage: tauri::State<'_, Mutex<Storage>>,
+    limits: HashMap<String, u32>,
+) -> Result<(), String> {
+    if limits.values().any(|&cps| cps == 0) {
+        return Err("Limit must be greater than 0".into());
+    }
+    let limits: HashMap<String, u32> = limits
+        .into_iter()
+        .map(|(name, cps)| (name.trim().to_lowercase(), cps))
+        .collect();
+    let value = serde_json::to_value(&limits).map_err(|e| e.to_string())?;
+    storage::lock_storage(&storage).write_key(STORE_KEY, value)
+}
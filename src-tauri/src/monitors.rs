@@ -0,0 +1,303 @@
+use serde::Serialize;
+
+// ---------------------------------------------------------------------------
+// Monitors
+//
+// Lets a fixed click target be stored relative to a monitor instead of in
+// global pixels, which shift whenever displays are rearranged. Monitors are
+// identified by their GDI device name (e.g. `\\.\DISPLAY2`), which stays put
+// across rearrangement as long as the same ports are used.
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorInfo {
+    pub id: String,
+    /// Display name reported by the driver, e.g. "Generic PnP Monitor"
+    pub name: String,
+    /// Bounds in global screen pixels
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    /// Effective DPI, which includes the user's scaling setting
+    pub dpi: u32,
+    /// 1.0 at 96 DPI
+    pub scale_factor: f64,
+    pub primary: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenMetrics {
+    /// Bounding box of every monitor; its origin is negative when a monitor
+    /// sits left of or above the primary
+    pub virtual_screen: Rect,
+    pub monitors: Vec<MonitorInfo>,
+}
+
+pub fn list() -> Vec<MonitorInfo> {
+    imp::list()
+}
+
+/// Bounding box of every monitor, in global pixels.
+pub fn virtual_screen() -> Rect {
+    imp::virtual_screen()
+}
+
+/// The monitor containing the global point, if any.
+pub fn at(x: i32, y: i32) -> Option<MonitorInfo> {
+    imp::list()
+        .into_iter()
+        .find(|m| (m.x..m.x + m.width).contains(&x) && (m.y..m.y + m.height).contains(&y))
+}
+
+/// Converts a target given in percent of a monitor's size to pixels: relative
+/// to `monitor` if set, else global pixels on the primary monitor.
+pub fn percent_to_pixels(monitor: &str, x: f64, y: f64) -> Result<(i32, i32), String> {
+    if !(0.0..=100.0).contains(&x) || !(0.0..=100.0).contains(&y) {
+        return Err("Percent coordinates must be between 0 and 100".into());
+    }
+    let monitors = imp::list();
+    let found = if monitor.is_empty() {
+        monitors.into_iter().find(|m| m.primary)
+    } else {
+        monitors.into_iter().find(|m| m.id == monitor)
+    };
+    let m = found.ok_or("The selected monitor isn't connected")?;
+    // The far edge is the last pixel, not one past it
+    let scale =
+        |percent: f64, size: i32| (percent / 100.0 * (size - 1).max(0) as f64).round() as i32;
+    let (px, py) = (scale(x, m.width), scale(y, m.height));
+    Ok(if monitor.is_empty() {
+        (m.x + px, m.y + py)
+    } else {
+        (px, py)
+    })
+}
+
+/// Maps a stored fixed target to global physical pixels, resolved once per
+/// run so nudges can keep adjusting the stored point.
+#[derive(Debug, Clone, Copy)]
+pub struct TargetMapping {
+    /// Stored point that corresponds to `origin`
+    base: (i32, i32),
+    /// Physical position of `base`
+    origin: (i32, i32),
+    scale: f64,
+}
+
+impl TargetMapping {
+    /// Global physical pixels map to themselves
+    pub const IDENTITY: Self = Self {
+        base: (0, 0),
+        origin: (0, 0),
+        scale: 1.0,
+    };
+
+    /// `monitor` is the id the point is relative to, or empty for global
+    /// coordinates. `logical` points are in DPI-scaled pixels, e.g. captured
+    /// at 100% scaling, and get scaled up on high-DPI monitors.
+    pub fn resolve(monitor: &str, logical: bool, x: i32, y: i32) -> Result<Self, String> {
+        if monitor.is_empty() && !logical {
+            return Ok(Self::IDENTITY);
+        }
+        let monitors = imp::list();
+        let found = if monitor.is_empty() {
+            // Monitors keep their physical origin in logical space and
+            // shrink by their scale factor
+            monitors.into_iter().find(|m| {
+                let width = (m.width as f64 / m.scale_factor) as i32;
+                let height = (m.height as f64 / m.scale_factor) as i32;
+                (m.x..m.x + width).contains(&x) && (m.y..m.y + height).contains(&y)
+            })
+        } else {
+            monitors.into_iter().find(|m| m.id == monitor)
+        };
+        let Some(m) = found else {
+            if monitor.is_empty() {
+                // Off every monitor; leave it for the OS to clamp
+                return Ok(Self::IDENTITY);
+            }
+            return Err("The selected monitor isn't connected".into());
+        };
+        Ok(Self {
+            base: if monitor.is_empty() {
+                (m.x, m.y)
+            } else {
+                (0, 0)
+            },
+            origin: (m.x, m.y),
+            scale: if logical { m.scale_factor } else { 1.0 },
+        })
+    }
+
+    pub fn apply(&self, x: i32, y: i32) -> (i32, i32) {
+        let map = |v: i32, base: i32, origin: i32| {
+            origin + ((v - base) as f64 * self.scale).round() as i32
+        };
+        (
+            map(x, self.base.0, self.origin.0),
+            map(y, self.base.1, self.origin.1),
+        )
+    }
+}
+
+/// Opts the process into per-monitor DPI awareness so cursor positions and
+/// monitor bounds are physical pixels everywhere. Must run before any window
+/// is created; harmless if the manifest already did it.
+pub fn enable_dpi_awareness() {
+    imp::enable_dpi_awareness();
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use windows::core::{BOOL, PCWSTR};
+    use windows::Win32::Foundation::{LPARAM, RECT};
+    use windows::Win32::Graphics::Gdi::{
+        EnumDisplayDevicesW, EnumDisplayMonitors, GetMonitorInfoW, DISPLAY_DEVICEW, HDC, HMONITOR,
+        MONITORINFO, MONITORINFOEXW,
+    };
+    use windows::Win32::UI::HiDpi::{
+        GetDpiForMonitor, SetProcessDpiAwarenessContext,
+        DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2, MDT_EFFECTIVE_DPI,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetSystemMetrics, MONITORINFOF_PRIMARY, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN,
+        SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN,
+    };
+
+    use super::{MonitorInfo, Rect};
+
+    fn wide_to_string(wide: &[u16]) -> String {
+        let len = wide.iter().position(|&c| c == 0).unwrap_or(wide.len());
+        String::from_utf16_lossy(&wide[..len])
+    }
+
+    unsafe fn describe(monitor: HMONITOR) -> Option<MonitorInfo> {
+        let mut info = MONITORINFOEXW::default();
+        info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+        if !GetMonitorInfoW(
+            monitor,
+            &mut info as *mut MONITORINFOEXW as *mut MONITORINFO,
+        )
+        .as_bool()
+        {
+            return None;
+        }
+        let id = wide_to_string(&info.szDevice);
+
+        // The first device under the adapter is the attached monitor
+        let mut device = DISPLAY_DEVICEW {
+            cb: std::mem::size_of::<DISPLAY_DEVICEW>() as u32,
+            ..Default::default()
+        };
+        let name =
+            if EnumDisplayDevicesW(PCWSTR(info.szDevice.as_ptr()), 0, &mut device, 0).as_bool() {
+                wide_to_string(&device.DeviceString)
+            } else {
+                id.clone()
+            };
+
+        let (mut dpi_x, mut dpi_y) = (96, 96);
+        let _ = GetDpiForMonitor(monitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y);
+
+        let rect = info.monitorInfo.rcMonitor;
+        Some(MonitorInfo {
+            id,
+            name,
+            x: rect.left,
+            y: rect.top,
+            width: rect.right - rect.left,
+            height: rect.bottom - rect.top,
+            dpi: dpi_x,
+            scale_factor: dpi_x as f64 / 96.0,
+            primary: info.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0,
+        })
+    }
+
+    unsafe extern "system" fn collect(
+        monitor: HMONITOR,
+        _hdc: HDC,
+        _rect: *mut RECT,
+        data: LPARAM,
+    ) -> BOOL {
+        let monitors = &mut *(data.0 as *mut Vec<MonitorInfo>);
+        monitors.extend(describe(monitor));
+        BOOL(1)
+    }
+
+    pub fn enable_dpi_awareness() {
+        unsafe {
+            let _ = SetProcessDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2);
+        }
+    }
+
+    pub fn list() -> Vec<MonitorInfo> {
+        let mut monitors = Vec::new();
+        unsafe {
+            let _ = EnumDisplayMonitors(
+                None,
+                None,
+                Some(collect),
+                LPARAM(&mut monitors as *mut Vec<MonitorInfo> as isize),
+            );
+        }
+        monitors
+    }
+
+    pub fn virtual_screen() -> Rect {
+        unsafe {
+            Rect {
+                x: GetSystemMetrics(SM_XVIRTUALSCREEN),
+                y: GetSystemMetrics(SM_YVIRTUALSCREEN),
+                width: GetSystemMetrics(SM_CXVIRTUALSCREEN),
+                height: GetSystemMetrics(SM_CYVIRTUALSCREEN),
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod imp {
+    pub fn enable_dpi_awareness() {}
+    pub fn list() -> Vec<super::MonitorInfo> {
+        Vec::new()
+    }
+    pub fn virtual_screen() -> super::Rect {
+        super::Rect {
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 0,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub fn list_monitors() -> Vec<MonitorInfo> {
+    imp::list()
+}
+
+/// Everything needed to check a fixed target lands on a screen before
+/// starting.
+#[tauri::command]
+pub fn get_screen_metrics() -> ScreenMetrics {
+    ScreenMetrics {
+        virtual_screen: imp::virtual_screen(),
+        monitors: imp::list(),
+    }
+}
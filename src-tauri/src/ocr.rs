@@ -0,0 +1,104 @@
+use crate::capture::Grabber;
+use crate::monitors::Rect;
+
+// ---------------------------------------------------------------------------
+// Text recognition
+//
+// Reads the text in a screen rectangle with the OCR engine built into Windows
+// (Windows.Media.Ocr), in the user's profile languages. Nothing to install,
+// but it's tens of milliseconds per read, so callers check it sparingly.
+// ---------------------------------------------------------------------------
+
+/// Largest side a text region may have; the engine refuses bigger images
+pub const MAX_TEXT_SIDE: i32 = 2000;
+
+/// Recognizes the text in a top-down BGRA image, lines joined by spaces.
+pub fn recognize(width: i32, height: i32, bgra: &[u8]) -> Result<String, String> {
+    imp::recognize(width, height, bgra)
+}
+
+/// Reads a fixed screen rectangle repeatedly. Made on the thread that uses
+/// it, since the capture surfaces can't move between threads.
+pub struct Reader {
+    region: Rect,
+    grabber: Option<Grabber>,
+}
+
+impl Reader {
+    pub fn new(region: Rect) -> Self {
+        Self {
+            region,
+            grabber: Grabber::new(region.width, region.height),
+        }
+    }
+
+    /// The region's current text; None if the screen or the engine can't be
+    /// read.
+    pub fn read(&mut self) -> Option<String> {
+        let Rect {
+            x,
+            y,
+            width,
+            height,
+        } = self.region;
+        let pixels = self.grabber.as_mut()?.grab(x, y)?;
+        recognize(width, height, pixels).ok()
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use windows::Graphics::Imaging::{BitmapPixelFormat, SoftwareBitmap};
+    use windows::Media::Ocr::OcrEngine;
+    use windows::Storage::Streams::DataWriter;
+    use windows::Win32::System::WinRT::{RoInitialize, RO_INIT_MULTITHREADED};
+
+    fn run(width: i32, height: i32, bgra: &[u8]) -> windows::core::Result<String> {
+        // Already initialized (either way) is fine; WinRT just has to be up
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+        let writer = DataWriter::new()?;
+        writer.WriteBytes(bgra)?;
+        let bitmap = SoftwareBitmap::CreateCopyFromBuffer(
+            &writer.DetachBuffer()?,
+            BitmapPixelFormat::Bgra8,
+            width,
+            height,
+        )?;
+        let engine = OcrEngine::TryCreateFromUserProfileLanguages()?;
+        let result = engine.RecognizeAsync(&bitmap)?.get()?;
+        Ok(result.Text()?.to_string())
+    }
+
+    pub fn recognize(width: i32, height: i32, bgra: &[u8]) -> Result<String, String> {
+        run(width, height, bgra).map_err(|e| format!("Text recognition failed: {}", e.message()))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod imp {
+    pub fn recognize(_width: i32, _height: i32, _bgra: &[u8]) -> Result<String, String> {
+        Err("Text recognition is only available on Windows".into())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+/// What the engine reads in the screen rectangle with top-left `(x, y)`, for
+/// writing a pattern against.
+#[tauri::command]
+pub async fn read_screen_text(x: i32, y: i32, width: i32, height: i32) -> Result<String, String> {
+    if !(1..=MAX_TEXT_SIDE).contains(&width) || !(1..=MAX_TEXT_SIDE).contains(&height) {
+        return Err(format!(
+            "Width and height must be between 1 and {MAX_TEXT_SIDE}"
+        ));
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut grabber = Grabber::new(width, height).ok_or("Couldn't capture the screen")?;
+        let pixels = grabber.grab(x, y).ok_or("Couldn't capture the screen")?;
+        recognize(width, height, pixels)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
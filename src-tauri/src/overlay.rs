@@ -0,0 +1,301 @@
+use std::sync::Mutex;
+
+use crate::win_input;
+
+// ---------------------------------------------------------------------------
+// Coordinate debugging overlay
+//
+// A click-through, always-on-top window across the whole virtual screen that
+// outlines each monitor, draws the grid SendInput's normalized 0-65535
+// coordinates map onto, and marks a target point next to where its
+// normalized form actually lands. Meant for "clicks land 20px off" reports,
+// which usually come down to DPI or multi-monitor math.
+// ---------------------------------------------------------------------------
+
+/// What the overlay draws besides the monitors and grid.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+struct Marker {
+    /// Requested point in screen pixels
+    target: (i32, i32),
+    /// Its normalized SendInput coordinates
+    normalized: (i32, i32),
+}
+
+static MARKER: Mutex<Option<Marker>> = Mutex::new(None);
+
+fn set_marker(target: Option<(i32, i32)>) {
+    let marker = target.map(|(x, y)| Marker {
+        target: (x, y),
+        normalized: win_input::normalize_coords(x, y),
+    });
+    *MARKER.lock().unwrap_or_else(|e| e.into_inner()) = marker;
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn marker() -> Option<Marker> {
+    *MARKER.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use std::sync::atomic::{AtomicIsize, Ordering};
+    use std::thread;
+
+    use windows::core::{w, BOOL};
+    use windows::Win32::Foundation::{COLORREF, HWND, LPARAM, LRESULT, RECT, WPARAM};
+    use windows::Win32::Graphics::Gdi::{
+        BeginPaint, CreatePen, CreateSolidBrush, DeleteObject, EndPaint, EnumDisplayMonitors,
+        FillRect, GetStockObject, InvalidateRect, LineTo, MoveToEx, Rectangle, SelectObject,
+        SetBkMode, SetTextColor, TextOutW, HDC, HMONITOR, NULL_BRUSH, PAINTSTRUCT, PS_DOT,
+        PS_SOLID, TRANSPARENT,
+    };
+    use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GetMessageW,
+        GetSystemMetrics, PostMessageW, PostQuitMessage, RegisterClassW,
+        SetLayeredWindowAttributes, ShowWindow, TranslateMessage, LWA_COLORKEY, MSG,
+        SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN, SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN,
+        SW_SHOWNOACTIVATE, WM_CLOSE, WM_DESTROY, WM_PAINT, WNDCLASSW, WS_EX_LAYERED,
+        WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_EX_TRANSPARENT, WS_POPUP,
+    };
+
+    use super::marker;
+
+    // Painted as fully transparent via the color key
+    const KEY: COLORREF = COLORREF(0x00FF00FF);
+    const MONITOR: COLORREF = COLORREF(0x0000FF00);
+    const GRID: COLORREF = COLORREF(0x00808080);
+    const TARGET: COLORREF = COLORREF(0x00FFFF00);
+    const LANDING: COLORREF = COLORREF(0x000000FF);
+    const GRID_DIVISIONS: i32 = 8;
+    const CROSSHAIR: i32 = 12;
+
+    static HWND_VALUE: AtomicIsize = AtomicIsize::new(0);
+
+    fn window() -> Option<HWND> {
+        let value = HWND_VALUE.load(Ordering::Acquire);
+        (value != 0).then_some(HWND(value as *mut _))
+    }
+
+    unsafe extern "system" fn collect_monitor(
+        _monitor: HMONITOR,
+        _hdc: HDC,
+        rect: *mut RECT,
+        data: LPARAM,
+    ) -> BOOL {
+        let monitors = &mut *(data.0 as *mut Vec<RECT>);
+        monitors.push(*rect);
+        BOOL(1)
+    }
+
+    unsafe fn crosshair(hdc: HDC, x: i32, y: i32) {
+        let _ = MoveToEx(hdc, x - CROSSHAIR, y, None);
+        let _ = LineTo(hdc, x + CROSSHAIR + 1, y);
+        let _ = MoveToEx(hdc, x, y - CROSSHAIR, None);
+        let _ = LineTo(hdc, x, y + CROSSHAIR + 1);
+    }
+
+    unsafe fn label(hdc: HDC, x: i32, y: i32, text: &str) {
+        let wide: Vec<u16> = text.encode_utf16().collect();
+        let _ = TextOutW(hdc, x, y, &wide);
+    }
+
+    unsafe fn paint(hwnd: HWND) {
+        let mut ps = PAINTSTRUCT::default();
+        let hdc = BeginPaint(hwnd, &mut ps);
+        // Client coordinates start at the virtual screen's top-left
+        let ox = GetSystemMetrics(SM_XVIRTUALSCREEN);
+        let oy = GetSystemMetrics(SM_YVIRTUALSCREEN);
+
+        let background = CreateSolidBrush(KEY);
+        FillRect(hdc, &ps.rcPaint, background);
+        let _ = DeleteObject(background.into());
+        SetBkMode(hdc, TRANSPARENT);
+        SelectObject(hdc, GetStockObject(NULL_BRUSH));
+
+        let mut monitors: Vec<RECT> = Vec::new();
+        let _ = EnumDisplayMonitors(
+            None,
+            None,
+            Some(collect_monitor),
+            LPARAM(&mut monitors as *mut Vec<RECT> as isize),
+        );
+        let pen = CreatePen(PS_SOLID, 3, MONITOR);
+        let old = SelectObject(hdc, pen.into());
+        SetTextColor(hdc, MONITOR);
+        for m in &monitors {
+            let _ = Rectangle(hdc, m.left - ox, m.top - oy, m.right - ox, m.bottom - oy);
+            label(
+                hdc,
+                m.left - ox + 8,
+                m.top - oy + 8,
+                &format!(
+                    "monitor {},{} {}x{}",
+                    m.left,
+                    m.top,
+                    m.right - m.left,
+                    m.bottom - m.top
+                ),
+            );
+        }
+        SelectObject(hdc, old);
+        let _ = DeleteObject(pen.into());
+
+        // Normalized absolute coordinates span the whole virtual desktop
+        let cx = GetSystemMetrics(SM_CXVIRTUALSCREEN);
+        let cy = GetSystemMetrics(SM_CYVIRTUALSCREEN);
+        let pen = CreatePen(PS_DOT, 1, GRID);
+        let old = SelectObject(hdc, pen.into());
+        SetTextColor(hdc, GRID);
+        for i in 0..=GRID_DIVISIONS {
+            let x = i * cx / GRID_DIVISIONS;
+            let y = i * cy / GRID_DIVISIONS;
+            let _ = MoveToEx(hdc, x, 0, None);
+            let _ = LineTo(hdc, x, cy);
+            let _ = MoveToEx(hdc, 0, y, None);
+            let _ = LineTo(hdc, cx, y);
+            let n = i * 65535 / GRID_DIVISIONS;
+            label(hdc, x + 4, cy / 2, &n.to_string());
+            label(hdc, cx / 2 + 4, y + 4, &n.to_string());
+        }
+        SelectObject(hdc, old);
+        let _ = DeleteObject(pen.into());
+
+        if let Some(marker) = marker() {
+            let (tx, ty) = marker.target;
+            let (nx, ny) = marker.normalized;
+            // Where Windows puts a normalized point
+            let lx = ox + ((nx as i64 * cx as i64) >> 16) as i32;
+            let ly = oy + ((ny as i64 * cy as i64) >> 16) as i32;
+
+            let pen = CreatePen(PS_SOLID, 1, TARGET);
+            let old = SelectObject(hdc, pen.into());
+            crosshair(hdc, tx - ox, ty - oy);
+            SelectObject(hdc, old);
+            let _ = DeleteObject(pen.into());
+
+            let pen = CreatePen(PS_SOLID, 1, LANDING);
+            let old = SelectObject(hdc, pen.into());
+            crosshair(hdc, lx - ox, ly - oy);
+            SelectObject(hdc, old);
+            let _ = DeleteObject(pen.into());
+
+            SetTextColor(hdc, TARGET);
+            label(
+                hdc,
+                tx - ox + CROSSHAIR + 4,
+                ty - oy + 4,
+                &format!("target {tx},{ty} -> {nx},{ny} -> lands {lx},{ly}"),
+            );
+        }
+
+        let _ = EndPaint(hwnd, &ps);
+    }
+
+    unsafe extern "system" fn wnd_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+    ) -> LRESULT {
+        match msg {
+            WM_PAINT => {
+                paint(hwnd);
+                LRESULT(0)
+            }
+            WM_CLOSE => {
+                let _ = DestroyWindow(hwnd);
+                LRESULT(0)
+            }
+            WM_DESTROY => {
+                HWND_VALUE.store(0, Ordering::Release);
+                PostQuitMessage(0);
+                LRESULT(0)
+            }
+            _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+        }
+    }
+
+    pub fn show() {
+        if let Some(hwnd) = window() {
+            unsafe {
+                let _ = InvalidateRect(Some(hwnd), None, true);
+            }
+            return;
+        }
+        thread::spawn(|| unsafe {
+            let Ok(module) = GetModuleHandleW(None) else {
+                return;
+            };
+            let class = WNDCLASSW {
+                lpfnWndProc: Some(wnd_proc),
+                hInstance: module.into(),
+                lpszClassName: w!("AutoInputDebugOverlay"),
+                ..Default::default()
+            };
+            // Fails harmlessly when the class is left over from an earlier show
+            RegisterClassW(&class);
+            let Ok(hwnd) = CreateWindowExW(
+                WS_EX_LAYERED
+                    | WS_EX_TRANSPARENT
+                    | WS_EX_TOPMOST
+                    | WS_EX_TOOLWINDOW
+                    | WS_EX_NOACTIVATE,
+                w!("AutoInputDebugOverlay"),
+                w!(""),
+                WS_POPUP,
+                GetSystemMetrics(SM_XVIRTUALSCREEN),
+                GetSystemMetrics(SM_YVIRTUALSCREEN),
+                GetSystemMetrics(SM_CXVIRTUALSCREEN),
+                GetSystemMetrics(SM_CYVIRTUALSCREEN),
+                None,
+                None,
+                Some(module.into()),
+                None,
+            ) else {
+                return;
+            };
+            let _ = SetLayeredWindowAttributes(hwnd, KEY, 0, LWA_COLORKEY);
+            HWND_VALUE.store(hwnd.0 as isize, Ordering::Release);
+            let _ = ShowWindow(hwnd, SW_SHOWNOACTIVATE);
+
+            let mut msg = MSG::default();
+            while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        });
+    }
+
+    pub fn hide() {
+        if let Some(hwnd) = window() {
+            unsafe {
+                let _ = PostMessageW(Some(hwnd), WM_CLOSE, WPARAM(0), LPARAM(0));
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod imp {
+    pub fn show() {}
+    pub fn hide() {}
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+/// Shows the overlay, or redraws it if already up. `x`/`y` mark a target
+/// point in screen pixels, e.g. a config's fixed click location.
+#[tauri::command]
+pub fn show_debug_overlay(x: Option<i32>, y: Option<i32>) {
+    set_marker(x.zip(y));
+    imp::show();
+}
+
+#[tauri::command]
+pub fn hide_debug_overlay() {
+    imp::hide();
+}
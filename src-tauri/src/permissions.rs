@@ -0,0 +1,185 @@
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::hooks::{self, KeyEvent, ListenerGuard, MouseEvent};
+use crate::storage::{self, Storage};
+use crate::{expander, hotkeys, remap, safe_mode};
+
+// ---------------------------------------------------------------------------
+// Feature permissions
+//
+// Subsystems that watch all system-wide input or take it over are off until
+// the user opts in to each one. The flags are a store key rather than part of
+// any profile, so importing a profile can't grant them.
+//
+// Every hook listener that can swallow or change input goes through
+// `add_key_listener`/`add_mouse_listener` here, which also keep them all off
+// in safe mode. Only listeners that watch without touching anything, for as
+// long as something the user started is running (pausing while typing, the
+// idle trigger, the position picker), go to `hooks` directly. The panic stop
+// is the one exception: it goes to `hooks` directly and swallows
+// Ctrl+Alt+End, which has no other use, so stopping works in safe mode and
+// without any permission granted.
+// ---------------------------------------------------------------------------
+
+const STORE_KEY: &str = "featurePermissions";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// Keyboard hook for text expansion
+    TextExpansion,
+    /// Keyboard and mouse hooks for toggle keys and wheel remaps
+    KeyRemap,
+    /// `block_input` runs
+    InputBlocking,
+    /// Keyboard and mouse hooks for hold-to-run keys and mouse-button hotkeys
+    HookHotkeys,
+    /// Keyboard hook for nudging a running config with Ctrl+Alt+arrows
+    LiveTuning,
+}
+
+impl Feature {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "textExpansion" => Ok(Self::TextExpansion),
+            "keyRemap" => Ok(Self::KeyRemap),
+            "inputBlocking" => Ok(Self::InputBlocking),
+            "hookHotkeys" => Ok(Self::HookHotkeys),
+            "liveTuning" => Ok(Self::LiveTuning),
+            _ => Err(format!("Unknown feature: {name}")),
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Self::TextExpansion => "Text expansion",
+            Self::KeyRemap => "Key remapping",
+            Self::InputBlocking => "Input blocking",
+            Self::HookHotkeys => "Hold and mouse-button hotkeys",
+            Self::LiveTuning => "Live tuning",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FeaturePermissions {
+    pub text_expansion: bool,
+    pub key_remap: bool,
+    pub input_blocking: bool,
+    pub hook_hotkeys: bool,
+    pub live_tuning: bool,
+}
+
+impl FeaturePermissions {
+    fn flag(&mut self, feature: Feature) -> &mut bool {
+        match feature {
+            Feature::TextExpansion => &mut self.text_expansion,
+            Feature::KeyRemap => &mut self.key_remap,
+            Feature::InputBlocking => &mut self.input_blocking,
+            Feature::HookHotkeys => &mut self.hook_hotkeys,
+            Feature::LiveTuning => &mut self.live_tuning,
+        }
+    }
+}
+
+fn lock_permissions(
+    state: &Mutex<FeaturePermissions>,
+) -> std::sync::MutexGuard<'_, FeaturePermissions> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Reads the granted features from the store.
+pub fn load(app: &AppHandle) {
+    let saved = {
+        let storage = app.state::<Mutex<Storage>>();
+        let data = storage::lock_storage(&storage).read_store();
+        data.get(STORE_KEY)
+            .cloned()
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default()
+    };
+    *lock_permissions(&app.state::<Mutex<FeaturePermissions>>()) = saved;
+}
+
+pub fn allowed(app: &AppHandle, feature: Feature) -> bool {
+    *lock_permissions(&app.state::<Mutex<FeaturePermissions>>()).flag(feature)
+}
+
+/// Whether `feature` may hook input right now, and why not if it can't.
+pub fn check(app: &AppHandle, feature: Feature) -> Result<(), String> {
+    if safe_mode() {
+        return Err(format!("{} is off in safe mode", feature.describe()));
+    }
+    if !allowed(app, feature) {
+        return Err(format!(
+            "{} hasn't been allowed in permissions",
+            feature.describe()
+        ));
+    }
+    Ok(())
+}
+
+/// A keyboard hook listener for `feature`, if it's allowed.
+pub fn add_key_listener(
+    app: &AppHandle,
+    feature: Feature,
+    f: impl Fn(&KeyEvent) -> bool + Send + Sync + 'static,
+) -> Result<ListenerGuard, String> {
+    check(app, feature)?;
+    Ok(hooks::add_key_listener(f))
+}
+
+/// A mouse hook listener for `feature`, if it's allowed.
+pub fn add_mouse_listener(
+    app: &AppHandle,
+    feature: Feature,
+    f: impl Fn(&MouseEvent) -> bool + Send + Sync + 'static,
+) -> Result<ListenerGuard, String> {
+    check(app, feature)?;
+    Ok(hooks::add_mouse_listener(f))
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub fn get_feature_permissions(
+    state: tauri::State<'_, Mutex<FeaturePermissions>>,
+) -> FeaturePermissions {
+    *lock_permissions(&state)
+}
+
+/// Grants or revokes one feature ("textExpansion", "keyRemap",
+/// "inputBlocking", "hookHotkeys" or "liveTuning"). Text expansion and key
+/// remaps are armed or torn down right away. Hook hotkeys stop right away
+/// when revoked, but wait for the next `register_hotkeys` when granted. Live
+/// tuning takes effect from the next run.
+#[tauri::command]
+pub fn set_feature_permission(
+    app: AppHandle,
+    state: tauri::State<'_, Mutex<FeaturePermissions>>,
+    storage: tauri::State<'_, Mutex<Storage>>,
+    feature: String,
+    allowed: bool,
+) -> Result<FeaturePermissions, String> {
+    let feature = Feature::parse(&feature)?;
+    let permissions = {
+        let mut permissions = lock_permissions(&state);
+        *permissions.flag(feature) = allowed;
+        *permissions
+    };
+    let value = serde_json::to_value(permissions).map_err(|e| e.to_string())?;
+    storage::lock_storage(&storage).write_key(STORE_KEY, value)?;
+
+    match feature {
+        Feature::TextExpansion => expander::load(&app),
+        Feature::KeyRemap => remap::load(&app),
+        Feature::HookHotkeys if !allowed => hotkeys::drop_hook_listeners(&app),
+        Feature::InputBlocking | Feature::HookHotkeys | Feature::LiveTuning => {}
+    }
+    Ok(permissions)
+}
@@ -0,0 +1,154 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::capture::{self, PixelColor};
+use crate::hooks::{self, MouseButton, MouseKind};
+use crate::monitors;
+
+// ---------------------------------------------------------------------------
+// Picking a point on screen
+//
+// The next physical left click anywhere is captured through the mouse hook
+// and swallowed, so it doesn't land on whatever is underneath. Escape or a
+// right click cancels.
+// ---------------------------------------------------------------------------
+
+const PICK_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long to keep swallowing after the pick, for the matching button-up
+const RELEASE_WAIT: Duration = Duration::from_secs(1);
+
+static PICKING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PickedPosition {
+    /// Global screen pixels
+    pub x: i32,
+    pub y: i32,
+    /// Monitor under the click, with the position relative to it
+    pub monitor: Option<String>,
+    pub monitor_x: i32,
+    pub monitor_y: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PickedColor {
+    pub position: PickedPosition,
+    pub color: PixelColor,
+}
+
+type Outcome = Result<(i32, i32), String>;
+
+/// Clears `PICKING` however the pick ends.
+struct PickGuard;
+
+impl Drop for PickGuard {
+    fn drop(&mut self) {
+        PICKING.store(false, Ordering::Release);
+    }
+}
+
+fn wait_for_click() -> Outcome {
+    if PICKING.swap(true, Ordering::AcqRel) {
+        return Err("Already picking a position".into());
+    }
+    let _guard = PickGuard;
+
+    let (tx, rx) = mpsc::channel::<Outcome>();
+    // Only the first outcome counts
+    let tx = Arc::new(Mutex::new(Some(tx)));
+    let finish = |tx: &Mutex<Option<Sender<Outcome>>>, outcome: Outcome| {
+        if let Some(tx) = tx.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            let _ = tx.send(outcome);
+        }
+    };
+    let released = Arc::new(AtomicBool::new(false));
+
+    let _mouse = {
+        let tx = Arc::clone(&tx);
+        let released = Arc::clone(&released);
+        hooks::add_mouse_listener(move |event| {
+            if event.injected {
+                return false;
+            }
+            match event.kind {
+                MouseKind::Down(MouseButton::Left) => {
+                    finish(&tx, Ok((event.x, event.y)));
+                    true
+                }
+                MouseKind::Down(MouseButton::Right) => {
+                    finish(&tx, Err("Cancelled".into()));
+                    true
+                }
+                MouseKind::Up(MouseButton::Left | MouseButton::Right) => {
+                    released.store(true, Ordering::Release);
+                    true
+                }
+                _ => false,
+            }
+        })
+    };
+    let _keys = {
+        let tx = Arc::clone(&tx);
+        let released = Arc::clone(&released);
+        hooks::add_key_listener(move |event| {
+            if event.injected || event.vk != hooks::VK_ESCAPE {
+                return false;
+            }
+            released.store(true, Ordering::Release);
+            finish(&tx, Err("Cancelled".into()));
+            true
+        })
+    };
+
+    let outcome = rx
+        .recv_timeout(PICK_TIMEOUT)
+        .unwrap_or_else(|_| Err("Timed out waiting for a click".into()));
+    // Keep the listener until the button comes back up, so the target app
+    // doesn't see a stray release
+    let since = Instant::now();
+    while !released.load(Ordering::Acquire) && since.elapsed() < RELEASE_WAIT {
+        thread::sleep(Duration::from_millis(10));
+    }
+    outcome
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+async fn pick() -> Result<PickedPosition, String> {
+    let (x, y) = tauri::async_runtime::spawn_blocking(wait_for_click)
+        .await
+        .map_err(|e| e.to_string())??;
+    let monitor = monitors::at(x, y);
+    Ok(PickedPosition {
+        x,
+        y,
+        monitor_x: monitor.as_ref().map_or(x, |m| x - m.x),
+        monitor_y: monitor.as_ref().map_or(y, |m| y - m.y),
+        monitor: monitor.map(|m| m.id),
+    })
+}
+
+/// Waits for the user to click somewhere and returns where.
+#[tauri::command]
+pub async fn pick_position() -> Result<PickedPosition, String> {
+    pick().await
+}
+
+/// Like `pick_position`, also returning the color under the click. The click
+/// is swallowed, so the pixel is read before the app there can react.
+#[tauri::command]
+pub async fn pick_color() -> Result<PickedColor, String> {
+    let position = pick().await?;
+    let color = capture::pixel_color(position.x, position.y)
+        .ok_or("Couldn't read the color at that point")?;
+    Ok(PickedColor { position, color })
+}
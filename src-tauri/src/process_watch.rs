@@ -0,0 +1,203 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{
+    alerts, engine, release_held_inputs, safe_mode, sleep_unless_stopped, start_worker,
+    stop_worker_for, AutoInputSettings,
+};
+
+// ---------------------------------------------------------------------------
+// Process-tied runs
+//
+// One watched process at a time: a run starts when it launches (or right
+// away if it's already running) and is stopped, with held inputs released,
+// when it exits. Polled, since being told about process creation needs WMI
+// or a driver; a second's delay is fine for a game starting up.
+// ---------------------------------------------------------------------------
+
+const PROCESS_POLL: Duration = Duration::from_secs(1);
+
+#[derive(Default)]
+pub struct ProcessWatchState {
+    cancel: Option<Arc<AtomicBool>>,
+    info: Option<ProcessWatchInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessWatchInfo {
+    /// Executable name as matched, lowercased with its extension
+    pub process_name: String,
+}
+
+/// Payload of `process-launched` and `process-exited`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProcessEvent {
+    process_name: String,
+    /// Why the run couldn't be started, for `process-launched`
+    error: Option<String>,
+}
+
+fn lock_watch(state: &Mutex<ProcessWatchState>) -> std::sync::MutexGuard<'_, ProcessWatchState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Whether a process watch will start a run.
+pub fn is_watching(app: &AppHandle) -> bool {
+    lock_watch(&app.state::<Mutex<ProcessWatchState>>())
+        .info
+        .is_some()
+}
+
+/// "Game" and "game.exe" both name game.exe.
+fn executable_name(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    if name.contains('.') {
+        name
+    } else {
+        format!("{name}.exe")
+    }
+}
+
+fn spawn_watch(
+    app: AppHandle,
+    settings: AutoInputSettings,
+    process_name: String,
+    cancel: Arc<AtomicBool>,
+) {
+    thread::spawn(move || {
+        let mut running = false;
+        loop {
+            let now_running = imp::is_running(&process_name);
+            if now_running != running {
+                running = now_running;
+                let error = if running {
+                    alerts::trigger_fired(&app, &settings, "process", None);
+                    start_worker(&app, settings.clone()).err()
+                } else {
+                    stop_worker_for(&app, "process-exited");
+                    release_held_inputs();
+                    None
+                };
+                let _ = app.emit(
+                    if running {
+                        "process-launched"
+                    } else {
+                        "process-exited"
+                    },
+                    ProcessEvent {
+                        process_name: process_name.clone(),
+                        error,
+                    },
+                );
+            }
+            if !sleep_unless_stopped(PROCESS_POLL, &cancel) {
+                return;
+            }
+        }
+    });
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+        TH32CS_SNAPPROCESS,
+    };
+
+    /// Whether any process runs the executable `name` (lowercase).
+    pub fn is_running(name: &str) -> bool {
+        unsafe {
+            let Ok(snapshot) = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) else {
+                return false;
+            };
+            let mut entry = PROCESSENTRY32W {
+                dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+                ..Default::default()
+            };
+            let mut found = false;
+            let mut more = Process32FirstW(snapshot, &mut entry).is_ok();
+            while more && !found {
+                let len = entry
+                    .szExeFile
+                    .iter()
+                    .position(|&c| c == 0)
+                    .unwrap_or(entry.szExeFile.len());
+                found = String::from_utf16_lossy(&entry.szExeFile[..len]).to_lowercase() == name;
+                more = Process32NextW(snapshot, &mut entry).is_ok();
+            }
+            let _ = CloseHandle(snapshot);
+            found
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod imp {
+    pub fn is_running(_name: &str) -> bool {
+        false
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+/// Ties `settings` to `process_name`: started while it runs, stopped when
+/// it exits. Replaces any existing watch.
+#[tauri::command]
+pub fn watch_process(
+    app: AppHandle,
+    state: tauri::State<'_, Mutex<ProcessWatchState>>,
+    settings: AutoInputSettings,
+    process_name: String,
+) -> Result<ProcessWatchInfo, String> {
+    if safe_mode() {
+        return Err("Process watching is disabled in safe mode".into());
+    }
+    if process_name.trim().is_empty() {
+        return Err("No process name given".into());
+    }
+    let info = ProcessWatchInfo {
+        process_name: executable_name(&process_name),
+    };
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let mut watch = lock_watch(&state);
+        if let Some(old) = watch.cancel.replace(Arc::clone(&cancel)) {
+            old.store(true, Ordering::Release);
+        }
+        watch.info = Some(info.clone());
+    }
+    engine::schedule_changed(&app);
+    spawn_watch(app, settings, info.process_name.clone(), cancel);
+    Ok(info)
+}
+
+/// Stops watching. A run the watch started keeps going.
+#[tauri::command]
+pub fn unwatch_process(app: AppHandle, state: tauri::State<'_, Mutex<ProcessWatchState>>) {
+    {
+        let mut watch = lock_watch(&state);
+        if let Some(cancel) = watch.cancel.take() {
+            cancel.store(true, Ordering::Release);
+        }
+        watch.info = None;
+    }
+    engine::schedule_changed(&app);
+}
+
+#[tauri::command]
+pub fn get_process_watch(
+    state: tauri::State<'_, Mutex<ProcessWatchState>>,
+) -> Option<ProcessWatchInfo> {
+    lock_watch(&state).info.clone()
+}
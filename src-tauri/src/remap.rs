@@ -0,0 +1,236 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::hooks::{spawn_injector, ListenerGuard, MouseKind};
+use crate::permissions::{self, Feature};
+use crate::storage::{self, Storage};
+use crate::{diagnostics, keys, win_input};
+
+// ---------------------------------------------------------------------------
+// Key remapping
+//
+// Hold-to-toggle: a physical press of a configured key latches it down (via
+// an injected key-down) and the next press releases it. The physical events
+// themselves are swallowed so the target app only ever sees the latched state.
+//
+// Wheel-to-key: physical wheel notches are swallowed and replaced with a tap
+// of the key configured for that direction.
+// ---------------------------------------------------------------------------
+
+const TOGGLE_KEYS_KEY: &str = "toggleKeys";
+const WHEEL_KEYS_KEY: &str = "wheelKeys";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WheelKeys {
+    pub up: Option<String>,
+    pub down: Option<String>,
+}
+
+#[derive(Default)]
+pub struct RemapState {
+    toggle_keys: Vec<String>,
+    listener: Option<ListenerGuard>,
+    /// Keys currently held down by us
+    latched: Arc<Mutex<HashSet<u16>>>,
+    wheel_keys: WheelKeys,
+    wheel_listener: Option<ListenerGuard>,
+}
+
+fn lock_remap(state: &Mutex<RemapState>) -> std::sync::MutexGuard<'_, RemapState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn lock_set(set: &Mutex<HashSet<u16>>) -> std::sync::MutexGuard<'_, HashSet<u16>> {
+    set.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn listen_toggle(
+    app: &AppHandle,
+    keys: &[String],
+    latched: Arc<Mutex<HashSet<u16>>>,
+) -> Result<ListenerGuard, String> {
+    let keys = keys
+        .iter()
+        .map(|k| keys::parse(k).map(win_input::VIRTUAL_KEY))
+        .collect::<Result<Vec<_>, _>>()?;
+    // Physically held keys, so OS auto-repeat doesn't flip the latch
+    let held = Mutex::new(HashSet::new());
+    // The latch flips where the key is sent, so it always matches what
+    // was last sent, also for `release_latched`
+    let toggles = spawn_injector(move |vk: win_input::VIRTUAL_KEY| {
+        let mut latched = lock_set(&latched);
+        if latched.remove(&vk.0) {
+            win_input::key_up(vk);
+        } else {
+            latched.insert(vk.0);
+            win_input::key_down(vk);
+        }
+    });
+
+    permissions::add_key_listener(app, Feature::KeyRemap, move |event| {
+        if event.injected {
+            return false;
+        }
+        let Some(&vk) = keys.iter().find(|k| k.0 == event.vk) else {
+            return false;
+        };
+
+        let mut held = lock_set(&held);
+        if !event.down {
+            held.remove(&event.vk);
+            return true;
+        }
+        if held.insert(event.vk) {
+            let _ = toggles.send(vk);
+        }
+        true
+    })
+}
+
+fn listen_wheel(app: &AppHandle, keys: &WheelKeys) -> Result<ListenerGuard, String> {
+    let parse = |name: Option<&str>| name.map(keys::parse).transpose();
+    let up = parse(keys.up.as_deref())?.map(win_input::VIRTUAL_KEY);
+    let down = parse(keys.down.as_deref())?.map(win_input::VIRTUAL_KEY);
+    let presses = spawn_injector(win_input::key_press);
+
+    permissions::add_mouse_listener(app, Feature::KeyRemap, move |event| {
+        let MouseKind::Wheel(delta) = event.kind else {
+            return false;
+        };
+        if event.injected {
+            return false;
+        }
+        match if delta > 0 { up } else { down } {
+            Some(vk) => {
+                let _ = presses.send(vk);
+                true
+            }
+            None => false,
+        }
+    })
+}
+
+/// Stores the mapping, and arms it only if remaps are permitted and this
+/// isn't safe mode; an error means it isn't armed. Likewise for `apply`.
+fn apply_wheel(app: &AppHandle, state: &mut RemapState, keys: WheelKeys) -> Result<(), String> {
+    state.wheel_listener = None;
+    let armed = if keys.up.is_some() || keys.down.is_some() {
+        listen_wheel(app, &keys).map(|l| state.wheel_listener = Some(l))
+    } else {
+        Ok(())
+    };
+    state.wheel_keys = keys;
+    armed
+}
+
+/// Keeps `keys` either way; an error means the remap isn't armed.
+fn apply(app: &AppHandle, state: &mut RemapState, keys: Vec<String>) -> Result<(), String> {
+    state.listener = None;
+    release_latched(state);
+    let armed = if keys.is_empty() {
+        Ok(())
+    } else {
+        listen_toggle(app, &keys, Arc::clone(&state.latched)).map(|l| state.listener = Some(l))
+    };
+    state.toggle_keys = keys;
+    armed
+}
+
+fn release_latched(state: &RemapState) {
+    for vk in lock_set(&state.latched).drain() {
+        win_input::key_up(win_input::VIRTUAL_KEY(vk));
+    }
+}
+
+/// Lets go of every key the toggle remap is holding down. The remap stays
+/// armed.
+pub fn release_latched_keys(app: &AppHandle) {
+    let state = app.state::<Mutex<RemapState>>();
+    release_latched(&lock_remap(&state));
+}
+
+/// Arms the remaps saved in the store, if any and if permitted.
+pub fn load(app: &AppHandle) {
+    let data = storage::lock_storage(&app.state::<Mutex<Storage>>()).read_store();
+    let toggle_keys = data
+        .get(TOGGLE_KEYS_KEY)
+        .cloned()
+        .and_then(|v| serde_json::from_value::<Vec<String>>(v).ok())
+        .unwrap_or_default();
+    let wheel_keys = data
+        .get(WHEEL_KEYS_KEY)
+        .cloned()
+        .and_then(|v| serde_json::from_value::<WheelKeys>(v).ok())
+        .unwrap_or_default();
+
+    let state = app.state::<Mutex<RemapState>>();
+    let mut state = lock_remap(&state);
+    if let Err(e) = apply(app, &mut state, toggle_keys) {
+        diagnostics::log(&format!("toggle keys not armed: {e}"));
+    }
+    if let Err(e) = apply_wheel(app, &mut state, wheel_keys) {
+        diagnostics::log(&format!("wheel keys not armed: {e}"));
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub fn get_toggle_keys(state: tauri::State<'_, Mutex<RemapState>>) -> Vec<String> {
+    lock_remap(&state).toggle_keys.clone()
+}
+
+/// Replaces and persists the hold-to-toggle keys. Any key we're holding is
+/// released first; an empty list turns the remap off. Unknown key names are
+/// refused; without the remap permission, or in safe mode, the keys are
+/// saved but the error says they aren't armed.
+#[tauri::command]
+pub fn set_toggle_keys(
+    app: AppHandle,
+    state: tauri::State<'_, Mutex<RemapState>>,
+    storage: tauri::State<'_, Mutex<Storage>>,
+    keys: Vec<String>,
+) -> Result<(), String> {
+    if keys.iter().any(|k| k.is_empty()) {
+        return Err("Key cannot be empty".into());
+    }
+    for key in &keys {
+        keys::parse(key)?;
+    }
+
+    let list = serde_json::to_value(&keys).map_err(|e| e.to_string())?;
+    storage::lock_storage(&storage).write_key(TOGGLE_KEYS_KEY, list)?;
+
+    apply(&app, &mut lock_remap(&state), keys)
+}
+
+#[tauri::command]
+pub fn get_wheel_keys(state: tauri::State<'_, Mutex<RemapState>>) -> WheelKeys {
+    lock_remap(&state).wheel_keys.clone()
+}
+
+/// Replaces and persists the wheel mapping; unset directions scroll normally.
+/// Unknown key names are refused, and like `set_toggle_keys` an error after
+/// saving means the mapping isn't armed.
+#[tauri::command]
+pub fn set_wheel_keys(
+    app: AppHandle,
+    state: tauri::State<'_, Mutex<RemapState>>,
+    storage: tauri::State<'_, Mutex<Storage>>,
+    keys: WheelKeys,
+) -> Result<(), String> {
+    for key in [&keys.up, &keys.down].into_iter().flatten() {
+        keys::parse(key)?;
+    }
+
+    let value = serde_json::to_value(&keys).map_err(|e| e.to_string())?;
+    storage::lock_storage(&storage).write_key(WHEEL_KEYS_KEY, value)?;
+
+    apply_wheel(&app, &mut lock_remap(&state), keys)
+}
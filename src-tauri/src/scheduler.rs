@@ -0,0 +1,548 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use chrono::{
+    DateTime, Datelike, Duration as ChronoDuration, Local, NaiveDate, NaiveTime, TimeZone, Timelike,
+};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{engine, safe_mode, sleep_unless_stopped, start_worker_from, AutoInputSettings};
+
+// ---------------------------------------------------------------------------
+// Scheduled starts
+//
+// One schedule at a time: either a single start at a timestamp or a
+// recurring five-field cron expression evaluated in local time. The
+// scheduler thread sleeps until the next fire time, then starts the worker
+// exactly as the `start_action` command would.
+// ---------------------------------------------------------------------------
+
+#[derive(Default)]
+pub struct SchedulerState {
+    cancel: Option<Arc<AtomicBool>>,
+    info: Option<ScheduleInfo>,
+    /// The schedule is a start put off by `queue_start`, not one the user armed
+    queued: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleInfo {
+    /// Unix time in milliseconds of the next start
+    pub next_fire: i64,
+    pub recurring: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScheduleFired {
+    fired_at: i64,
+    next_fire: Option<i64>,
+    error: Option<String>,
+}
+
+fn lock_scheduler(state: &Mutex<SchedulerState>) -> std::sync::MutexGuard<'_, SchedulerState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// ---------------------------------------------------------------------------
+// Cron expressions: minute hour day-of-month month day-of-week
+// Each field accepts `*`, numbers, lists (`1,15`), ranges (`9-17`) and
+// steps (`*/5`, `10-50/10`). Day-of-week is 0-6 from Sunday (7 also Sunday).
+// ---------------------------------------------------------------------------
+
+/// Days searched ahead for the next start. Leap days can be eight years
+/// apart (2096 to 2104), so `0 0 29 2 *` needs that much.
+const CRON_SEARCH_DAYS: u32 = 8 * 366 + 1;
+
+struct CronField {
+    allowed: Vec<bool>,
+    /// Starts with `*`, so `*/5` too. Like Vixie cron, this is what decides
+    /// whether the day fields combine with AND or OR.
+    any: bool,
+}
+
+impl CronField {
+    fn parse(text: &str, min: u32, max: u32) -> Result<Self, String> {
+        let mut allowed = vec![false; max as usize + 1];
+        for part in text.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => {
+                    let step: u32 = step.parse().map_err(|_| format!("Bad step in '{part}'"))?;
+                    if step == 0 {
+                        return Err(format!("Zero step in '{part}'"));
+                    }
+                    (range, step)
+                }
+                None => (part, 1),
+            };
+            let (lo, hi) = if range == "*" {
+                (min, max)
+            } else if let Some((lo, hi)) = range.split_once('-') {
+                let lo = lo.parse().map_err(|_| format!("Bad value in '{part}'"))?;
+                let hi = hi.parse().map_err(|_| format!("Bad value in '{part}'"))?;
+                (lo, hi)
+            } else {
+                let v: u32 = range
+                    .parse()
+                    .map_err(|_| format!("Bad value in '{part}'"))?;
+                // `5/10` means "from 5 to the end, every 10"
+                (v, if step > 1 { max } else { v })
+            };
+            if lo < min || hi > max || lo > hi {
+                return Err(format!("'{part}' is outside {min}-{max}"));
+            }
+            for v in (lo..=hi).step_by(step as usize) {
+                allowed[v as usize] = true;
+            }
+        }
+        Ok(Self {
+            allowed,
+            any: text.starts_with('*'),
+        })
+    }
+
+    fn matches(&self, v: u32) -> bool {
+        self.allowed.get(v as usize).copied().unwrap_or(false)
+    }
+}
+
+pub struct Cron {
+    minute: CronField,
+    hour: CronField,
+    day: CronField,
+    month: CronField,
+    weekday: CronField,
+}
+
+impl Cron {
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err("Cron expression needs 5 fields: minute hour day month weekday".into());
+        };
+        let mut weekday = CronField::parse(weekday, 0, 7)?;
+        if weekday.allowed[7] {
+            weekday.allowed[0] = true;
+        }
+        Ok(Self {
+            minute: CronField::parse(minute, 0, 59)?,
+            hour: CronField::parse(hour, 0, 23)?,
+            day: CronField::parse(day, 1, 31)?,
+            month: CronField::parse(month, 1, 12)?,
+            weekday,
+        })
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day = self.day.matches(date.day());
+        let weekday = self.weekday.matches(date.weekday().num_days_from_sunday());
+        // Classic cron: when both day fields are restricted, either may match
+        let day_ok = if self.day.any || self.weekday.any {
+            day && weekday
+        } else {
+            day || weekday
+        };
+        day_ok && self.month.matches(date.month())
+    }
+
+    /// First matching minute strictly after `after`. Walks day by day and
+    /// only looks at the hours and minutes of days that match.
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start =
+            after.naive_local().with_second(0)?.with_nanosecond(0)? + ChronoDuration::minutes(1);
+        let mut date = start.date();
+        for _ in 0..CRON_SEARCH_DAYS {
+            if self.matches_day(date) {
+                let from = if date == start.date() {
+                    start.time()
+                } else {
+                    NaiveTime::MIN
+                };
+                for hour in (from.hour()..24).filter(|&h| self.hour.matches(h)) {
+                    let first = if hour == from.hour() {
+                        from.minute()
+                    } else {
+                        0
+                    };
+                    for minute in (first..60).filter(|&m| self.minute.matches(m)) {
+                        let naive = date.and_hms_opt(hour, minute, 0)?;
+                        // None when a DST jump skips this minute
+                        let Some(t) = Local.from_local_datetime(&naive).earliest() else {
+                            continue;
+                        };
+                        if t > after {
+                            return Some(t);
+                        }
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Activity windows: the hours of the day a profile may start in, e.g.
+// 18:00-23:00. A window whose end is before its start runs past midnight.
+// ---------------------------------------------------------------------------
+
+pub struct ActivityWindow {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl ActivityWindow {
+    /// None when both ends are empty, i.e. any time is fine.
+    pub fn parse(start: &str, end: &str) -> Result<Option<Self>, String> {
+        if start.is_empty() && end.is_empty() {
+            return Ok(None);
+        }
+        let time = |text: &str| {
+            NaiveTime::parse_from_str(text, "%H:%M")
+                .map_err(|_| format!("'{text}' isn't a time like 18:00"))
+        };
+        let (start, end) = (time(start)?, time(end)?);
+        if start == end {
+            return Err("Allowed hours must start and end at different times".into());
+        }
+        Ok(Some(Self { start, end }))
+    }
+
+    pub fn contains(&self, t: NaiveTime) -> bool {
+        if self.start < self.end {
+            self.start <= t && t < self.end
+        } else {
+            t >= self.start || t < self.end
+        }
+    }
+
+    /// When the window next opens after `now`.
+    pub fn next_open(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        let mut day = now.date_naive();
+        if now.time() >= self.start {
+            day = day.succ_opt()?;
+        }
+        Local
+            .from_local_datetime(&day.and_time(self.start))
+            .earliest()
+    }
+
+    /// e.g. "18:00-23:00", for messages
+    pub fn describe(&self) -> String {
+        format!(
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Scheduler thread
+// ---------------------------------------------------------------------------
+
+fn spawn_schedule(
+    app: AppHandle,
+    settings: AutoInputSettings,
+    label: Option<String>,
+    first: DateTime<Local>,
+    cron: Option<Cron>,
+    cancel: Arc<AtomicBool>,
+) {
+    thread::spawn(move || {
+        let mut next = first;
+        loop {
+            let wait = (next - Local::now()).to_std().unwrap_or(Duration::ZERO);
+            if !sleep_unless_stopped(wait, &cancel) {
+                return;
+            }
+
+            let following = cron.as_ref().and_then(|c| c.next_after(Local::now()));
+            let error = start_worker_from(&app, settings.clone(), 0, label.clone()).err();
+            {
+                let state = app.state::<Mutex<SchedulerState>>();
+                let mut sched = lock_scheduler(&state);
+                // Only touch the state if we haven't been replaced meanwhile
+                if sched
+                    .cancel
+                    .as_ref()
+                    .is_some_and(|c| Arc::ptr_eq(c, &cancel))
+                {
+                    match following {
+                        Some(t) => {
+                            sched.info = Some(ScheduleInfo {
+                                next_fire: t.timestamp_millis(),
+                                recurring: true,
+                            })
+                        }
+                        None => {
+                            sched.cancel = None;
+                            sched.info = None;
+                            sched.queued = false;
+                        }
+                    }
+                }
+            }
+            // Back to idle if that was the last start and it didn't take
+            engine::schedule_changed(&app);
+            let _ = app.emit(
+                "schedule-fired",
+                ScheduleFired {
+                    fired_at: Local::now().timestamp_millis(),
+                    next_fire: following.map(|t| t.timestamp_millis()),
+                    error,
+                },
+            );
+
+            match following {
+                Some(t) => next = t,
+                None => return,
+            }
+        }
+    });
+}
+
+/// Replaces any existing schedule with one firing at `first`. A queued start
+/// only replaces another queued start, never a schedule the user armed.
+fn arm(
+    app: AppHandle,
+    state: &Mutex<SchedulerState>,
+    settings: AutoInputSettings,
+    label: Option<String>,
+    first: DateTime<Local>,
+    cron: Option<Cron>,
+    queued: bool,
+) -> Result<ScheduleInfo, String> {
+    let info = ScheduleInfo {
+        next_fire: first.timestamp_millis(),
+        recurring: cron.is_some(),
+    };
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let mut sched = lock_scheduler(state);
+        if queued && sched.info.is_some() && !sched.queued {
+            return Err(
+                "Outside this profile's allowed hours, and a schedule is already set. \
+                 Cancel it to queue this start instead."
+                    .into(),
+            );
+        }
+        if let Some(old) = sched.cancel.replace(Arc::clone(&cancel)) {
+            old.store(true, Ordering::Release);
+        }
+        sched.info = Some(info.clone());
+        sched.queued = queued;
+    }
+    engine::schedule_changed(&app);
+    spawn_schedule(app, settings, label, first, cron, cancel);
+    Ok(info)
+}
+
+/// Schedules a one-off start at `at`, e.g. when a profile's activity window
+/// opens, keeping the run's `label`. Refuses if the user has a schedule
+/// armed; an earlier queued start is replaced. Must not be called with the
+/// input state locked, since the start itself takes that lock.
+pub fn queue_start(
+    app: &AppHandle,
+    settings: AutoInputSettings,
+    label: Option<String>,
+    at: DateTime<Local>,
+) -> Result<ScheduleInfo, String> {
+    if safe_mode() {
+        return Err("Schedules are disabled in safe mode".into());
+    }
+    let state = app.state::<Mutex<SchedulerState>>();
+    arm(app.clone(), &state, settings, label, at, None, true)
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+/// Arms a start at `at` (Unix ms) or on every match of `cron`, replacing any
+/// existing schedule.
+#[tauri::command]
+pub fn schedule_action(
+    app: AppHandle,
+    state: tauri::State<'_, Mutex<SchedulerState>>,
+    settings: AutoInputSettings,
+    at: Option<i64>,
+    cron: Option<String>,
+) -> Result<ScheduleInfo, String> {
+    if safe_mode() {
+        return Err("Schedules are disabled in safe mode".into());
+    }
+    let (first, cron) = match (at, cron) {
+        (Some(at), None) => {
+            let first = Local
+                .timestamp_millis_opt(at)
+                .single()
+                .ok_or("Invalid start time")?;
+            if first <= Local::now() {
+                return Err("Start time is in the past".into());
+            }
+            (first, None)
+        }
+        (None, Some(expr)) => {
+            let cron = Cron::parse(&expr)?;
+            let first = cron
+                .next_after(Local::now())
+                .ok_or("Cron expression never matches")?;
+            (first, Some(cron))
+        }
+        _ => return Err("Provide either a start time or a cron expression".into()),
+    };
+
+    arm(app, &state, settings, None, first, cron, false)
+}
+
+#[tauri::command]
+pub fn cancel_schedule(app: AppHandle, state: tauri::State<'_, Mutex<SchedulerState>>) {
+    {
+        let mut sched = lock_scheduler(&state);
+        if let Some(cancel) = sched.cancel.take() {
+            cancel.store(true, Ordering::Release);
+        }
+        sched.info = None;
+        sched.queued = false;
+    }
+    engine::schedule_changed(&app);
+}
+
+#[tauri::command]
+pub fn get_schedule(state: tauri::State<'_, Mutex<SchedulerState>>) -> Option<ScheduleInfo> {
+    lock_scheduler(&state).info.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    fn next(expr: &str, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        Cron::parse(expr).unwrap().next_after(after)
+    }
+
+    fn time(h: u32, mi: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, mi, 0).unwrap()
+    }
+
+    #[test]
+    fn activity_window_parse() {
+        assert!(ActivityWindow::parse("", "").unwrap().is_none());
+        assert!(ActivityWindow::parse("18:00", "23:00").unwrap().is_some());
+        assert!(ActivityWindow::parse("18:00", "").is_err());
+        assert!(ActivityWindow::parse("6pm", "23:00").is_err());
+        assert!(ActivityWindow::parse("25:00", "23:00").is_err());
+        assert!(ActivityWindow::parse("18:00", "18:00").is_err());
+    }
+
+    #[test]
+    fn activity_window_runs_past_midnight() {
+        let window = ActivityWindow::parse("22:00", "06:00").unwrap().unwrap();
+        assert!(window.contains(time(23, 30)));
+        assert!(window.contains(time(0, 0)));
+        assert!(window.contains(time(5, 59)));
+        assert!(!window.contains(time(6, 0)));
+        assert!(!window.contains(time(21, 59)));
+
+        // Early morning, inside the window: next opening is tonight
+        assert_eq!(
+            window.next_open(at(2025, 6, 10, 5, 0)),
+            Some(at(2025, 6, 10, 22, 0))
+        );
+        // Late evening, already open: next opening is tomorrow night
+        assert_eq!(
+            window.next_open(at(2025, 6, 10, 23, 0)),
+            Some(at(2025, 6, 11, 22, 0))
+        );
+        assert_eq!(
+            window.next_open(at(2025, 6, 10, 22, 0)),
+            Some(at(2025, 6, 11, 22, 0))
+        );
+    }
+
+    #[test]
+    fn parse_rejects_bad_expressions() {
+        assert!(Cron::parse("* * * *").is_err());
+        assert!(Cron::parse("60 * * * *").is_err());
+        assert!(Cron::parse("*/0 * * * *").is_err());
+        assert!(Cron::parse("5-1 * * * *").is_err());
+        assert!(Cron::parse("0 0 0 * *").is_err());
+        assert!(Cron::parse("a * * * *").is_err());
+    }
+
+    #[test]
+    fn parse_fields() {
+        let field = CronField::parse("10-50/10,5", 0, 59).unwrap();
+        let set: Vec<u32> = (0..60).filter(|&v| field.matches(v)).collect();
+        assert_eq!(set, [5, 10, 20, 30, 40, 50]);
+        assert!(!field.any);
+        assert!(CronField::parse("*", 0, 59).unwrap().any);
+        assert!(CronField::parse("*/5", 0, 59).unwrap().any);
+        // 7 is Sunday as well
+        assert!(Cron::parse("0 0 * * 7").unwrap().weekday.matches(0));
+    }
+
+    #[test]
+    fn next_fire_is_strictly_after() {
+        assert_eq!(
+            next("*/15 * * * *", at(2026, 1, 1, 10, 7)),
+            Some(at(2026, 1, 1, 10, 15))
+        );
+        assert_eq!(
+            next("*/15 * * * *", at(2026, 1, 1, 10, 15)),
+            Some(at(2026, 1, 1, 10, 30))
+        );
+        assert_eq!(
+            next("0 0 * * *", at(2026, 12, 31, 23, 59)),
+            Some(at(2027, 1, 1, 0, 0))
+        );
+    }
+
+    #[test]
+    fn next_fire_skips_to_weekdays() {
+        // Friday 10:00 to Monday 09:30
+        assert_eq!(
+            next("30 9 * * 1-5", at(2026, 1, 9, 10, 0)),
+            Some(at(2026, 1, 12, 9, 30))
+        );
+        assert_eq!(
+            next("0 0 * * 7", at(2026, 1, 1, 0, 0)),
+            Some(at(2026, 1, 4, 0, 0))
+        );
+    }
+
+    #[test]
+    fn next_fire_finds_leap_days() {
+        assert_eq!(
+            next("0 0 29 2 *", at(2026, 3, 1, 0, 0)),
+            Some(at(2028, 2, 29, 0, 0))
+        );
+        assert_eq!(next("0 0 30 2 *", at(2026, 3, 1, 0, 0)), None);
+    }
+
+    #[test]
+    fn day_fields_or_only_when_both_restricted() {
+        // The 13th or any Friday: Friday 2 January comes first
+        assert_eq!(
+            next("0 0 13 * 5", at(2026, 1, 1, 0, 0)),
+            Some(at(2026, 1, 2, 0, 0))
+        );
+        // `*/2` counts as unrestricted, so both must match: the 13th on an
+        // even weekday (Tuesday 13 January), not Saturday 3 January
+        assert_eq!(
+            next("0 0 13 * */2", at(2026, 1, 1, 0, 0)),
+            Some(at(2026, 1, 13, 0, 0))
+        );
+    }
+}
@@ -0,0 +1,791 @@
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager};
+
+// ---------------------------------------------------------------------------
+// Settings persistence
+//
+// All app data lives in one directory: the app data dir by default, or a
+// user-chosen override (network share, synced folder). The directory is
+// guarded by a lock file so two machines pointed at the same folder don't
+// overwrite each other — whoever gets there second runs read-only.
+//
+// An optional shared directory (provisioned by IT, a modpack, ...) holds
+// read-only configs as JSON files. They're merged into the configs the
+// frontend reads and stripped again on save, so only the user's own configs
+// and their edited copies of shared ones are ever written.
+// ---------------------------------------------------------------------------
+
+const STORE_FILE: &str = "settings.json";
+const LOCK_FILE: &str = "autoinput.lock";
+const POINTER_FILE: &str = "data-dir.json";
+const DATA_DIR_ARG: &str = "--data-dir";
+const SHARED_DIR_ARG: &str = "--shared-dir";
+// Marks configs that came from the shared directory
+const SHARED_FLAG: &str = "shared";
+const BACKUP_DIR: &str = "backups";
+const BACKUP_PREFIX: &str = "settings-";
+// Store key holding the number of backups to keep
+const RETENTION_KEY: &str = "backupRetention";
+const DEFAULT_RETENTION: usize = 10;
+
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+// A lock not refreshed for this long is assumed to belong to a crashed instance
+const STALE_LOCK_SECS: u64 = 120;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LockInfo {
+    host: String,
+    pid: u32,
+    heartbeat: u64,
+}
+
+impl LockInfo {
+    fn current() -> Self {
+        Self {
+            host: host_name(),
+            pid: std::process::id(),
+            heartbeat: unix_now(),
+        }
+    }
+
+    fn is_ours(&self) -> bool {
+        self.host == host_name() && self.pid == std::process::id()
+    }
+
+    fn is_stale(&self) -> bool {
+        unix_now().saturating_sub(self.heartbeat) > STALE_LOCK_SECS
+    }
+}
+
+struct DirLock {
+    path: PathBuf,
+    released: Arc<AtomicBool>,
+    /// Set by the heartbeat when the lock file turns out to belong to
+    /// someone else, e.g. after two instances both took over a stale lock
+    lost: Arc<AtomicBool>,
+}
+
+impl DirLock {
+    /// Takes the lock on `dir`, or returns the host currently holding it.
+    /// The lock file is only ever created with `create_new`, so of two
+    /// instances starting together exactly one gets it.
+    fn acquire(dir: &Path) -> Result<Self, String> {
+        let path = dir.join(LOCK_FILE);
+        if !create_lock(&path) {
+            match read_lock(&path) {
+                Some(existing) if existing.is_ours() => {}
+                Some(existing) if !existing.is_stale() => return Err(existing.host),
+                // Unreadable is either a crash mid-write or another instance
+                // between creating the file and filling it in
+                None if !lock_file_is_stale(&path) => return Err("another instance".into()),
+                _ => {
+                    let _ = fs::remove_file(&path);
+                    if !create_lock(&path) {
+                        return Err(
+                            read_lock(&path).map_or_else(|| "another instance".into(), |l| l.host)
+                        );
+                    }
+                }
+            }
+        }
+
+        let released = Arc::new(AtomicBool::new(false));
+        let lost = Arc::new(AtomicBool::new(false));
+        let heartbeat_path = path.clone();
+        let heartbeat_released = Arc::clone(&released);
+        let heartbeat_lost = Arc::clone(&lost);
+        thread::spawn(move || loop {
+            thread::sleep(HEARTBEAT_INTERVAL);
+            if heartbeat_released.load(Ordering::Acquire) {
+                break;
+            }
+            // Refreshing a lock that's been taken over would steal it back
+            // from an instance that is already writing
+            if !read_lock(&heartbeat_path).is_some_and(|l| l.is_ours()) {
+                heartbeat_lost.store(true, Ordering::Release);
+                break;
+            }
+            write_lock(&heartbeat_path);
+        });
+
+        Ok(Self {
+            path,
+            released,
+            lost,
+        })
+    }
+
+    fn is_lost(&self) -> bool {
+        self.lost.load(Ordering::Acquire)
+    }
+}
+
+impl Drop for DirLock {
+    fn drop(&mut self) {
+        self.released.store(true, Ordering::Release);
+        if read_lock(&self.path).is_some_and(|l| l.is_ours()) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn read_lock(path: &Path) -> Option<LockInfo> {
+    let text = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+/// Creates the lock file if there is none. True if it's now ours; also when
+/// the directory can't hold a lock file at all, since then there is nothing
+/// to coordinate through.
+fn create_lock(path: &Path) -> bool {
+    match OpenOptions::new().write(true).create_new(true).open(path) {
+        Ok(mut file) => {
+            if let Ok(text) = serde_json::to_string(&LockInfo::current()) {
+                let _ = file.write_all(text.as_bytes());
+            }
+            true
+        }
+        Err(e) => e.kind() != ErrorKind::AlreadyExists,
+    }
+}
+
+/// Replaces the lock file in one step, so a reader never sees it half-written.
+fn write_lock(path: &Path) {
+    if let Ok(text) = serde_json::to_string(&LockInfo::current()) {
+        let _ = write_atomic(path, &text);
+    }
+}
+
+fn lock_file_is_stale(path: &Path) -> bool {
+    mtime(path)
+        .and_then(|t| t.elapsed().ok())
+        .is_some_and(|age| age.as_secs() > STALE_LOCK_SECS)
+}
+
+fn host_name() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_else(|_| "unknown".into())
+}
+
+fn unix_now() -> u64 {
+    unix_now_ms() / 1000
+}
+
+pub fn unix_now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// ---------------------------------------------------------------------------
+// Storage state
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataDirInfo {
+    pub path: String,
+    pub custom: bool,
+    pub read_only: bool,
+    pub locked_by: Option<String>,
+    pub shared_dir: Option<String>,
+}
+
+pub struct Storage {
+    dir: PathBuf,
+    custom: bool,
+    lock: Option<DirLock>,
+    locked_by: Option<String>,
+    /// Read-only configs merged in on top of the user's own
+    shared_dir: Option<PathBuf>,
+    /// Modification time of the store file as of our last read/write, used to
+    /// tell external edits apart from our own writes
+    known_mtime: Option<SystemTime>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub name: String,
+    /// Unix time in milliseconds when the backup was taken
+    pub timestamp: u64,
+    pub size: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DataDirPointer {
+    data_dir: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    shared_dir: Option<String>,
+}
+
+impl Storage {
+    pub fn open(app: &AppHandle) -> Self {
+        let (dir, custom) = match data_dir_override(app) {
+            Some(dir) => (dir, true),
+            None => (default_dir(app), false),
+        };
+        let mut storage = Self::open_dir(dir, custom);
+        storage.shared_dir = shared_dir_setting(app);
+        storage
+    }
+
+    fn open_dir(dir: PathBuf, custom: bool) -> Self {
+        let _ = fs::create_dir_all(&dir);
+        let (lock, locked_by) = match DirLock::acquire(&dir) {
+            Ok(lock) => (Some(lock), None),
+            Err(host) => (None, Some(host)),
+        };
+        let known_mtime = mtime(&dir.join(STORE_FILE));
+        Self {
+            dir,
+            custom,
+            lock,
+            locked_by,
+            shared_dir: None,
+            known_mtime,
+        }
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.lock.as_ref().is_none_or(DirLock::is_lost)
+    }
+
+    /// Who holds the lock when it isn't us.
+    fn holder(&self) -> Option<String> {
+        match &self.lock {
+            Some(lock) if lock.is_lost() => read_lock(&lock.path).map(|l| l.host),
+            _ => self.locked_by.clone(),
+        }
+    }
+
+    pub fn info(&self) -> DataDirInfo {
+        DataDirInfo {
+            path: self.dir.to_string_lossy().into_owned(),
+            custom: self.custom,
+            read_only: self.is_read_only(),
+            locked_by: self.holder(),
+            shared_dir: self
+                .shared_dir
+                .as_ref()
+                .map(|d| d.to_string_lossy().into_owned()),
+        }
+    }
+
+    /// Configs from the shared directory, each flagged `shared: true`. Every
+    /// `*.json` file holds one config or an array of them; files without
+    /// an `id` use their file name.
+    fn shared_configs(&self) -> Vec<Value> {
+        let Some(entries) = self.shared_dir.as_ref().and_then(|d| fs::read_dir(d).ok()) else {
+            return Vec::new();
+        };
+        let mut files: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        files.sort();
+
+        let mut configs = Vec::new();
+        for path in files {
+            let Some(data) = fs::read_to_string(&path)
+                .ok()
+                .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+            else {
+                continue;
+            };
+            let stem = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let list = match data {
+                Value::Array(list) => list,
+                config => vec![config],
+            };
+            for (i, mut config) in list.into_iter().enumerate() {
+                let Some(map) = config.as_object_mut() else {
+                    continue;
+                };
+                map.entry("id")
+                    .or_insert_with(|| Value::String(format!("shared-{stem}-{i}")));
+                map.insert(SHARED_FLAG.into(), Value::Bool(true));
+                configs.push(config);
+            }
+        }
+        configs
+    }
+
+    /// `data` with shared configs appended to `appState.configs`, except
+    /// those the user has saved an edited copy of (same id).
+    pub fn with_shared(&self, mut data: Value) -> Value {
+        let shared = self.shared_configs();
+        if shared.is_empty() {
+            return data;
+        }
+        let Some(configs) = data
+            .pointer_mut("/appState/configs")
+            .and_then(Value::as_array_mut)
+        else {
+            return data;
+        };
+        for config in shared {
+            if !configs.iter().any(|c| c.get("id") == config.get("id")) {
+                configs.push(config);
+            }
+        }
+        data
+    }
+
+    /// `data` without the shared configs `with_shared` added. One the user
+    /// changed is kept as their own copy, which then shadows the original.
+    fn without_shared(&self, mut data: Value) -> Value {
+        let shared = self.shared_configs();
+        if let Some(configs) = data
+            .pointer_mut("/appState/configs")
+            .and_then(Value::as_array_mut)
+        {
+            configs.retain(|c| !shared.contains(c));
+            for config in configs.iter_mut().filter_map(Value::as_object_mut) {
+                config.remove(SHARED_FLAG);
+            }
+        }
+        data
+    }
+
+    /// Path of a file of ours inside the data directory.
+    pub fn data_path(&self, name: &str) -> PathBuf {
+        self.dir.join(name)
+    }
+
+    pub fn store_path(&self) -> PathBuf {
+        self.dir.join(STORE_FILE)
+    }
+
+    pub fn read_store(&self) -> Value {
+        fs::read_to_string(self.store_path())
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_else(|| Value::Object(Default::default()))
+    }
+
+    fn check_writable(&self) -> Result<(), String> {
+        if self.is_read_only() {
+            let holder = self.holder();
+            let holder = holder.as_deref().unwrap_or("another instance");
+            return Err(format!("Data directory is in use by {holder} (read-only)"));
+        }
+        Ok(())
+    }
+
+    pub fn write_store(&mut self, data: &Value) -> Result<(), String> {
+        self.check_writable()?;
+        let text = serde_json::to_string_pretty(data).map_err(|e| e.to_string())?;
+        self.backup_current(retention(data));
+        write_atomic(&self.store_path(), &text)?;
+        self.known_mtime = mtime(&self.store_path());
+        Ok(())
+    }
+
+    /// Sets one top-level key of the store, keeping everything else as is.
+    pub fn write_key(&mut self, key: &str, value: Value) -> Result<(), String> {
+        let mut data = self.read_store();
+        match data.as_object_mut() {
+            Some(map) => {
+                map.insert(key.into(), value);
+            }
+            None => data = serde_json::json!({ key: value }),
+        }
+        self.write_store(&data)
+    }
+
+    /// Returns the store contents if the file was changed by someone else
+    /// since we last saw it. Unparseable files (e.g. a half-saved edit) are
+    /// skipped until they become valid.
+    fn poll_external_change(&mut self) -> Option<Value> {
+        let path = self.store_path();
+        let current = mtime(&path);
+        if current == self.known_mtime {
+            return None;
+        }
+        let data = serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+        self.known_mtime = current;
+        Some(data)
+    }
+
+    fn backup_dir(&self) -> PathBuf {
+        self.dir.join(BACKUP_DIR)
+    }
+
+    /// Copies the store file as it is now into the backups folder, then prunes
+    /// the oldest backups beyond `keep`. Best-effort: a failed backup never
+    /// blocks the write itself.
+    fn backup_current(&self, keep: usize) {
+        let src = self.store_path();
+        if keep == 0 || !src.exists() {
+            return;
+        }
+        let dir = self.backup_dir();
+        if fs::create_dir_all(&dir).is_err() {
+            return;
+        }
+        let name = format!("{BACKUP_PREFIX}{}.json", unix_now_ms());
+        let _ = fs::copy(&src, dir.join(name));
+
+        for old in self.list_backups().into_iter().skip(keep) {
+            let _ = fs::remove_file(dir.join(old.name));
+        }
+    }
+
+    /// Backups sorted newest first.
+    pub fn list_backups(&self) -> Vec<BackupInfo> {
+        let Ok(entries) = fs::read_dir(self.backup_dir()) else {
+            return Vec::new();
+        };
+        let mut backups: Vec<BackupInfo> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let timestamp = backup_timestamp(&name)?;
+                let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                Some(BackupInfo {
+                    name,
+                    timestamp,
+                    size,
+                })
+            })
+            .collect();
+        backups.sort_by_key(|b| std::cmp::Reverse(b.timestamp));
+        backups
+    }
+
+    /// Replaces the store with the named backup. Like any write, this backs
+    /// up the current store first, so a restore can be undone.
+    pub fn restore_backup(&mut self, name: &str) -> Result<Value, String> {
+        if !self.list_backups().iter().any(|b| b.name == name) {
+            return Err(format!("Backup not found: {name}"));
+        }
+        let text = fs::read_to_string(self.backup_dir().join(name)).map_err(|e| e.to_string())?;
+        let data: Value =
+            serde_json::from_str(&text).map_err(|e| format!("Backup is not valid JSON: {e}"))?;
+        self.write_store(&data)?;
+        Ok(data)
+    }
+
+    /// Releases the directory lock (on exit, when switching directories, or
+    /// before handing over to an elevated copy of the app).
+    pub fn release(&mut self) {
+        self.lock = None;
+    }
+
+    /// Takes the lock back after `release`, e.g. when the hand-over didn't
+    /// happen. Stays read-only if someone else got it in between.
+    pub fn reacquire(&mut self) {
+        match DirLock::acquire(&self.dir) {
+            Ok(lock) => {
+                self.lock = Some(lock);
+                self.locked_by = None;
+            }
+            Err(host) => self.locked_by = Some(host),
+        }
+    }
+}
+
+/// Writes via a temp file + rename so a crash mid-write can't truncate the store.
+pub fn write_atomic(path: &Path, text: &str) -> Result<(), String> {
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, text).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, path).map_err(|e| e.to_string())
+}
+
+enum FileOp {
+    Write(String),
+    Remove,
+}
+
+/// Keeps a file next to the store up to date for a thread that mustn't stall
+/// on the disk or the storage lock, like the run worker. Changes land in
+/// order on a thread of their own; dropping the writer waits for the last.
+pub struct BackgroundWriter {
+    ops: Option<Sender<FileOp>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl BackgroundWriter {
+    pub fn new(app: AppHandle, file: &'static str) -> Self {
+        let (ops, queued) = mpsc::channel();
+        let thread = thread::spawn(move || {
+            for op in queued {
+                let storage = app.state::<Mutex<Storage>>();
+                let storage = lock_storage(&storage);
+                if storage.is_read_only() {
+                    continue;
+                }
+                let path = storage.data_path(file);
+                let _ = match op {
+                    FileOp::Write(text) => write_atomic(&path, &text),
+                    FileOp::Remove => fs::remove_file(&path).map_err(|e| e.to_string()),
+                };
+            }
+        });
+        Self {
+            ops: Some(ops),
+            thread: Some(thread),
+        }
+    }
+
+    pub fn write(&self, text: String) {
+        self.send(FileOp::Write(text));
+    }
+
+    pub fn remove(&self) {
+        self.send(FileOp::Remove);
+    }
+
+    fn send(&self, op: FileOp) {
+        if let Some(ops) = &self.ops {
+            let _ = ops.send(op);
+        }
+    }
+}
+
+impl Drop for BackgroundWriter {
+    fn drop(&mut self) {
+        // Closing the queue ends the thread once it's written out
+        self.ops = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn default_dir(app: &AppHandle) -> PathBuf {
+    app.path()
+        .app_data_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+}
+
+fn pointer_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_config_dir()
+        .ok()
+        .map(|d| d.join(POINTER_FILE))
+}
+
+/// `--shared-dir <path>` on the command line, else `sharedDir` in the
+/// pointer file.
+fn shared_dir_setting(app: &AppHandle) -> Option<PathBuf> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == SHARED_DIR_ARG {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(value) = arg.strip_prefix("--shared-dir=") {
+            return Some(PathBuf::from(value));
+        }
+    }
+
+    let text = fs::read_to_string(pointer_path(app)?).ok()?;
+    let pointer: DataDirPointer = serde_json::from_str(&text).ok()?;
+    pointer
+        .shared_dir
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+}
+
+/// `--data-dir <path>` on the command line wins over the saved override.
+fn data_dir_override(app: &AppHandle) -> Option<PathBuf> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == DATA_DIR_ARG {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(value) = arg.strip_prefix("--data-dir=") {
+            return Some(PathBuf::from(value));
+        }
+    }
+
+    let text = fs::read_to_string(pointer_path(app)?).ok()?;
+    let pointer: DataDirPointer = serde_json::from_str(&text).ok()?;
+    pointer
+        .data_dir
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+}
+
+fn mtime(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Polls the store file and emits `config-reloaded` with the new contents
+/// whenever it is edited outside the app.
+pub fn spawn_watcher(app: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(WATCH_INTERVAL);
+        let Some(state) = app.try_state::<Mutex<Storage>>() else {
+            continue;
+        };
+        let changed = {
+            let mut storage = lock_storage(&state);
+            storage
+                .poll_external_change()
+                .map(|data| storage.with_shared(data))
+        };
+        if let Some(data) = changed {
+            let _ = app.emit("config-reloaded", data);
+            crate::refresh_tray_menu(&app);
+        }
+    });
+}
+
+/// When a backup was taken, from its file name; None for anything that isn't
+/// one of ours, so only those can be listed or restored.
+fn backup_timestamp(name: &str) -> Option<u64> {
+    let digits = name.strip_prefix(BACKUP_PREFIX)?.strip_suffix(".json")?;
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+fn retention(data: &Value) -> usize {
+    data.get(RETENTION_KEY)
+        .and_then(Value::as_u64)
+        .map(|n| n as usize)
+        .unwrap_or(DEFAULT_RETENTION)
+}
+
+pub fn lock_storage(state: &Mutex<Storage>) -> std::sync::MutexGuard<'_, Storage> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub fn get_data_dir(state: tauri::State<'_, Mutex<Storage>>) -> DataDirInfo {
+    lock_storage(&state).info()
+}
+
+/// Points persistence at `path`, or back at the default app data dir when `None`.
+#[tauri::command]
+pub fn set_data_dir(
+    app: AppHandle,
+    state: tauri::State<'_, Mutex<Storage>>,
+    path: Option<String>,
+) -> Result<DataDirInfo, String> {
+    let pointer_file = pointer_path(&app).ok_or("Config directory unavailable")?;
+    if let Some(parent) = pointer_file.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let path = path.filter(|p| !p.trim().is_empty());
+    // Keep a provisioned shared dir; only the data dir is the user's to change
+    let saved: DataDirPointer = fs::read_to_string(&pointer_file)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default();
+    let pointer = DataDirPointer {
+        data_dir: path.clone(),
+        shared_dir: saved.shared_dir,
+    };
+    let text = serde_json::to_string_pretty(&pointer).map_err(|e| e.to_string())?;
+    fs::write(&pointer_file, text).map_err(|e| e.to_string())?;
+
+    let info = {
+        let mut storage = lock_storage(&state);
+        let shared_dir = storage.shared_dir.take();
+        storage.release();
+        *storage = match path {
+            Some(dir) => Storage::open_dir(PathBuf::from(dir), true),
+            None => Storage::open_dir(default_dir(&app), false),
+        };
+        storage.shared_dir = shared_dir;
+        let _ = app.emit("config-reloaded", storage.with_shared(storage.read_store()));
+        storage.info()
+    };
+    crate::refresh_tray_menu(&app);
+    Ok(info)
+}
+
+#[tauri::command]
+pub fn read_store(state: tauri::State<'_, Mutex<Storage>>) -> Value {
+    let storage = lock_storage(&state);
+    storage.with_shared(storage.read_store())
+}
+
+#[tauri::command]
+pub fn write_store(
+    app: AppHandle,
+    state: tauri::State<'_, Mutex<Storage>>,
+    data: Value,
+) -> Result<(), String> {
+    {
+        let mut storage = lock_storage(&state);
+        let data = storage.without_shared(data);
+        storage.write_store(&data)?;
+    }
+    // Saved configs may have been renamed, retagged or removed
+    crate::refresh_tray_menu(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_backups(state: tauri::State<'_, Mutex<Storage>>) -> Vec<BackupInfo> {
+    lock_storage(&state).list_backups()
+}
+
+#[tauri::command]
+pub fn restore_backup(
+    app: AppHandle,
+    state: tauri::State<'_, Mutex<Storage>>,
+    name: String,
+) -> Result<Value, String> {
+    let data = {
+        let mut storage = lock_storage(&state);
+        let data = storage.restore_backup(&name)?;
+        storage.with_shared(data)
+    };
+    // Same as an outside edit, so every window and the tray pick it up
+    let _ = app.emit("config-reloaded", data.clone());
+    crate::refresh_tray_menu(&app);
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backup_names_are_ours_only() {
+        assert_eq!(
+            backup_timestamp("settings-1700000000000.json"),
+            Some(1_700_000_000_000)
+        );
+        for name in [
+            "settings.json",
+            "settings-.json",
+            "settings-+12.json",
+            "settings-12.json.tmp",
+            "settings-12abc.json",
+            "../settings-12.json",
+            "settings-../12.json",
+            "other-12.json",
+        ] {
+            assert_eq!(backup_timestamp(name), None, "{name}");
+        }
+    }
+}
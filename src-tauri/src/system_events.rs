@@ -0,0 +1,121 @@
+use std::sync::OnceLock;
+
+// ---------------------------------------------------------------------------
+// Session and power notifications
+//
+// Windows only delivers these to a window, so a hidden one is created on a
+// dedicated thread. (Message-only windows miss broadcasts such as power
+// events, hence a real top-level window that is never shown.)
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub enum SystemEvent {
+    /// Workstation locked
+    SessionLocked,
+    /// This session lost the console or its remote connection
+    SessionDisconnected,
+    /// The machine is about to sleep or hibernate
+    Suspending,
+}
+
+type Handler = Box<dyn Fn(SystemEvent) + Send + Sync>;
+
+static HANDLER: OnceLock<Handler> = OnceLock::new();
+
+/// Starts delivering system events to `handler`. Only the first call takes
+/// effect.
+pub fn watch(handler: impl Fn(SystemEvent) + Send + Sync + 'static) {
+    if HANDLER.set(Box::new(handler)).is_ok() {
+        imp::spawn_window();
+    }
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn dispatch(event: SystemEvent) {
+    if let Some(handler) = HANDLER.get() {
+        handler(event);
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use std::thread;
+
+    use windows::core::w;
+    use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+    use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows::Win32::System::RemoteDesktop::{
+        WTSRegisterSessionNotification, NOTIFY_FOR_THIS_SESSION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, RegisterClassW,
+        TranslateMessage, MSG, PBT_APMSUSPEND, WINDOW_EX_STYLE, WINDOW_STYLE, WM_POWERBROADCAST,
+        WM_WTSSESSION_CHANGE, WNDCLASSW, WTS_CONSOLE_DISCONNECT, WTS_REMOTE_DISCONNECT,
+        WTS_SESSION_LOCK,
+    };
+
+    use super::{dispatch, SystemEvent};
+
+    unsafe extern "system" fn wnd_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+    ) -> LRESULT {
+        match (msg, wparam.0 as u32) {
+            (WM_WTSSESSION_CHANGE, WTS_SESSION_LOCK) => dispatch(SystemEvent::SessionLocked),
+            (WM_WTSSESSION_CHANGE, WTS_CONSOLE_DISCONNECT | WTS_REMOTE_DISCONNECT) => {
+                dispatch(SystemEvent::SessionDisconnected)
+            }
+            (WM_POWERBROADCAST, PBT_APMSUSPEND) => dispatch(SystemEvent::Suspending),
+            _ => {}
+        }
+        DefWindowProcW(hwnd, msg, wparam, lparam)
+    }
+
+    pub fn spawn_window() {
+        thread::spawn(|| unsafe {
+            let Ok(module) = GetModuleHandleW(None) else {
+                return;
+            };
+            let class = WNDCLASSW {
+                lpfnWndProc: Some(wnd_proc),
+                hInstance: module.into(),
+                lpszClassName: w!("AutoInputSystemEvents"),
+                ..Default::default()
+            };
+            if RegisterClassW(&class) == 0 {
+                return;
+            }
+            let Ok(hwnd) = CreateWindowExW(
+                WINDOW_EX_STYLE::default(),
+                w!("AutoInputSystemEvents"),
+                w!(""),
+                WINDOW_STYLE::default(),
+                0,
+                0,
+                0,
+                0,
+                None,
+                None,
+                Some(module.into()),
+                None,
+            ) else {
+                return;
+            };
+            let _ = WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION);
+
+            let mut msg = MSG::default();
+            while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        });
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod imp {
+    pub fn spawn_window() {}
+}
@@ -0,0 +1,208 @@
+use std::cell::Cell;
+use std::fs;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::storage::{self, BackgroundWriter, Storage};
+
+// ---------------------------------------------------------------------------
+// Daily usage
+//
+// Active injection time is summed per local calendar day and kept in its own
+// file next to the store, so the frequent updates don't churn store backups.
+// An optional daily limit (a store key, so it isn't part of any profile)
+// refuses new runs and stops the current one once reached.
+// ---------------------------------------------------------------------------
+
+const USAGE_FILE: &str = "usage.json";
+const LIMIT_KEY: &str = "dailyLimitMinutes";
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DayUsage {
+    /// Local date as YYYY-MM-DD
+    day: String,
+    active_ms: u64,
+}
+
+#[derive(Default)]
+pub struct UsageState {
+    today: DayUsage,
+    limit_minutes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageToday {
+    pub active_ms: u64,
+    pub limit_minutes: Option<u64>,
+    pub limit_reached: bool,
+}
+
+fn lock_usage(state: &Mutex<UsageState>) -> std::sync::MutexGuard<'_, UsageState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn today() -> String {
+    Local::now().format("%Y-%m-%d").to_string()
+}
+
+impl UsageState {
+    /// Starts a fresh count when the date has changed since the last update.
+    fn roll_over(&mut self) {
+        let day = today();
+        if self.today.day != day {
+            self.today = DayUsage { day, active_ms: 0 };
+        }
+    }
+
+    fn limit_reached(&self) -> bool {
+        self.limit_minutes
+            .is_some_and(|m| self.today.active_ms >= m * 60_000)
+    }
+
+    fn report(&mut self) -> UsageToday {
+        self.roll_over();
+        UsageToday {
+            active_ms: self.today.active_ms,
+            limit_minutes: self.limit_minutes,
+            limit_reached: self.limit_reached(),
+        }
+    }
+}
+
+/// Reads today's usage and the limit from the data directory.
+pub fn load(app: &AppHandle) {
+    let (today, limit_minutes) = {
+        let storage = app.state::<Mutex<Storage>>();
+        let storage = storage::lock_storage(&storage);
+        let today = fs::read_to_string(storage.data_path(USAGE_FILE))
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        let limit = storage.read_store().get(LIMIT_KEY).and_then(|v| v.as_u64());
+        (today, limit)
+    };
+    let state = app.state::<Mutex<UsageState>>();
+    let mut usage = lock_usage(&state);
+    usage.today = today;
+    usage.limit_minutes = limit_minutes.filter(|&m| m > 0);
+    usage.roll_over();
+}
+
+/// Whether today's limit is used up; checked before starting a run.
+pub fn limit_reached(app: &AppHandle) -> bool {
+    let state = app.state::<Mutex<UsageState>>();
+    let mut usage = lock_usage(&state);
+    usage.roll_over();
+    usage.limit_reached()
+}
+
+/// Adds to today's total. Returns the new total to save, and false if that
+/// used up the limit.
+fn add_active(app: &AppHandle, elapsed: Duration) -> (DayUsage, bool) {
+    let state = app.state::<Mutex<UsageState>>();
+    let mut usage = lock_usage(&state);
+    usage.roll_over();
+    usage.today.active_ms += elapsed.as_millis() as u64;
+    (usage.today.clone(), !usage.limit_reached())
+}
+
+/// Measures a worker's active time. Time between `active` calls counts,
+/// except across an `idle` call (pauses, rests). Totals are flushed every few
+/// seconds and when the meter is dropped, the file writes happening off the
+/// worker thread.
+pub struct UsageMeter {
+    app: AppHandle,
+    writer: BackgroundWriter,
+    since: Cell<Instant>,
+    pending: Cell<Duration>,
+    flushed_at: Cell<Instant>,
+    exhausted: Cell<bool>,
+}
+
+impl UsageMeter {
+    pub fn start(app: AppHandle) -> Self {
+        let now = Instant::now();
+        Self {
+            writer: BackgroundWriter::new(app.clone(), USAGE_FILE),
+            app,
+            since: Cell::new(now),
+            pending: Cell::new(Duration::ZERO),
+            flushed_at: Cell::new(now),
+            exhausted: Cell::new(false),
+        }
+    }
+
+    /// Counts the time since the last call. Returns false once the daily
+    /// limit is used up.
+    pub fn active(&self) -> bool {
+        let now = Instant::now();
+        self.count_until(now);
+        if now - self.flushed_at.get() >= FLUSH_INTERVAL {
+            self.flush();
+        }
+        !self.exhausted.get()
+    }
+
+    fn count_until(&self, now: Instant) {
+        self.pending
+            .set(self.pending.get() + (now - self.since.get()));
+        self.since.set(now);
+    }
+
+    /// Discards the time since the last call.
+    pub fn idle(&self) {
+        self.since.set(Instant::now());
+    }
+
+    pub fn exhausted(&self) -> bool {
+        self.exhausted.get()
+    }
+
+    fn flush(&self) {
+        let pending = self.pending.replace(Duration::ZERO);
+        self.flushed_at.set(Instant::now());
+        let (today, within_limit) = add_active(&self.app, pending);
+        if let Ok(text) = serde_json::to_string(&today) {
+            self.writer.write(text);
+        }
+        if !within_limit {
+            self.exhausted.set(true);
+        }
+    }
+}
+
+impl Drop for UsageMeter {
+    fn drop(&mut self) {
+        self.count_until(Instant::now());
+        self.flush();
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+#[tauri::command]
+pub fn get_usage_today(state: tauri::State<'_, Mutex<UsageState>>) -> UsageToday {
+    lock_usage(&state).report()
+}
+
+/// Sets the daily limit in minutes; None or 0 removes it.
+#[tauri::command]
+pub fn set_daily_limit(
+    state: tauri::State<'_, Mutex<UsageState>>,
+    storage: tauri::State<'_, Mutex<Storage>>,
+    minutes: Option<u64>,
+) -> Result<(), String> {
+    let minutes = minutes.filter(|&m| m > 0);
+    storage::lock_storage(&storage).write_key(LIMIT_KEY, serde_json::json!(minutes))?;
+    lock_usage(&state).limit_minutes = minutes;
+    Ok(())
+}
@@ -0,0 +1,243 @@
+use base64::Engine as _;
+
+use crate::capture::Grabber;
+use crate::monitors::{self, Rect};
+use crate::AutoInputSettings;
+
+// ---------------------------------------------------------------------------
+// Image template search
+//
+// Finds where a small reference image (a button, an icon) currently is on
+// screen. Matching is on grayscale by mean absolute difference: a coarse pass
+// over a downscaled copy of the whole virtual screen, then a full-resolution
+// pass around the best coarse hit. That keeps a full-screen search to a few
+// milliseconds, which matters since it runs before every click.
+// ---------------------------------------------------------------------------
+
+/// Coarse-pass downscale factor for templates big enough to survive it
+const COARSE_FACTOR: usize = 4;
+/// Templates must keep at least this many pixels per side when downscaled
+const MIN_COARSE_SIDE: usize = 8;
+
+#[derive(Debug, Clone)]
+struct Gray {
+    width: usize,
+    height: usize,
+    px: Vec<u8>,
+}
+
+impl Gray {
+    fn luma(r: u8, g: u8, b: u8) -> u8 {
+        ((r as u32 * 77 + g as u32 * 150 + b as u32 * 29) >> 8) as u8
+    }
+
+    fn from_bgra(width: usize, height: usize, bgra: &[u8]) -> Self {
+        let px = bgra
+            .chunks_exact(4)
+            .map(|p| Self::luma(p[2], p[1], p[0]))
+            .collect();
+        Self { width, height, px }
+    }
+
+    /// Box-averages each `factor`×`factor` block into one pixel.
+    fn downscale(&self, factor: usize) -> Self {
+        let (width, height) = (self.width / factor, self.height / factor);
+        let mut px = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = 0u32;
+                for dy in 0..factor {
+                    let row = (y * factor + dy) * self.width + x * factor;
+                    sum += self.px[row..row + factor]
+                        .iter()
+                        .map(|&v| v as u32)
+                        .sum::<u32>();
+                }
+                px.push((sum / (factor * factor) as u32) as u8);
+            }
+        }
+        Self { width, height, px }
+    }
+
+    /// Sum of absolute differences with `template` placed at `(ox, oy)`, or
+    /// None as soon as it passes `limit`.
+    fn difference(&self, template: &Gray, ox: usize, oy: usize, limit: u64) -> Option<u64> {
+        let mut total = 0u64;
+        for ty in 0..template.height {
+            let row = (oy + ty) * self.width + ox;
+            let screen = &self.px[row..row + template.width];
+            let tpl = &template.px[ty * template.width..(ty + 1) * template.width];
+            total += screen
+                .iter()
+                .zip(tpl)
+                .map(|(&a, &b)| a.abs_diff(b) as u64)
+                .sum::<u64>();
+            if total > limit {
+                return None;
+            }
+        }
+        Some(total)
+    }
+
+    /// Top-left of the closest placement of `template` with its top-left in
+    /// the given ranges, and its difference.
+    fn best(
+        &self,
+        template: &Gray,
+        xs: std::ops::Range<usize>,
+        ys: std::ops::Range<usize>,
+    ) -> Option<(usize, usize, u64)> {
+        let max_x = (self.width + 1).checked_sub(template.width)?;
+        let max_y = (self.height + 1).checked_sub(template.height)?;
+        let mut best: Option<(usize, usize, u64)> = None;
+        for oy in ys.start..ys.end.min(max_y) {
+            for ox in xs.start..xs.end.min(max_x) {
+                let limit = best.map_or(u64::MAX, |(_, _, d)| d);
+                if let Some(d) = self.difference(template, ox, oy, limit) {
+                    if best.is_none_or(|(_, _, b)| d < b) {
+                        best = Some((ox, oy, d));
+                    }
+                }
+            }
+        }
+        best
+    }
+}
+
+/// Decodes a PNG, given as base64 or a `data:` URL, to grayscale.
+fn decode_template(encoded: &str) -> Result<Gray, String> {
+    let data = encoded
+        .split_once("base64,")
+        .map_or(encoded, |(_, data)| data)
+        .trim();
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|_| "The image isn't valid base64")?;
+    let mut decoder = png::Decoder::new(bytes.as_slice());
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder
+        .read_info()
+        .map_err(|e| format!("Couldn't read the image: {e}"))?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut buf)
+        .map_err(|e| format!("Couldn't read the image: {e}"))?;
+    let channels = info.color_type.samples();
+    let px = buf[..info.line_size * info.height as usize]
+        .chunks_exact(info.line_size)
+        .flat_map(|line| line[..info.width as usize * channels].chunks_exact(channels))
+        .map(|p| match channels {
+            1 | 2 => p[0],
+            _ => Gray::luma(p[0], p[1], p[2]),
+        })
+        .collect();
+    Ok(Gray {
+        width: info.width as usize,
+        height: info.height as usize,
+        px,
+    })
+}
+
+/// A decoded template image, checked before the run starts.
+#[derive(Debug)]
+pub struct Template {
+    image: Gray,
+    /// Downscaled image and its factor, when it's big enough for it
+    coarse: Option<(Gray, usize)>,
+    /// Largest mean per-pixel difference (0-1) still counted as found
+    tolerance: f64,
+}
+
+impl Template {
+    pub fn from_settings(s: &AutoInputSettings) -> Result<Option<Self>, String> {
+        if s.location_mode != "image" {
+            return Ok(None);
+        }
+        if s.target_image.is_empty() {
+            return Err("No target image selected".into());
+        }
+        Self::decode(&s.target_image, s.image_tolerance).map(Some)
+    }
+
+    /// `tolerance` is the mean per-pixel difference, 0-1, still counted as
+    /// a match.
+    pub fn decode(encoded: &str, tolerance: f64) -> Result<Self, String> {
+        let image = decode_template(encoded)?;
+        if image.width == 0 || image.height == 0 {
+            return Err("The template image is empty".into());
+        }
+        let coarse = (image.width.min(image.height) / COARSE_FACTOR >= MIN_COARSE_SIDE)
+            .then(|| (image.downscale(COARSE_FACTOR), COARSE_FACTOR));
+        Ok(Self {
+            image,
+            coarse,
+            tolerance: tolerance.clamp(0.0, 1.0),
+        })
+    }
+}
+
+/// Locates a template on screen each time it's asked. Made on the thread
+/// that uses it, since the capture surfaces can't move between threads.
+pub struct Finder {
+    template: Template,
+    screen: Rect,
+    grabber: Option<Grabber>,
+}
+
+impl Finder {
+    pub fn new(template: Template) -> Self {
+        Self {
+            template,
+            screen: monitors::virtual_screen(),
+            grabber: None,
+        }
+    }
+
+    /// Center of the best match in global screen pixels, or None if nothing
+    /// on screen is close enough.
+    pub fn locate(&mut self) -> Option<(i32, i32)> {
+        let (at, difference) = self.closest()?;
+        self.accepts(difference).then_some(at)
+    }
+
+    /// Whether a `closest` difference is within the template's tolerance.
+    pub fn accepts(&self, difference: f64) -> bool {
+        difference <= self.template.tolerance
+    }
+
+    /// Center of the closest placement however far off it is, with its mean
+    /// per-pixel difference on the same 0-1 scale as the tolerance. None if
+    /// the screen can't be read.
+    pub fn closest(&mut self) -> Option<((i32, i32), f64)> {
+        let (width, height) = (self.screen.width, self.screen.height);
+        if self.grabber.is_none() {
+            self.grabber = Grabber::new(width, height);
+        }
+        let pixels = self.grabber.as_mut()?.grab(self.screen.x, self.screen.y)?;
+        let screen = Gray::from_bgra(width as usize, height as usize, pixels);
+        let tpl = &self.template.image;
+
+        let (xs, ys) = match &self.template.coarse {
+            Some((coarse, factor)) => {
+                let (cx, cy, _) =
+                    screen
+                        .downscale(*factor)
+                        .best(coarse, 0..usize::MAX, 0..usize::MAX)?;
+                // The coarse hit is only accurate to a block either way
+                let (x, y) = (cx * factor, cy * factor);
+                (
+                    x.saturating_sub(*factor)..x + factor + 1,
+                    y.saturating_sub(*factor)..y + factor + 1,
+                )
+            }
+            None => (0..usize::MAX, 0..usize::MAX),
+        };
+        let (x, y, difference) = screen.best(tpl, xs, ys)?;
+        let mean = difference as f64 / (tpl.width * tpl.height) as f64;
+        let center = (
+            self.screen.x + (x + tpl.width / 2) as i32,
+            self.screen.y + (y + tpl.height / 2) as i32,
+        );
+        Some((center, mean / 255.0))
+    }
+}
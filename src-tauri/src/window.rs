@@ -0,0 +1,436 @@
+use std::time::{Duration, Instant};
+
+use regex_lite::Regex;
+use serde::Serialize;
+
+use crate::monitors::Rect;
+use crate::AutoInputSettings;
+
+// ---------------------------------------------------------------------------
+// Windows
+//
+// The top-level windows a run can be pointed at. Only what the taskbar would
+// show is listed: visible, titled, unowned, not a tool window and not cloaked
+// (suspended store apps and windows on other virtual desktops are cloaked).
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowInfo {
+    /// Window handle; only valid until the window closes
+    pub hwnd: isize,
+    pub title: String,
+    pub class_name: String,
+    /// Executable file name, lowercased (e.g. "game.exe"). Empty for
+    /// protected processes that can't be opened
+    pub process_name: String,
+    pub pid: u32,
+    /// Visible bounds in global screen pixels, without the drop shadow
+    pub rect: Rect,
+}
+
+/// A child control (button, edit box, ...) inside a top-level window.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlInfo {
+    pub hwnd: isize,
+    /// The top-level window it belongs to
+    pub window: isize,
+    /// Dialog control ID, which unlike the handle is the same every time
+    /// the window is opened; 0 if it has none
+    pub id: i32,
+    pub class_name: String,
+    pub text: String,
+    /// Bounds relative to the top-left of the window's client area, so they
+    /// stay valid when the window moves
+    pub rect: Rect,
+}
+
+/// Top-level windows in z-order, topmost first.
+pub fn list() -> Vec<WindowInfo> {
+    imp::list()
+}
+
+/// Details of any window, titled or not, while it's open.
+pub fn info(hwnd: isize) -> Option<WindowInfo> {
+    imp::info(hwnd)
+}
+
+/// `target_window` setting: the window a run is meant for.
+#[derive(Debug, Clone)]
+pub enum TargetWindow {
+    /// A handle from `list_windows`
+    Handle(isize),
+    /// Case-insensitive pattern matched against window titles
+    Title(Regex),
+}
+
+impl TargetWindow {
+    pub fn from_settings(s: &AutoInputSettings) -> Result<Option<Self>, String> {
+        let target = s.target_window.trim();
+        if target.is_empty() {
+            return Ok(None);
+        }
+        if let Ok(hwnd) = target.parse::<isize>() {
+            if !imp::exists(hwnd) {
+                return Err("The target window has been closed".into());
+            }
+            return Ok(Some(Self::Handle(hwnd)));
+        }
+        Regex::new(&format!("(?i){target}"))
+            .map(|pattern| Some(Self::Title(pattern)))
+            .map_err(|e| format!("Invalid window title pattern: {e}"))
+    }
+
+    pub fn matches(&self, hwnd: isize, title: &str) -> bool {
+        match self {
+            Self::Handle(target) => *target == hwnd,
+            Self::Title(pattern) => pattern.is_match(title),
+        }
+    }
+
+    /// Handle of the window, if it's open; the topmost match for a title.
+    pub fn find(&self) -> Option<isize> {
+        match self {
+            Self::Handle(hwnd) => imp::exists(*hwnd).then_some(*hwnd),
+            Self::Title(pattern) => list()
+                .into_iter()
+                .find(|w| pattern.is_match(&w.title))
+                .map(|w| w.hwnd),
+        }
+    }
+}
+
+/// `auto_focus` setting, resolved once per run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoFocus {
+    Off,
+    /// Bring the target window forward once, when injecting begins
+    Start,
+    /// Also bring it back whenever it loses focus, instead of pausing
+    Always,
+}
+
+impl AutoFocus {
+    pub fn parse(mode: &str) -> Self {
+        match mode {
+            "start" => Self::Start,
+            "always" => Self::Always,
+            _ => Self::Off,
+        }
+    }
+}
+
+/// How often `FocusWatch` looks at the foreground window again
+const FOCUS_RECHECK: Duration = Duration::from_millis(100);
+
+/// Whether the target window has focus, looked up at most every
+/// `FOCUS_RECHECK` so fast runs aren't reading window titles per input.
+pub struct FocusWatch {
+    target: TargetWindow,
+    checked_at: Option<Instant>,
+    focused: bool,
+}
+
+impl FocusWatch {
+    pub fn new(target: TargetWindow) -> Self {
+        Self {
+            target,
+            checked_at: None,
+            focused: false,
+        }
+    }
+
+    pub fn focused(&mut self) -> bool {
+        if self.checked_at.is_none_or(|t| t.elapsed() >= FOCUS_RECHECK) {
+            self.checked_at = Some(Instant::now());
+            self.focused =
+                imp::foreground().is_some_and(|(hwnd, title)| self.target.matches(hwnd, &title));
+        }
+        self.focused
+    }
+
+    /// Brings the target window to the foreground. Returns whether it has
+    /// focus now.
+    pub fn refocus(&mut self) -> bool {
+        self.checked_at = Some(Instant::now());
+        self.focused = self.target.find().is_some_and(imp::bring_to_front);
+        self.focused
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use std::ffi::c_void;
+
+    use windows::core::{BOOL, PWSTR};
+    use windows::Win32::Foundation::{CloseHandle, HWND, LPARAM, POINT, RECT};
+    use windows::Win32::Graphics::Dwm::{
+        DwmGetWindowAttribute, DWMWA_CLOAKED, DWMWA_EXTENDED_FRAME_BOUNDS,
+    };
+    use windows::Win32::Graphics::Gdi::ClientToScreen;
+    use windows::Win32::System::Threading::{
+        AttachThreadInput, GetCurrentThreadId, OpenProcess, QueryFullProcessImageNameW,
+        PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        BringWindowToTop, EnumChildWindows, EnumWindows, GetAncestor, GetClassNameW, GetDlgCtrlID,
+        GetForegroundWindow, GetWindow, GetWindowLongW, GetWindowRect, GetWindowTextW,
+        GetWindowThreadProcessId, IsIconic, IsWindow, IsWindowVisible, SetForegroundWindow,
+        ShowWindow, WindowFromPoint, GA_ROOT, GWL_EXSTYLE, GW_OWNER, SW_RESTORE, WS_EX_TOOLWINDOW,
+    };
+
+    use super::{ControlInfo, Rect, WindowInfo};
+
+    pub fn process_name(pid: u32) -> Option<String> {
+        unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+            let mut buf = [0u16; 260];
+            let mut len = buf.len() as u32;
+            let ok = QueryFullProcessImageNameW(
+                process,
+                PROCESS_NAME_WIN32,
+                PWSTR(buf.as_mut_ptr()),
+                &mut len,
+            );
+            let _ = CloseHandle(process);
+            ok.ok()?;
+            let path = String::from_utf16_lossy(&buf[..len as usize]);
+            let name = path.rsplit('\\').next().unwrap_or(&path);
+            Some(name.to_lowercase())
+        }
+    }
+
+    unsafe fn listed(hwnd: HWND) -> bool {
+        if !IsWindowVisible(hwnd).as_bool() || GetWindow(hwnd, GW_OWNER).is_ok() {
+            return false;
+        }
+        if GetWindowLongW(hwnd, GWL_EXSTYLE) as u32 & WS_EX_TOOLWINDOW.0 != 0 {
+            return false;
+        }
+        let mut cloaked = 0u32;
+        let _ = DwmGetWindowAttribute(
+            hwnd,
+            DWMWA_CLOAKED,
+            &mut cloaked as *mut u32 as *mut c_void,
+            std::mem::size_of::<u32>() as u32,
+        );
+        cloaked == 0
+    }
+
+    unsafe fn bounds(hwnd: HWND) -> Rect {
+        let mut rect = RECT::default();
+        // Window rects include the invisible resize border on Windows 10+
+        if DwmGetWindowAttribute(
+            hwnd,
+            DWMWA_EXTENDED_FRAME_BOUNDS,
+            &mut rect as *mut RECT as *mut c_void,
+            std::mem::size_of::<RECT>() as u32,
+        )
+        .is_err()
+        {
+            let _ = GetWindowRect(hwnd, &mut rect);
+        }
+        Rect {
+            x: rect.left,
+            y: rect.top,
+            width: rect.right - rect.left,
+            height: rect.bottom - rect.top,
+        }
+    }
+
+    unsafe fn title(hwnd: HWND) -> String {
+        let mut title = [0u16; 512];
+        let len = GetWindowTextW(hwnd, &mut title) as usize;
+        String::from_utf16_lossy(&title[..len])
+    }
+
+    unsafe fn class_name(hwnd: HWND) -> String {
+        let mut class = [0u16; 256];
+        let len = GetClassNameW(hwnd, &mut class) as usize;
+        String::from_utf16_lossy(&class[..len])
+    }
+
+    unsafe fn describe(hwnd: HWND) -> WindowInfo {
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        WindowInfo {
+            hwnd: hwnd.0 as isize,
+            title: title(hwnd),
+            class_name: class_name(hwnd),
+            process_name: process_name(pid).unwrap_or_default(),
+            pid,
+            rect: bounds(hwnd),
+        }
+    }
+
+    unsafe extern "system" fn collect(hwnd: HWND, data: LPARAM) -> BOOL {
+        let windows = &mut *(data.0 as *mut Vec<WindowInfo>);
+        if listed(hwnd) {
+            let window = describe(hwnd);
+            if !window.title.is_empty() {
+                windows.push(window);
+            }
+        }
+        BOOL(1)
+    }
+
+    pub fn list() -> Vec<WindowInfo> {
+        let mut windows = Vec::new();
+        unsafe {
+            let _ = EnumWindows(
+                Some(collect),
+                LPARAM(&mut windows as *mut Vec<WindowInfo> as isize),
+            );
+        }
+        windows
+    }
+
+    unsafe fn describe_control(hwnd: HWND, window: HWND) -> ControlInfo {
+        let mut origin = POINT::default();
+        let _ = ClientToScreen(window, &mut origin);
+        let mut rect = RECT::default();
+        let _ = GetWindowRect(hwnd, &mut rect);
+        ControlInfo {
+            hwnd: hwnd.0 as isize,
+            window: window.0 as isize,
+            id: GetDlgCtrlID(hwnd),
+            class_name: class_name(hwnd),
+            text: title(hwnd),
+            rect: Rect {
+                x: rect.left - origin.x,
+                y: rect.top - origin.y,
+                width: rect.right - rect.left,
+                height: rect.bottom - rect.top,
+            },
+        }
+    }
+
+    struct Controls {
+        window: HWND,
+        found: Vec<ControlInfo>,
+    }
+
+    unsafe extern "system" fn collect_control(hwnd: HWND, data: LPARAM) -> BOOL {
+        let controls = &mut *(data.0 as *mut Controls);
+        if IsWindowVisible(hwnd).as_bool() {
+            let control = describe_control(hwnd, controls.window);
+            controls.found.push(control);
+        }
+        BOOL(1)
+    }
+
+    pub fn controls(window: isize) -> Vec<ControlInfo> {
+        let window = HWND(window as *mut c_void);
+        let mut controls = Controls {
+            window,
+            found: Vec::new(),
+        };
+        unsafe {
+            let _ = EnumChildWindows(
+                Some(window),
+                Some(collect_control),
+                LPARAM(&mut controls as *mut Controls as isize),
+            );
+        }
+        controls.found
+    }
+
+    pub fn control_at(x: i32, y: i32) -> Option<ControlInfo> {
+        unsafe {
+            let hwnd = WindowFromPoint(POINT { x, y });
+            let window = GetAncestor(hwnd, GA_ROOT);
+            (!hwnd.is_invalid() && !window.is_invalid() && hwnd != window)
+                .then(|| describe_control(hwnd, window))
+        }
+    }
+
+    pub fn exists(hwnd: isize) -> bool {
+        unsafe { IsWindow(Some(HWND(hwnd as *mut c_void))).as_bool() }
+    }
+
+    pub fn info(hwnd: isize) -> Option<WindowInfo> {
+        exists(hwnd).then(|| unsafe { describe(HWND(hwnd as *mut c_void)) })
+    }
+
+    /// Handle and title of the window with focus.
+    pub fn foreground() -> Option<(isize, String)> {
+        unsafe {
+            let hwnd = GetForegroundWindow();
+            (!hwnd.is_invalid()).then(|| (hwnd.0 as isize, title(hwnd)))
+        }
+    }
+
+    pub fn bring_to_front(hwnd: isize) -> bool {
+        unsafe {
+            let hwnd = HWND(hwnd as *mut c_void);
+            if IsIconic(hwnd).as_bool() {
+                let _ = ShowWindow(hwnd, SW_RESTORE);
+            }
+            // Windows only lets the thread owning the foreground hand it
+            // over, so borrow that thread's input state for the switch
+            let foreground = GetForegroundWindow();
+            let theirs = GetWindowThreadProcessId(foreground, None);
+            let ours = GetCurrentThreadId();
+            let attached =
+                theirs != 0 && theirs != ours && AttachThreadInput(ours, theirs, true).as_bool();
+            let _ = BringWindowToTop(hwnd);
+            let _ = SetForegroundWindow(hwnd);
+            if attached {
+                let _ = AttachThreadInput(ours, theirs, false);
+            }
+            GetForegroundWindow() == hwnd
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod imp {
+    pub fn list() -> Vec<super::WindowInfo> {
+        Vec::new()
+    }
+    pub fn controls(_window: isize) -> Vec<super::ControlInfo> {
+        Vec::new()
+    }
+    pub fn control_at(_x: i32, _y: i32) -> Option<super::ControlInfo> {
+        None
+    }
+    pub fn exists(_hwnd: isize) -> bool {
+        false
+    }
+    pub fn info(_hwnd: isize) -> Option<super::WindowInfo> {
+        None
+    }
+    pub fn foreground() -> Option<(isize, String)> {
+        None
+    }
+    pub fn bring_to_front(_hwnd: isize) -> bool {
+        false
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+/// Windows the user could pick as a run's target.
+#[tauri::command]
+pub fn list_windows() -> Vec<WindowInfo> {
+    list()
+}
+
+/// Visible child controls of a window from `list_windows`, in z-order.
+#[tauri::command]
+pub fn list_controls(hwnd: isize) -> Result<Vec<ControlInfo>, String> {
+    if !imp::exists(hwnd) {
+        return Err("That window has been closed".into());
+    }
+    Ok(imp::controls(hwnd))
+}
+
+/// The control under a global screen point, for picking one by clicking
+/// on it. None over a window's own frame or background.
+#[tauri::command]
+pub fn control_at(x: i32, y: i32) -> Option<ControlInfo> {
+    imp::control_at(x, y)
+}
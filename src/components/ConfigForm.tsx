@@ -23,6 +23,9 @@ const sectionClass = "border-2 border-border p-4 space-y-3 transition-all durati
 const numberInputClass =
   "text-center h-11 text-base tabular-nums [&::-webkit-inner-spin-button]:appearance-none [&::-webkit-outer-spin-button]:appearance-none";
 
+/** Matches the backend's default input-rate ceiling */
+const DEFAULT_MAX_CPS = 1000;
+
 const INTERVAL_FIELDS = [
   { label: "HR", key: "hours" as const },
   { label: "MIN", key: "minutes" as const },
@@ -123,6 +126,24 @@ export function ConfigForm({ config, onChange, disabled = false }: ConfigFormPro
               </div>
             ))}
           </div>
+          <div className="flex items-center gap-3">
+            <p className="text-xs text-muted-foreground uppercase tracking-widest font-medium shrink-0">Max CPS</p>
+            <Input
+              type="number"
+              min="1"
+              value={config.maxCps ?? DEFAULT_MAX_CPS}
+              disabled={disabled || config.unlimitedCps}
+              onChange={(e) => onChange({ maxCps: Math.max(1, parseInt(e.target.value) || 1) })}
+              className={numberInputClass}
+            />
+            <Button
+              variant={config.unlimitedCps ? "secondary" : "ghost"}
+              disabled={disabled}
+              onClick={() => onChange({ unlimitedCps: !config.unlimitedCps })}
+            >
+              No limit
+            </Button>
+          </div>
         </section>
       ) : null}
 
@@ -331,6 +352,14 @@ export function ConfigForm({ config, onChange, disabled = false }: ConfigFormPro
             >
               Count
             </Button>
+            <Button
+              variant={config.repeatMode === "duration" ? "secondary" : "ghost"}
+              className="flex-1"
+              disabled={disabled}
+              onClick={() => onChange({ repeatMode: "duration" })}
+            >
+              Duration
+            </Button>
           </div>
           {config.repeatMode === "count" ? (
             <Input
@@ -341,6 +370,20 @@ export function ConfigForm({ config, onChange, disabled = false }: ConfigFormPro
               onChange={(e) => onChange({ repeatCount: Math.max(1, parseInt(e.target.value) || 1) })}
               className={`w-24 ${numberInputClass}`}
             />
+          ) : config.repeatMode === "duration" ? (
+            <div className="flex items-center gap-1.5">
+              <Input
+                type="number"
+                min="1"
+                value={config.repeatDurationSecs}
+                disabled={disabled}
+                onChange={(e) =>
+                  onChange({ repeatDurationSecs: Math.max(1, parseInt(e.target.value) || 1) })
+                }
+                className={`w-24 ${numberInputClass}`}
+              />
+              <span className={sublabelClass}>sec</span>
+            </div>
           ) : null}
         </div>
       </section>
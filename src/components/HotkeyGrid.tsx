@@ -1,7 +1,8 @@
 import { KeyCapture } from "@/components/KeyCapture";
 import type { HotkeySet } from "@/types/settings";
 
-const OPERATIONS = ["start", "stop", "toggle"] as const;
+/** Hold runs the config only while its key or button is held down. */
+const OPERATIONS = ["start", "stop", "toggle", "hold"] as const;
 
 interface HotkeyGridProps {
   value: HotkeySet;
@@ -14,14 +15,15 @@ export function HotkeyGrid({ value, onChange, label, disabled }: HotkeyGridProps
   return (
     <div className="space-y-3">
       {label ? <p className="text-sm uppercase tracking-widest text-muted-foreground font-bold">{label}</p> : null}
-      <div className="grid grid-cols-3 gap-3">
+      <div className="grid grid-cols-4 gap-3">
         {OPERATIONS.map((op) => (
           <div key={op} className="space-y-1">
             <p className="text-xs text-muted-foreground text-center uppercase tracking-widest font-medium">{op}</p>
             <KeyCapture
-              value={value[op]}
+              value={value[op] ?? null}
               disabled={disabled}
               clearable
+              allowMouse
               onChange={(v) => onChange({ ...value, [op]: v })}
             />
           </div>
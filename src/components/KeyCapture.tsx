@@ -21,12 +21,24 @@ function normalizeKey(e: React.KeyboardEvent): string {
   }
 }
 
+/**
+ * Mouse buttons that can be bound, by `MouseEvent.button`. Left and right
+ * stay free for focusing the field.
+ */
+const MOUSE_BUTTONS: Record<number, string> = {
+  1: "Mouse3",
+  3: "Mouse4",
+  4: "Mouse5",
+};
+
 interface KeyCaptureProps {
   value: string | null;
   onChange: (key: string | null) => void;
   disabled?: boolean;
   /** Allow the user to clear the binding (Escape while listening, or click x). */
   clearable?: boolean;
+  /** Also accept the middle and side mouse buttons. */
+  allowMouse?: boolean;
   className?: string;
   placeholder?: string;
 }
@@ -36,6 +48,7 @@ export function KeyCapture({
   onChange,
   disabled,
   clearable,
+  allowMouse,
   className,
   placeholder = "Press a key",
 }: KeyCaptureProps) {
@@ -61,6 +74,15 @@ export function KeyCapture({
         )}
         onFocus={() => setListening(true)}
         onBlur={() => setListening(false)}
+        onMouseDown={(e) => {
+          const name = MOUSE_BUTTONS[e.button];
+          if (!allowMouse || !listening || !name) return;
+          // Side buttons would otherwise navigate the webview back/forward
+          e.preventDefault();
+          onChange(name);
+          setListening(false);
+          buttonRef.current?.blur();
+        }}
         onKeyDown={(e) => {
           if (!listening) return;
 
@@ -9,10 +9,13 @@ interface ActionPayload {
   minutes: number;
   seconds: number;
   milliseconds: number;
+  maxCps?: number;
+  unlimitedCps?: boolean;
   mouseButton: string;
   clickType: string;
   repeatMode: string;
   repeatCount: number;
+  repeatDurationSecs: number;
   locationMode: string;
   fixedX: number;
   fixedY: number;
@@ -25,16 +28,19 @@ interface ActionPayload {
   keyMode: string;
 }
 
-function buildPayload(config: InputConfig): ActionPayload {
+export function buildPayload(config: InputConfig): ActionPayload {
   return {
     hours: config.hours,
     minutes: config.minutes,
     seconds: config.seconds,
     milliseconds: config.milliseconds,
+    maxCps: config.maxCps,
+    unlimitedCps: config.unlimitedCps,
     mouseButton: config.mouseButton,
     clickType: config.clickType,
     repeatMode: config.repeatMode,
     repeatCount: config.repeatCount,
+    repeatDurationSecs: config.repeatDurationSecs,
     locationMode: config.locationMode,
     fixedX: config.fixedX,
     fixedY: config.fixedY,
@@ -48,6 +54,13 @@ function buildPayload(config: InputConfig): ActionPayload {
   };
 }
 
+interface HotkeyTriggered {
+  id: string;
+  action: "start" | "stop" | "toggle" | "hold";
+  started: boolean;
+  error: string | null;
+}
+
 export interface ActionControlState {
   runningId: string | null;
   error: string | null;
@@ -79,6 +92,17 @@ export function useActionControlProvider(): ActionControlState {
     };
   }, []);
 
+  // Global hotkeys run in the backend; follow what they started or stopped
+  useEffect(() => {
+    const unlisten = listen<HotkeyTriggered>("hotkey-triggered", ({ payload }) => {
+      setRunningId(payload.started ? payload.id : null);
+      setError(payload.error);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const startConfig = useCallback(async (config: InputConfig) => {
     setError(null);
     try {
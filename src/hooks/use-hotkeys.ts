@@ -1,52 +1,43 @@
 import { useEffect } from "react";
-import { register, unregister } from "@tauri-apps/plugin-global-shortcut";
+import { invoke } from "@tauri-apps/api/core";
 import type { InputConfig } from "@/types/settings";
+import { buildPayload } from "./use-action-control";
 import { useLatest } from "./use-latest";
 
-interface HotkeyActions {
-  startConfig: (config: InputConfig) => void;
-  stopCurrent: () => void;
-  toggleConfig: (config: InputConfig) => void;
+interface HotkeyConflict {
+  hotkey: string;
+  error: string;
 }
 
 /**
  * Registers global hotkeys for all configs in the list.
- * Each config has its own start/stop/toggle hotkeys.
+ * Each config has its own start/stop/toggle hotkeys. The backend owns the
+ * registrations so they keep working while the window is hidden; presses are
+ * reported back through the `hotkey-triggered` event.
+ * Nothing is registered when the app was launched with `--safe-mode`.
  */
-export function useHotkeys(configs: InputConfig[], actions: HotkeyActions) {
-  const actionsRef = useLatest(actions);
+export function useHotkeys(configs: InputConfig[]) {
   const configsRef = useLatest(configs);
 
   // Build a stable dependency string from all hotkey bindings
   const hotkeyFingerprint = configs
-    .map((c) => `${c.id}:${c.hotkeys.start}:${c.hotkeys.stop}:${c.hotkeys.toggle}`)
+    .map((c) => `${c.id}:${c.hotkeys.start}:${c.hotkeys.stop}:${c.hotkeys.toggle}:${c.hotkeys.hold}`)
     .join("|");
 
   useEffect(() => {
-    let disposed = false;
-    const keys: string[] = [];
-
-    const safeRegister = async (key: string | null, cb: () => void) => {
-      if (!key) return;
-      // Avoid registering the same key twice
-      if (keys.includes(key)) return;
-      keys.push(key);
-      try {
-        await register(key, () => {
-          if (!disposed) cb();
-        });
-      } catch {
-        // Key might already be registered by OS or conflicting
-      }
-    };
-
     (async () => {
       try {
-        for (const config of configsRef.current) {
-          const cfg = config; // capture for closure
-          await safeRegister(cfg.hotkeys.start, () => actionsRef.current.startConfig(cfg));
-          await safeRegister(cfg.hotkeys.stop, () => actionsRef.current.stopCurrent());
-          await safeRegister(cfg.hotkeys.toggle, () => actionsRef.current.toggleConfig(cfg));
+        if (await invoke<boolean>("is_safe_mode")) return;
+        const bindings = configsRef.current.map((config) => ({
+          id: config.id,
+          label: null,
+          settings: buildPayload(config),
+          hotkeys: config.hotkeys,
+        }));
+        const conflicts = await invoke<HotkeyConflict[]>("register_hotkeys", { bindings });
+        for (const { hotkey, error } of conflicts) {
+          // Usually held by another program
+          console.warn(`Couldn't register hotkey ${hotkey}:`, error);
         }
       } catch (err) {
         console.warn("Failed to register hotkeys:", err);
@@ -54,9 +45,8 @@ export function useHotkeys(configs: InputConfig[], actions: HotkeyActions) {
     })();
 
     return () => {
-      disposed = true;
-      keys.forEach((k) => unregister(k).catch(() => {}));
+      invoke("unregister_hotkeys").catch(() => {});
     };
     // eslint-disable-next-line react-hooks/exhaustive-deps
-  }, [hotkeyFingerprint, actionsRef, configsRef]);
+  }, [hotkeyFingerprint, configsRef]);
 }
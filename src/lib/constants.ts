@@ -9,6 +9,7 @@ export const DEFAULT_CONFIG: Omit<InputConfig, "id"> = {
   milliseconds: 20,
   repeatMode: "infinite",
   repeatCount: 10,
+  repeatDurationSecs: 60,
   mouseButton: "left",
   mouseMode: "click",
   clickType: "single",
@@ -20,7 +21,7 @@ export const DEFAULT_CONFIG: Omit<InputConfig, "id"> = {
   fixedY: 0,
   holdKey: "e",
   keyMode: "hold",
-  hotkeys: { start: null, stop: null, toggle: null },
+  hotkeys: { start: null, stop: null, toggle: null, hold: null },
 };
 
 export const DEFAULT_APP_STATE: AppState = {
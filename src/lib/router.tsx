@@ -6,8 +6,10 @@ import {
   Outlet,
   Link,
 } from "@tanstack/react-router";
-import { useQuery } from "@tanstack/react-query";
-import { appStateQueryOptions } from "@/lib/queries";
+import { useEffect } from "react";
+import { useQuery, useQueryClient } from "@tanstack/react-query";
+import { listen } from "@tauri-apps/api/event";
+import { appStateQueryKey, appStateQueryOptions } from "@/lib/queries";
 import { useActionControlProvider, ActionControlContext } from "@/hooks/use-action-control";
 import { useHotkeys } from "@/hooks/use-hotkeys";
 import { HomePage } from "@/routes/home";
@@ -19,11 +21,22 @@ import { EditPage } from "@/routes/edit";
 // ---------------------------------------------------------------------------
 
 function RootLayout() {
+  const qc = useQueryClient();
   const { data: state } = useQuery(appStateQueryOptions);
   const actions = useActionControlProvider();
 
+  // Pick up edits made to the settings file outside the app
+  useEffect(() => {
+    const unlisten = listen("config-reloaded", () => {
+      qc.invalidateQueries({ queryKey: appStateQueryKey });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [qc]);
+
   // Register hotkeys for all configs
-  useHotkeys(state?.configs ?? [], actions);
+  useHotkeys(state?.configs ?? []);
 
   // ---- Full mode render ----
   return (
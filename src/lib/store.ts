@@ -1,12 +1,25 @@
-import { load } from "@tauri-apps/plugin-store";
+import { invoke } from "@tauri-apps/api/core";
 import { nanoid } from "nanoid";
 import type { InputConfig, AppState } from "@/types/settings";
 import { DEFAULT_APP_STATE, DEFAULT_CONFIG } from "@/lib/constants";
 
-const STORE_FILE = "settings.json";
 const STATE_KEY = "appState";
 const LEGACY_KEY = "settings";
 
+type StoreData = Record<string, unknown>;
+
+/**
+ * The backend owns the store file so it can honor a custom data directory
+ * and its lock. Writes fail while another machine holds that directory.
+ */
+async function readStore(): Promise<StoreData> {
+  return invoke<StoreData>("read_store");
+}
+
+async function writeStore(data: StoreData): Promise<void> {
+  await invoke("write_store", { data });
+}
+
 /**
  * Migrate legacy single-settings format to the new multi-config format.
  */
@@ -34,6 +47,7 @@ function migrateLegacy(legacy: Record<string, unknown>): AppState {
     milliseconds: l.milliseconds,
     repeatMode: l.repeatMode as InputConfig["repeatMode"],
     repeatCount: l.repeatCount,
+    repeatDurationSecs: 60,
     mouseButton: l.mouseButton as InputConfig["mouseButton"],
     mouseMode: l.mouseMode as InputConfig["mouseMode"],
     clickType: l.clickType as InputConfig["clickType"],
@@ -60,6 +74,7 @@ function migrateLegacy(legacy: Record<string, unknown>): AppState {
     milliseconds: l.milliseconds,
     repeatMode: l.repeatMode as InputConfig["repeatMode"],
     repeatCount: l.repeatCount,
+    repeatDurationSecs: 60,
     mouseButton: l.mouseButton as InputConfig["mouseButton"],
     mouseMode: l.mouseMode as InputConfig["mouseMode"],
     clickType: l.clickType as InputConfig["clickType"],
@@ -80,27 +95,27 @@ function migrateLegacy(legacy: Record<string, unknown>): AppState {
 
 export async function loadAppState(): Promise<AppState> {
   try {
-    const store = await load(STORE_FILE, { autoSave: false, defaults: {} });
+    const data = await readStore();
 
     // Try new format first
-    const state = await store.get<AppState>(STATE_KEY);
+    const state = data[STATE_KEY] as AppState | undefined;
     if (state && Array.isArray(state.configs)) {
       // Back-fill defaults for any new fields added after initial release
       for (const cfg of state.configs) {
         if (cfg.dragDirectionX == null) cfg.dragDirectionX = 0;
         if (cfg.dragDirectionY == null) cfg.dragDirectionY = -1;
+        if (cfg.repeatDurationSecs == null) cfg.repeatDurationSecs = 60;
       }
       return { ...DEFAULT_APP_STATE, ...state };
     }
 
     // Fall back to legacy migration
-    const legacy = await store.get<Record<string, unknown>>(LEGACY_KEY);
+    const legacy = data[LEGACY_KEY] as Record<string, unknown> | undefined;
     if (legacy) {
       const migrated = migrateLegacy(legacy);
       // Save migrated state and clean up
-      await store.set(STATE_KEY, migrated);
-      await store.delete(LEGACY_KEY);
-      await store.save();
+      const { [LEGACY_KEY]: _, ...rest } = data;
+      await writeStore({ ...rest, [STATE_KEY]: migrated });
       return migrated;
     }
 
@@ -113,9 +128,8 @@ export async function loadAppState(): Promise<AppState> {
 
 export async function saveAppState(state: AppState): Promise<void> {
   try {
-    const store = await load(STORE_FILE, { autoSave: false, defaults: {} });
-    await store.set(STATE_KEY, state);
-    await store.save();
+    const data = await readStore();
+    await writeStore({ ...data, [STATE_KEY]: state });
   } catch (err) {
     console.warn("Failed to save app state:", err);
   }
@@ -181,6 +181,7 @@ export function HomePage() {
                     <KeyCapture
                       value={config.hotkeys.toggle}
                       clearable
+                      allowMouse
                       onChange={(key) =>
                         updateConfig(qc, config.id, {
                           hotkeys: { ...config.hotkeys, toggle: key },
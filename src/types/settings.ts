@@ -2,7 +2,7 @@ export type ActionType = "click" | "hold-key";
 export type MouseButton = "left" | "right" | "middle";
 export type ClickType = "single" | "double";
 export type MouseMode = "click" | "hold";
-export type RepeatMode = "infinite" | "count";
+export type RepeatMode = "infinite" | "count" | "duration";
 export type LocationMode = "current" | "fixed";
 export type KeyMode = "hold" | "repeat";
 
@@ -10,6 +10,8 @@ export interface HotkeySet {
   start: string | null;
   stop: string | null;
   toggle: string | null;
+  /** Runs only while held */
+  hold?: string | null;
 }
 
 /**
@@ -19,6 +21,10 @@ export interface HotkeySet {
 export interface InputConfig {
   id: string;
   name: string;
+  /** Free-form labels for search and the tray "Run" menu */
+  tags?: string[];
+  /** Set on configs from the read-only shared directory */
+  shared?: boolean;
   actionType: ActionType;
 
   // Interval (for click / key-repeat modes)
@@ -26,10 +32,15 @@ export interface InputConfig {
   minutes: number;
   seconds: number;
   milliseconds: number;
+  /** Ceiling on inputs per second; the backend defaults to 1000 */
+  maxCps?: number;
+  /** Lifts the ceiling entirely */
+  unlimitedCps?: boolean;
 
   // Repeat
   repeatMode: RepeatMode;
   repeatCount: number;
+  repeatDurationSecs: number;
 
   // Mouse settings
   mouseButton: MouseButton;
@@ -48,6 +59,14 @@ export interface InputConfig {
 
   // Per-config hotkeys
   hotkeys: HotkeySet;
+
+  // Per-app switching: becomes the active profile when a matching window
+  // comes to the foreground
+  /** Executable name, e.g. "game.exe" */
+  switchApp?: string;
+  /** Case-insensitive regular expression matched against window titles */
+  switchTitle?: string;
+  armHotkeysOnSwitch?: boolean;
 }
 
 /**